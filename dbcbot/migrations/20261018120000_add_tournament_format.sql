@@ -0,0 +1,5 @@
+-- Add migration script here
+CREATE TYPE tournament_format AS ENUM ('single_elimination', 'double_elimination');
+
+ALTER TABLE tournaments
+ADD COLUMN format tournament_format NOT NULL DEFAULT 'single_elimination';
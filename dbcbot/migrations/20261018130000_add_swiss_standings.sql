@@ -0,0 +1,13 @@
+-- Add migration script here
+ALTER TYPE tournament_format ADD VALUE IF NOT EXISTS 'swiss';
+
+CREATE TABLE IF NOT EXISTS standings (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    wins INT NOT NULL DEFAULT 0,
+    losses INT NOT NULL DEFAULT 0,
+    byes INT NOT NULL DEFAULT 0,
+    buchholz INT NOT NULL DEFAULT 0,
+    opponent_win_percentage REAL NOT NULL DEFAULT 0,
+    PRIMARY KEY (tournament_id, discord_id)
+);
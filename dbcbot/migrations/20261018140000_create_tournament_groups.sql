@@ -0,0 +1,9 @@
+-- Add migration script here
+ALTER TYPE tournament_format ADD VALUE IF NOT EXISTS 'round_robin';
+
+CREATE TABLE IF NOT EXISTS tournament_groups (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    group_number INT NOT NULL,
+    PRIMARY KEY (tournament_id, discord_id)
+);
@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE TYPE seeding_method AS ENUM ('trophies', 'manual', 'random');
+
+ALTER TABLE tournaments
+ADD COLUMN seeding_method seeding_method;
+
+ALTER TABLE tournament_players
+ADD COLUMN seed INT;
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE tournaments
+ADD COLUMN draw_seed BIGINT;
@@ -0,0 +1,24 @@
+-- Add migration script here
+ALTER TABLE tournaments
+ADD COLUMN team_size INT NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS teams (
+    team_id SERIAL PRIMARY KEY,
+    guild_id VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    captain_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Members that have not accepted their invite yet are pending
+CREATE TABLE IF NOT EXISTS team_members (
+    team_id INT NOT NULL REFERENCES teams(team_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    accepted BOOLEAN NOT NULL DEFAULT false,
+    PRIMARY KEY (team_id, discord_id)
+);
+
+CREATE TABLE IF NOT EXISTS tournament_teams (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    team_id INT NOT NULL REFERENCES teams(team_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    PRIMARY KEY (tournament_id, team_id)
+);
@@ -0,0 +1,28 @@
+-- Add migration script here
+ALTER TYPE tournament_format ADD VALUE IF NOT EXISTS 'showdown';
+
+-- Points awarded for each placement, starting from first place
+ALTER TABLE tournaments
+ADD COLUMN placement_points INT[] NOT NULL DEFAULT '{10,8,7,6,5,4,3,2,1,0}',
+ADD COLUMN lobby_games INT NOT NULL DEFAULT 3;
+
+CREATE TABLE IF NOT EXISTS lobbies (
+    lobby_id VARCHAR(255) PRIMARY KEY,
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    round INT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS lobby_players (
+    lobby_id VARCHAR(255) NOT NULL REFERENCES lobbies(lobby_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    PRIMARY KEY (lobby_id, discord_id)
+);
+
+CREATE TABLE IF NOT EXISTS lobby_results (
+    lobby_id VARCHAR(255) NOT NULL REFERENCES lobbies(lobby_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    game_number INT NOT NULL,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    placement INT NOT NULL,
+    points INT NOT NULL,
+    PRIMARY KEY (lobby_id, game_number, discord_id)
+);
@@ -0,0 +1,8 @@
+-- Add migration script here
+-- Round 0 stands for the final round, whichever round that ends up being
+CREATE TABLE IF NOT EXISTS round_wins_required (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    round INT NOT NULL,
+    wins_required INT NOT NULL,
+    PRIMARY KEY (tournament_id, round)
+);
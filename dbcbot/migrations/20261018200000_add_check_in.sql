@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE tournaments
+ADD COLUMN check_in_end BIGINT;
+
+ALTER TABLE tournament_players
+ADD COLUMN checked_in BOOLEAN NOT NULL DEFAULT false;
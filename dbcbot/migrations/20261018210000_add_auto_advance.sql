@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE tournaments
+ADD COLUMN auto_advance BOOLEAN NOT NULL DEFAULT true;
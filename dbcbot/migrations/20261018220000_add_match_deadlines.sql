@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE tournaments
+ADD COLUMN round_duration INT;
+
+ALTER TABLE matches
+ADD COLUMN escalated BOOLEAN NOT NULL DEFAULT false;
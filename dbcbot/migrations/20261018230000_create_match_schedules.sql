@@ -0,0 +1,9 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS match_schedules (
+    match_id VARCHAR(255) PRIMARY KEY REFERENCES matches(match_id) ON DELETE CASCADE,
+    proposed_time BIGINT NOT NULL,
+    time_of_proposal BIGINT NOT NULL,
+    proposer VARCHAR(255) NOT NULL,
+    accepted BOOLEAN NOT NULL DEFAULT false,
+    reminded BOOLEAN NOT NULL DEFAULT false
+);
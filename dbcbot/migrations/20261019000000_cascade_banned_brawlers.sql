@@ -0,0 +1,6 @@
+-- Remove the ban list of a tournament together with the tournament
+
+ALTER TABLE banned_brawlers
+    DROP CONSTRAINT banned_brawlers_tournament_id_fkey,
+    ADD CONSTRAINT banned_brawlers_tournament_id_fkey
+        FOREIGN KEY (tournament_id) REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE;
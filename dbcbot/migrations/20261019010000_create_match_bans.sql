@@ -0,0 +1,9 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS match_bans (
+    match_id VARCHAR(255) NOT NULL REFERENCES matches(match_id) ON DELETE CASCADE,
+    turn INT NOT NULL,
+    discord_id VARCHAR(255) NOT NULL,
+    brawler VARCHAR(255) NOT NULL,
+    PRIMARY KEY (match_id, turn),
+    UNIQUE (match_id, brawler)
+);
@@ -0,0 +1,17 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS map_pools (
+    guild_id VARCHAR(255) NOT NULL,
+    mode mode NOT NULL,
+    map_id INT NOT NULL,
+    map_name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (guild_id, mode, map_id)
+);
+
+CREATE TABLE IF NOT EXISTS map_rotations (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    round INT NOT NULL,
+    game INT NOT NULL DEFAULT 0,
+    map_id INT NOT NULL,
+    map_name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (tournament_id, round, game)
+);
@@ -0,0 +1,11 @@
+-- Add migration script here
+ALTER TYPE tournament_status ADD VALUE IF NOT EXISTS 'registration_closed' AFTER 'pending';
+
+ALTER TABLE tournaments ADD COLUMN IF NOT EXISTS max_participants INT;
+
+CREATE TABLE IF NOT EXISTS tournament_waitlist (
+    tournament_id INT NOT NULL REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    discord_id VARCHAR(255) NOT NULL REFERENCES users(discord_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    joined_at BIGINT NOT NULL,
+    PRIMARY KEY (tournament_id, discord_id)
+);
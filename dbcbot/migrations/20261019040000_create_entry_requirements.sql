@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS entry_requirements (
+    tournament_id INT PRIMARY KEY REFERENCES tournaments(tournament_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    min_trophies INT,
+    max_trophies INT,
+    min_highest_trophies INT,
+    min_three_vs_three_victories INT,
+    club_tag VARCHAR(255),
+    min_brawlers INT
+);
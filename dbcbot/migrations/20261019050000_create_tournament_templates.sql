@@ -0,0 +1,44 @@
+-- Add migration script here
+-- A template keeps the settings of a tournament so that new tournaments can be created with them
+CREATE TABLE IF NOT EXISTS tournament_templates (
+    template_id SERIAL PRIMARY KEY,
+    guild_id VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    mode mode NOT NULL,
+    format tournament_format NOT NULL,
+    map INT NOT NULL,
+    wins_required INT NOT NULL,
+    tournament_role_id VARCHAR(255) NOT NULL,
+    announcement_channel_id VARCHAR(255) NOT NULL,
+    notification_channel_id VARCHAR(255) NOT NULL,
+    team_size INT NOT NULL,
+    placement_points INT[] NOT NULL,
+    lobby_games INT NOT NULL,
+    auto_advance BOOLEAN NOT NULL,
+    round_duration INT,
+    max_participants INT,
+    banned_brawlers VARCHAR(255)[] NOT NULL DEFAULT '{}',
+    min_trophies INT,
+    max_trophies INT,
+    min_highest_trophies INT,
+    min_three_vs_three_victories INT,
+    club_tag VARCHAR(255),
+    min_brawlers INT,
+    UNIQUE (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS template_map_rotations (
+    template_id INT NOT NULL REFERENCES tournament_templates(template_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    round INT NOT NULL,
+    game INT NOT NULL DEFAULT 0,
+    map_id INT NOT NULL,
+    map_name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (template_id, round, game)
+);
+
+CREATE TABLE IF NOT EXISTS template_round_wins_required (
+    template_id INT NOT NULL REFERENCES tournament_templates(template_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    round INT NOT NULL,
+    wins_required INT NOT NULL,
+    PRIMARY KEY (template_id, round)
+);
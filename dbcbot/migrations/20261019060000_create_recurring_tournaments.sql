@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Weekday is counted from Monday (0) to Sunday (6). A NULL weekday repeats every day
+CREATE TABLE IF NOT EXISTS recurring_tournaments (
+    recurrence_id SERIAL PRIMARY KEY,
+    guild_id VARCHAR(255) NOT NULL,
+    template_id INT NOT NULL REFERENCES tournament_templates(template_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    weekday INT,
+    minute_of_day INT NOT NULL,
+    registration_hours INT NOT NULL,
+    auto_start BOOLEAN NOT NULL,
+    next_start BIGINT NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS match_overrides (
+    override_id SERIAL PRIMARY KEY,
+    match_id VARCHAR(255) NOT NULL REFERENCES matches(match_id) ON DELETE CASCADE,
+    previous_winner VARCHAR(255),
+    previous_score VARCHAR(255) NOT NULL,
+    winner VARCHAR(255) NOT NULL,
+    score VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    marshal_id VARCHAR(255) NOT NULL,
+    created_at BIGINT NOT NULL
+);
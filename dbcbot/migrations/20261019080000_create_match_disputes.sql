@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TYPE dispute_status AS ENUM ('open', 'accepted', 'rejected');
+
+CREATE TABLE IF NOT EXISTS match_disputes (
+    dispute_id SERIAL PRIMARY KEY,
+    match_id VARCHAR(255) NOT NULL REFERENCES matches(match_id) ON DELETE CASCADE,
+    discord_id VARCHAR(255) NOT NULL,
+    statement TEXT NOT NULL,
+    evidence TEXT[] NOT NULL DEFAULT '{}',
+    thread_id VARCHAR(255),
+    status dispute_status NOT NULL DEFAULT 'open',
+    resolved_by VARCHAR(255),
+    resolution TEXT,
+    created_at BIGINT NOT NULL,
+    resolved_at BIGINT
+);
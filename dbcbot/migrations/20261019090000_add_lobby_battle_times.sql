@@ -0,0 +1,7 @@
+-- Add migration script here
+-- Games are told apart by their battle time, as battle logs only keep the most recent battles
+ALTER TABLE lobbies
+ADD COLUMN start BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE lobby_results
+ADD COLUMN battle_time BIGINT NOT NULL DEFAULT 0;
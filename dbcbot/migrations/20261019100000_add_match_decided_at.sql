@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Rounds wait a while after their last result so that it can still be disputed before they advance
+ALTER TABLE matches
+ADD COLUMN decided_at BIGINT DEFAULT NULL;
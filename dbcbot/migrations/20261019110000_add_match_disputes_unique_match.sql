@@ -0,0 +1,3 @@
+-- Add migration script here
+-- The result of a match can only be disputed once
+CREATE UNIQUE INDEX IF NOT EXISTS match_disputes_match_id_key ON match_disputes (match_id);
@@ -1,8 +1,12 @@
 use crate::api::brawlify::GameMode;
-use crate::database::models::{BrawlMap, Mode, Tournament};
+use crate::database::models::{
+    BrawlMap, EntryRequirements, Mode, Requirement, SeedingMethod, Standing, Tournament,
+    RecurringTournament, TournamentDraw, TournamentFormat, TournamentSettings, TournamentTemplate,
+};
 use crate::database::{MatchDatabase, TournamentDatabase};
 use crate::log::Log;
 use crate::utils::discord::{modal, select_channel, select_options, select_role, splash};
+use crate::utils::time::{next_occurrence, parse_future_time, parse_recurrence};
 use crate::utils::shorthand::BotContextExt;
 use crate::{
     commands::checks::{is_config_set, is_manager},
@@ -10,16 +14,27 @@ use crate::{
     log, BotContext, BotData, BotError,
 };
 use anyhow::anyhow;
+use futures::StreamExt;
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use models::{Match, MatchPlayer, Player, PlayerType, TournamentStatus};
-use poise::serenity_prelude::{Channel, Role};
+use models::{Lobby, Match, MatchPlayer, Player, TournamentStatus};
+use poise::serenity_prelude::{Channel, ChannelId, Role};
 use poise::Modal;
 use poise::{
-    serenity_prelude::{self as serenity, Colour, CreateActionRow, CreateButton, CreateEmbed},
+    serenity_prelude::{
+        self as serenity, Colour, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed,
+        CreateMessage,
+    },
     CreateReply, ReplyHandle,
 };
 use tracing::{error, info, instrument};
 
+use super::marshal_commands::{
+    disputed_matches, generate_lobbies, generate_round_robin_round, generate_swiss_round,
+    group_stage_rounds, showdown_rounds, DISPUTE_WINDOW,
+};
+use super::user_commands::promote_from_waitlist;
 use super::CommandsContainer;
 
 /// CommandsContainer for the Manager commands.
@@ -34,6 +49,19 @@ impl CommandsContainer for ManagerCommands {
             set_config_slash(),
             create_tournament_slash(),
             start_tournament_slash(),
+            close_group_stage_slash(),
+            set_showdown_scoring_slash(),
+            open_check_in_slash(),
+            set_auto_advance_slash(),
+            set_round_deadline_slash(),
+            add_to_map_pool_slash(),
+            remove_from_map_pool_slash(),
+            set_round_map_slash(),
+            set_max_participants_slash(),
+            set_registration_slash(),
+            set_entry_requirement_slash(),
+            add_recurring_tournament_slash(),
+            remove_recurring_tournament_slash(),
             manager_menu(),
         ]
     }
@@ -72,6 +100,8 @@ async fn set_config_slash(
 /// Create a new tournament.
 ///
 #[poise::command(slash_command, prefix_command, guild_only, check = "is_manager")]
+// Every argument is an option of the slash command
+#[allow(clippy::too_many_arguments)]
 #[instrument]
 async fn create_tournament_slash(
     ctx: BotContext<'_>,
@@ -83,8 +113,13 @@ async fn create_tournament_slash(
     #[description = "Number of wins required to win a match. Default: 3"] wins_required: Option<
         i32,
     >,
+    #[description = "Format of the tournament. Default: Single Elimination"] format: Option<
+        TournamentFormat,
+    >,
+    #[description = "Number of players per team, up to 3. Default: 1"] team_size: Option<i32>,
+    #[description = "Start the tournament by itself at this UTC time, e.g. 2026-10-20 18:00"]
+    start_time: Option<String>,
 ) -> Result<(), BotError> {
-    let wins_required = wins_required.unwrap_or(3).max(1);
     let msg = ctx
         .send(
             CreateReply::default()
@@ -92,17 +127,36 @@ async fn create_tournament_slash(
                 .ephemeral(true),
         )
         .await?;
-    create_tournament(
-        ctx,
-        &msg,
+    let start_time = match start_time
+        .map(|input| parse_future_time(&input, ctx.now().timestamp()))
+        .transpose()
+    {
+        Ok(start_time) => start_time,
+        Err(e) => {
+            ctx.prompt(
+                &msg,
+                CreateEmbed::default()
+                    .title("Invalid start time")
+                    .description(e.to_string())
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let settings = TournamentSettings {
         name,
         mode,
-        role,
-        announcement,
-        notification,
-        wins_required,
-    )
-    .await
+        format: format.unwrap_or_default(),
+        wins_required: wins_required.unwrap_or(3).max(1),
+        team_size: team_size.unwrap_or(1).clamp(1, 3),
+        start_time,
+        tournament_role_id: role.id.to_string(),
+        announcement_channel_id: announcement.id().to_string(),
+        notification_channel_id: notification.id().to_string(),
+    };
+    create_tournament(ctx, &msg, settings).await
 }
 
 /// Start a tournament.
@@ -113,11 +167,25 @@ async fn create_tournament_slash(
     check = "is_manager",
     check = "is_config_set"
 )]
+// Every argument is an option of the slash command
+#[allow(clippy::too_many_arguments)]
 #[instrument]
 async fn start_tournament_slash(
     ctx: BotContext<'_>,
     tournament_id: i32,
     win_required: Option<i32>,
+    #[description = "Number of rounds for Swiss tournaments. Default: enough rounds to find a single winner"]
+    rounds: Option<i32>,
+    #[description = "Number of players per group for Round Robin tournaments. Default: 4"]
+    group_size: Option<i32>,
+    #[description = "How the players are seeded. Default: Trophies"] seeding: Option<
+        SeedingMethod,
+    >,
+    #[description = "Player tags or mentions from the first seed to the last, for Manual seeding"]
+    seeds: Option<String>,
+    #[description = "Wins required in specific rounds, e.g. 1=2, 2=2, final=3"] round_wins: Option<
+        String,
+    >,
 ) -> Result<(), BotError> {
     let map = BrawlMap::default();
     let msg = ctx
@@ -127,184 +195,188 @@ async fn start_tournament_slash(
                 .ephemeral(true),
         )
         .await?;
-    start_tournament(ctx, &msg, tournament_id, &map, win_required).await
+    let draw = DrawSettings {
+        seeding: seeding.unwrap_or_default(),
+        seeds: parse_seeds(&seeds.unwrap_or_default()),
+        rounds,
+        group_size: group_size.unwrap_or(4),
+    };
+    start_tournament(
+        ctx,
+        &msg,
+        tournament_id,
+        &map,
+        win_required,
+        &round_wins.unwrap_or_default(),
+        draw,
+    )
+    .await
 }
 
-async fn set_config(
+/// Close the group stage of a Round Robin tournament and start its playoff bracket.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn close_group_stage_slash(
     ctx: BotContext<'_>,
-    msg: &ReplyHandle<'_>,
-    marshal_role: serenity::Role,
-    announcement_channel: serenity::Channel,
-    log_channel: serenity::Channel,
+    tournament_id: i32,
+    #[description = "Number of players from each group that advance to the playoff bracket"]
+    advancing: i32,
 ) -> Result<(), BotError> {
-    let id = announcement_channel.id().to_string();
-    let announcement_channel_id = match announcement_channel.guild() {
-        Some(guild) => guild.id.to_string(),
-        None => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::new()
-                    .title("Invalid announcement channel")
-                    .description(
-                        "Please enter a valid server channel to set this announcement channel.",
-                    )
-                    .color(Colour::RED),
-                None,
-            )
-            .await?;
-            ctx.log(
-                "MANAGER CONFIGURATION SET FAILED!",
-                format!("Invalid announcement channel selected: {}", id),
-                log::State::FAILURE,
-                log::Model::MARSHAL,
-            )
-            .await?;
-            error!("Invalid announcement channel entered by {}", ctx.author());
-            return Ok(());
-        }
-    };
-    let id = log_channel.id().to_string();
-    let log_channel_id = match log_channel.guild() {
-        Some(guild) => guild.id.to_string(),
-        None => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::new()
-                    .title("Invalid log channel")
-                    .description("Please enter a valid server channel to set this log channel.")
-                    .color(Colour::RED),
-                None,
-            )
-            .await?;
-            ctx.log(
-                "MANAGER CONFIGURATION SET FAILED!",
-                format!("Invalid log channel selected: {}", id),
-                log::State::FAILURE,
-                log::Model::MARSHAL,
-            )
-            .await?;
-            error!("Invalid log channel entered by {}", ctx.author());
-            return Ok(());
-        }
-    };
-
-    let marshal_role_id = marshal_role.id.to_string();
-
-    ctx.data()
-        .database
-        .set_config(
-            &ctx.guild_id()
-                .ok_or(anyhow!("This command must be used within a server"))?
-                .to_string(),
-            &marshal_role_id,
-            &log_channel_id,
-            &announcement_channel_id,
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .content("Closing the group stage...")
+                .ephemeral(true),
         )
         .await?;
-    ctx.prompt(
-        msg,
-        CreateEmbed::new()
-            .title("Configuration set successfully!")
-            .description("Run this command again if you want to change the configuration.")
-            .color(Colour::DARK_GREEN),
-        None,
-    )
-    .await?;
+    close_group_stage(ctx, &msg, tournament_id, advancing).await
+}
 
-    info!(
-        "Set the configuration for guild {}",
-        ctx.guild_id().unwrap().to_string()
-    );
-    ctx.log(
-        "General configuration set!",
-        "The setting is set successfully!",
-        log::State::SUCCESS,
-        log::Model::GUILD,
-    )
-    .await?;
+/// Set the points per placement and the games per lobby of a Showdown tournament.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_showdown_scoring_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "Points from first place to last, e.g. 10,8,7,6,5,4,3,2,1,0"] points: String,
+    #[description = "Number of games played in each lobby. Default: 3"] games: Option<i32>,
+) -> Result<(), BotError> {
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .content("Updating the scoring...")
+                .ephemeral(true),
+        )
+        .await?;
+    set_showdown_scoring(ctx, &msg, tournament_id, &points, games.unwrap_or(3)).await
+}
 
-    Ok(())
+/// Open the check-in window of a tournament.
+///
+/// Players who have not checked in by the time the tournament starts are removed from it.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn open_check_in_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "How long the check-in stays open, in minutes. Default: 30"] minutes: Option<
+        i64,
+    >,
+) -> Result<(), BotError> {
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .content("Opening the check-in...")
+                .ephemeral(true),
+        )
+        .await?;
+    open_check_in(ctx, &msg, tournament_id, minutes.unwrap_or(30)).await
 }
 
-/// Create a new tournament.
-async fn create_tournament(
+/// Choose whether a tournament advances to the next round by itself.
+///
+/// When enabled, the next round is created as soon as the last match of the current round is
+/// decided. Disable it to keep advancing rounds manually with `next_round`.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_auto_advance_slash(
     ctx: BotContext<'_>,
-    msg: &ReplyHandle<'_>,
-    name: String,
-    mode: Mode,
-    role: serenity::Role,
-    announcement_channel: serenity::Channel,
-    notification_channel: serenity::Channel,
-    wins_required: i32,
+    tournament_id: i32,
+    #[description = "Advance rounds automatically once every match is decided"] enabled: bool,
 ) -> Result<(), BotError> {
     let guild_id = ctx.guild_id().unwrap().to_string();
-    let role_id = role.id.to_string();
-    let new_tournament_id = ctx
+    if ctx
         .data()
         .database
-        .create_tournament(
-            &guild_id,
-            &name,
-            &mode,
-            None,
-            role_id,
-            &announcement_channel.id().to_string(),
-            &notification_channel.id().to_string(),
-            wins_required,
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+        .is_none()
+    {
+        ctx.send(
+            CreateReply::default()
+                .content("The tournament with the given ID was not found.")
+                .ephemeral(true),
         )
         .await?;
-    ctx.prompt(
-        msg,
-        CreateEmbed::new()
-            .title("Successfully create a new tournament")
-            .description(format!("Tournament id: {}", new_tournament_id)),
-        None,
+        return Ok(());
+    }
+    ctx.data()
+        .database
+        .set_auto_advance(tournament_id, enabled)
+        .await?;
+    let mode = match enabled {
+        true => "automatically",
+        false => "manually",
+    };
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Tournament {} will now advance to the next round {}.",
+                tournament_id, mode
+            ))
+            .ephemeral(true),
     )
     .await?;
-    let description = format!(
-        r#"
-Tournament ID: {}
-Tournament name: {}
-    "#,
-        new_tournament_id, name
-    );
     ctx.log(
-        "Tournament created successfully!",
-        description,
+        "Round advancement updated",
+        format!(
+            "Tournament ID: {}\nRounds advance: {}\nUpdated by: {}",
+            tournament_id,
+            mode,
+            ctx.author().name
+        ),
         log::State::SUCCESS,
         log::Model::TOURNAMENT,
     )
     .await?;
-    info!(
-        "Created tournament {} for guild {}",
-        new_tournament_id, guild_id
-    );
-
     Ok(())
 }
 
-async fn start_tournament(
+/// Give players a deadline to finish the matches of each round.
+///
+/// When the deadline passes, a match with one ready player is won by forfeit and a match with
+/// nobody ready is handed over to the marshals. If the tournament is running, the current round
+/// gets the new deadline too.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_round_deadline_slash(
     ctx: BotContext<'_>,
-    msg: &ReplyHandle<'_>,
     tournament_id: i32,
-    map: &BrawlMap,
-    wins_required: Option<i32>,
+    #[description = "Minutes players have to finish each round. 0 removes the deadline"]
+    minutes: i32,
 ) -> Result<(), BotError> {
-    let wins_required = match wins_required {
-        Some(wins) => {
-            if wins < 1 {
-                ctx.send(CreateReply::default().content("Aborting operation: the number of required wins must not be less than 1!").ephemeral(true)).await?;
-                return Ok(());
-            } else {
-                wins
-            }
-        }
-        None => 2,
-    };
-
     let guild_id = ctx.guild_id().unwrap().to_string();
-
-    let tournament = match ctx
+    let mut tournament = match ctx
         .data()
         .database
         .get_tournament(&guild_id, tournament_id)
@@ -312,110 +384,1759 @@ async fn start_tournament(
     {
         Some(tournament) => tournament,
         None => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::default()
-                    .title("Tournament not found")
-                    .description("The tournament with the given ID was not found.")
-                    .color(Colour::RED),
-                None,
+            ctx.send(
+                CreateReply::default()
+                    .content("The tournament with the given ID was not found.")
+                    .ephemeral(true),
             )
             .await?;
             return Ok(());
         }
     };
-
-    match tournament.status {
-        TournamentStatus::Pending => (),
-        _ => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::default()
-                    .title("Tournament already started or ended")
-                    .description(
-                        "The tournament has already started or ended. You can't start it again.",
-                    )
-                    .color(Colour::RED),
-                None,
-            )
-            .await?;
-            return Ok(());
-        }
-    }
-
-    let tournament_players = ctx
-        .data()
+    if minutes < 0 {
+        ctx.send(
+            CreateReply::default()
+                .content("Aborting operation: the deadline must not be negative!")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    tournament.round_duration = Some(minutes).filter(|minutes| *minutes > 0);
+    ctx.data()
+        .database
+        .set_round_duration(tournament_id, tournament.round_duration)
+        .await?;
+    let deadline = tournament.round_deadline(ctx.now().timestamp());
+    let started = tournament.status == TournamentStatus::Started;
+    if started {
+        ctx.data()
+            .database
+            .set_round_deadline(tournament_id, tournament.current_round, deadline)
+            .await?;
+    }
+    let summary = match deadline {
+        Some(deadline) if started => format!(
+            "Each round now lasts {} minutes. The current round ends <t:{}:R>.",
+            minutes, deadline
+        ),
+        Some(_) => format!("Each round now lasts {} minutes.", minutes),
+        None => "Rounds no longer have a deadline.".to_string(),
+    };
+    ctx.send(CreateReply::default().content(&summary).ephemeral(true))
+        .await?;
+    ctx.log(
+        "Round deadline updated",
+        format!(
+            "Tournament ID: {}\n{}\nUpdated by: {}",
+            tournament_id,
+            summary,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Add a map to the map pool of a mode.
+///
+/// Maps assigned to the rounds and games of a tournament are picked from the pool of its mode.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn add_to_map_pool_slash(ctx: BotContext<'_>, mode: Mode) -> Result<(), BotError> {
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading maps..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let map: BrawlMap = ctx.map_selection(&msg, &mode).await?.into();
+    ctx.data()
+        .database
+        .add_to_map_pool(&guild_id, mode, &map)
+        .await?;
+    let pool = ctx.data().database.get_map_pool(&guild_id, mode).await?;
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new()
+            .title("Map added to the pool")
+            .description(format!(
+                "**{}** is now in the {} map pool.\n\nMap pool: {}",
+                map.name,
+                mode,
+                describe_map_pool(&pool)
+            )),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Map added to the pool",
+        format!(
+            "Mode: {}\nMap: {}\nAdded by: {}",
+            mode,
+            map.name,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove a map from the map pool of a mode.
+///
+/// Maps already assigned to the rounds of a tournament stay assigned.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn remove_from_map_pool_slash(ctx: BotContext<'_>, mode: Mode) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let pool = ctx.data().database.get_map_pool(&guild_id, mode).await?;
+    if pool.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content(format!("The {} map pool is empty.", mode))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading the map pool..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let map_id = select_options(
+        &ctx,
+        &msg,
+        CreateEmbed::new()
+            .title("Remove a map")
+            .description(format!("Select the map to remove from the {} map pool.", mode)),
+        None,
+        &pool,
+    )
+    .await?
+    .parse::<i32>()?;
+    let map = pool
+        .iter()
+        .find(|map| map.id == map_id)
+        .ok_or(anyhow!("Map {} is not in the pool", map_id))?;
+    ctx.data()
+        .database
+        .remove_from_map_pool(&guild_id, mode, map_id)
+        .await?;
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new()
+            .title("Map removed from the pool")
+            .description(format!(
+                "**{}** is no longer in the {} map pool.",
+                map.name, mode
+            )),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Map removed from the pool",
+        format!(
+            "Mode: {}\nMap: {}\nRemoved by: {}",
+            mode,
+            map.name,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Assign a map from the map pool to a round of a tournament, or to one game of its series.
+///
+/// A map assigned to a game is played in that game only and wins over the map of the round, which
+/// in turn wins over the map of the tournament. Results played on another map are rejected.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_round_map_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "The round to play the map in. 0 stands for the final"] round: i32,
+    #[description = "The game of the series to play the map in. Default: every game"] game: Option<
+        i32,
+    >,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content("The tournament with the given ID was not found.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    if round < 0 || game.is_some_and(|game| game < 1) {
+        ctx.send(
+            CreateReply::default()
+                .content("Aborting operation: the round must not be negative and games start from 1!")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let pool = ctx
+        .data()
+        .database
+        .get_map_pool(&guild_id, tournament.mode)
+        .await?;
+    if pool.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "The {} map pool is empty. Add maps to it with `/add_to_map_pool_slash` first.",
+                    tournament.mode
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading the map pool..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let map_id = select_options(
+        &ctx,
+        &msg,
+        CreateEmbed::new()
+            .title("Select a map")
+            .description(format!("Select a map from the {} map pool.", tournament.mode)),
+        None,
+        &pool,
+    )
+    .await?
+    .parse::<i32>()?;
+    let map = pool
+        .iter()
+        .find(|map| map.id == map_id)
+        .ok_or(anyhow!("Map {} is not in the pool", map_id))?;
+    ctx.data()
+        .database
+        .set_scheduled_map(tournament_id, round, game.unwrap_or(0), map)
+        .await?;
+    let summary = format!(
+        "**{}** will be played in {} of {}.",
+        map.name,
+        match game {
+            Some(game) => format!("game {}", game),
+            None => "every game".to_string(),
+        },
+        match round {
+            0 => "the final".to_string(),
+            round => format!("round {}", round),
+        }
+    );
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new().title("Map assigned").description(&summary),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Map assigned",
+        format!(
+            "Tournament: {} ({})\n{}\nAssigned by: {}",
+            tournament.name,
+            tournament_id,
+            summary,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set the maximum number of participants of a tournament.
+///
+/// Players who join a full tournament are put on its waitlist and take the spots that open up, in
+/// the order they joined it. Raising the cap lets the waitlist in right away.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_max_participants_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "The maximum number of participants. 0 removes the cap"] max_participants: i32,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let mut tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content("The tournament with the given ID was not found.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let error = if !tournament.status.before_start() {
+        Some("The cap can only be changed before the tournament starts.")
+    } else if max_participants < 0 {
+        Some("Aborting operation: the cap must not be negative!")
+    } else {
+        None
+    };
+    if let Some(error) = error {
+        ctx.send(CreateReply::default().content(error).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+    tournament.max_participants = Some(max_participants).filter(|max| *max > 0);
+    ctx.data()
+        .database
+        .set_max_participants(tournament_id, tournament.max_participants)
+        .await?;
+    let promoted = promote_from_waitlist(&ctx, &tournament).await?;
+    let participants = ctx
+        .data()
+        .database
+        .get_tournament_players(tournament_id)
+        .await?
+        .len();
+    let summary = match tournament.max_participants {
+        Some(max) => format!(
+            "The tournament now takes up to {} participants and has {}.",
+            max, participants
+        ),
+        None => format!(
+            "The tournament no longer has a cap and has {} participants.",
+            participants
+        ),
+    };
+    let summary = match promoted.is_empty() {
+        true => summary,
+        false => format!(
+            "{}\nPromoted from the waitlist: {}",
+            summary,
+            promoted
+                .iter()
+                .map(|discord_id| format!("<@{}>", discord_id))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    };
+    ctx.send(CreateReply::default().content(&summary).ephemeral(true))
+        .await?;
+    ctx.log(
+        "Participant cap updated",
+        format!(
+            "Tournament ID: {}\n{}\nUpdated by: {}",
+            tournament_id,
+            summary,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Close or reopen the registration of a tournament.
+///
+/// Players cannot join a tournament while its registration is closed. It can still be started
+/// like an open tournament.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_registration_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "Whether players can join the tournament"] open: bool,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content("The tournament with the given ID was not found.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let status = match open {
+        true => TournamentStatus::Pending,
+        false => TournamentStatus::RegistrationClosed,
+    };
+    // Checked by the update itself, since the tournament may be starting at the same time
+    if !ctx
+        .data()
+        .database
+        .set_registration_status(tournament_id, status)
+        .await?
+    {
+        ctx.send(
+            CreateReply::default()
+                .content("The registration can only be changed before the tournament starts.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let summary = match open {
+        true => format!("The registration of {} is open again.", tournament.name),
+        false => format!("The registration of {} is now closed.", tournament.name),
+    };
+    ctx.send(CreateReply::default().content(&summary).ephemeral(true))
+        .await?;
+    ctx.log(
+        "Registration updated",
+        format!(
+            "Tournament ID: {}\n{}\nUpdated by: {}",
+            tournament_id,
+            summary,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set or remove one of the requirements players must meet to join a tournament.
+///
+/// Requirements are checked against the live game profile of a player when they join.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn set_entry_requirement_slash(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    requirement: Requirement,
+    #[description = "A number, or a club tag for the club requirement. Leave empty to remove it"]
+    value: Option<String>,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    if ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+        .is_none()
+    {
+        ctx.send(
+            CreateReply::default()
+                .content("The tournament with the given ID was not found.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let mut requirements = ctx
+        .data()
+        .database
+        .get_entry_requirements(tournament_id)
+        .await?
+        .unwrap_or(EntryRequirements {
+            tournament_id,
+            ..Default::default()
+        });
+    if let Err(e) = requirements.set(requirement, value.as_deref()) {
+        ctx.send(
+            CreateReply::default()
+                .content(format!("Aborting operation: {}", e))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.data()
+        .database
+        .set_entry_requirements(&requirements)
+        .await?;
+    let summary = match requirements.describe() {
+        lines if lines.is_empty() => "Anyone can join the tournament.".to_string(),
+        lines => format!("Entry requirements:\n{}", lines.join("\n")),
+    };
+    ctx.send(CreateReply::default().content(&summary).ephemeral(true))
+        .await?;
+    ctx.log(
+        "Entry requirements updated",
+        format!(
+            "Tournament ID: {}\n{}\nUpdated by: {}",
+            tournament_id,
+            summary,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Create a tournament from a template on a schedule, such as every Saturday at 18:00 UTC.
+///
+/// Each tournament is created and announced the given number of hours before it starts, so that
+/// players can register for it. Without auto start, a manager starts each tournament by hand.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn add_recurring_tournament_slash(
+    ctx: BotContext<'_>,
+    #[description = "The name of every tournament created"]
+    #[min_length = 4]
+    #[max_length = 10]
+    name: String,
+    #[description = "When the tournaments start, e.g. every Saturday 18:00 or every day 18:00 (UTC)"]
+    schedule: String,
+    #[description = "How many hours before the start registration opens. Default: 24"]
+    registration_hours: Option<i32>,
+    #[description = "Whether the tournaments start by themselves. Default: true"] auto_start: Option<
+        bool,
+    >,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let (weekday, minute_of_day) = match parse_recurrence(&schedule) {
+        Ok(recurrence) => recurrence,
+        Err(e) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("Aborting operation: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let registration_hours = registration_hours.unwrap_or(24);
+    let period_hours = match weekday {
+        Some(_) => 7 * 24,
+        None => 24,
+    };
+    if registration_hours < 1 || registration_hours >= period_hours {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Aborting operation: registration must open between 1 and {} hours before the start!",
+                    period_hours - 1
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let templates = ctx.data().database.get_templates(&guild_id).await?;
+    if templates.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("There are no templates in this server. Save a tournament as a template from the manager menu first!")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading the templates..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let template_id = select_options(
+        &ctx,
+        &msg,
+        CreateEmbed::new()
+            .title("Select a template")
+            .description("Select the template the tournaments are created from."),
+        None,
+        &templates,
+    )
+    .await?
+    .parse::<i32>()?;
+    let mut recurrence = RecurringTournament {
+        recurrence_id: 0,
+        guild_id,
+        template_id,
+        name,
+        weekday,
+        minute_of_day,
+        registration_hours,
+        auto_start: auto_start.unwrap_or(true),
+        next_start: 0,
+    };
+    // The first tournament is the next one that can still open its registration on time
+    recurrence.next_start = next_occurrence(
+        weekday,
+        minute_of_day,
+        ctx.now().timestamp() + registration_hours as i64 * 60 * 60,
+    );
+    recurrence.recurrence_id = ctx
+        .data()
+        .database
+        .add_recurring_tournament(&recurrence)
+        .await?;
+    let summary = format!(
+        "{} will be created {}, {} hours before it starts. The first one starts on <t:{}:F> and registration opens <t:{}:R>.",
+        recurrence.name,
+        recurrence.schedule(),
+        registration_hours,
+        recurrence.next_start,
+        recurrence.next_creation()
+    );
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new()
+            .title("Recurring tournament added")
+            .description(&summary)
+            .color(Colour::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Recurring tournament added",
+        format!(
+            "Recurrence ID: {}\nTemplate ID: {}\n{}\nStarted by: {}\nAdded by: {}",
+            recurrence.recurrence_id,
+            template_id,
+            summary,
+            match recurrence.auto_start {
+                true => "Scheduled start",
+                false => "A manager",
+            },
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Stop creating the tournaments of a recurrence.
+///
+/// Tournaments it has already created are left as they are.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    check = "is_manager",
+    check = "is_config_set"
+)]
+#[instrument]
+async fn remove_recurring_tournament_slash(ctx: BotContext<'_>) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let recurrences = ctx
+        .data()
+        .database
+        .get_recurring_tournaments(&guild_id)
+        .await?;
+    if recurrences.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("There are no recurring tournaments in this server.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading the recurring tournaments..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let recurrence_id = select_options(
+        &ctx,
+        &msg,
+        CreateEmbed::new()
+            .title("Select a recurring tournament")
+            .description("Select the recurring tournament you want to stop."),
+        None,
+        &recurrences,
+    )
+    .await?
+    .parse::<i32>()?;
+    let recurrence = recurrences
+        .iter()
+        .find(|recurrence| recurrence.recurrence_id == recurrence_id)
+        .ok_or(anyhow!("Recurrence {} was not found", recurrence_id))?;
+    ctx.data()
+        .database
+        .remove_recurring_tournament(&guild_id, recurrence_id)
+        .await?;
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new()
+            .title("Recurring tournament removed")
+            .description(format!(
+                "{} will no longer be created {}.",
+                recurrence.name,
+                recurrence.schedule()
+            ))
+            .color(Colour::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Recurring tournament removed",
+        format!(
+            "Recurrence ID: {}\nTournament name: {}\nSchedule: {}\nRemoved by: {}",
+            recurrence_id,
+            recurrence.name,
+            recurrence.schedule(),
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Lists the maps of a map pool.
+fn describe_map_pool(pool: &[BrawlMap]) -> String {
+    match pool.is_empty() {
+        true => "empty".to_string(),
+        false => pool
+            .iter()
+            .map(|map| map.name.clone())
+            .collect::<Vec<String>>()
+            .join(", "),
+    }
+}
+
+async fn set_config(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    marshal_role: serenity::Role,
+    announcement_channel: serenity::Channel,
+    log_channel: serenity::Channel,
+) -> Result<(), BotError> {
+    let id = announcement_channel.id().to_string();
+    let announcement_channel_id = match announcement_channel.guild() {
+        Some(guild) => guild.id.to_string(),
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Invalid announcement channel")
+                    .description(
+                        "Please enter a valid server channel to set this announcement channel.",
+                    )
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            ctx.log(
+                "MANAGER CONFIGURATION SET FAILED!",
+                format!("Invalid announcement channel selected: {}", id),
+                log::State::FAILURE,
+                log::Model::MARSHAL,
+            )
+            .await?;
+            error!("Invalid announcement channel entered by {}", ctx.author());
+            return Ok(());
+        }
+    };
+    let id = log_channel.id().to_string();
+    let log_channel_id = match log_channel.guild() {
+        Some(guild) => guild.id.to_string(),
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Invalid log channel")
+                    .description("Please enter a valid server channel to set this log channel.")
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            ctx.log(
+                "MANAGER CONFIGURATION SET FAILED!",
+                format!("Invalid log channel selected: {}", id),
+                log::State::FAILURE,
+                log::Model::MARSHAL,
+            )
+            .await?;
+            error!("Invalid log channel entered by {}", ctx.author());
+            return Ok(());
+        }
+    };
+
+    let marshal_role_id = marshal_role.id.to_string();
+
+    ctx.data()
+        .database
+        .set_config(
+            &ctx.guild_id()
+                .ok_or(anyhow!("This command must be used within a server"))?
+                .to_string(),
+            &marshal_role_id,
+            &log_channel_id,
+            &announcement_channel_id,
+        )
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Configuration set successfully!")
+            .description("Run this command again if you want to change the configuration.")
+            .color(Colour::DARK_GREEN),
+        None,
+    )
+    .await?;
+
+    info!(
+        "Set the configuration for guild {}",
+        ctx.guild_id().unwrap().to_string()
+    );
+    ctx.log(
+        "General configuration set!",
+        "The setting is set successfully!",
+        log::State::SUCCESS,
+        log::Model::GUILD,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Create a new tournament.
+async fn create_tournament(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    settings: TournamentSettings,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let new_tournament_id = ctx
+        .data()
+        .database
+        .create_tournament(&guild_id, &settings, None)
+        .await?;
+    let start = match settings.start_time {
+        Some(start_time) => format!("<t:{}:F>", start_time),
+        None => "Manual".to_string(),
+    };
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Successfully create a new tournament")
+            .description(format!(
+                "Tournament id: {}\nStarts: {}",
+                new_tournament_id, start
+            )),
+        None,
+    )
+    .await?;
+    let description = format!(
+        r#"
+Tournament ID: {}
+Tournament name: {}
+Format: {}
+Team size: {}
+Start time: {}
+    "#,
+        new_tournament_id, settings.name, settings.format, settings.team_size, start
+    );
+    ctx.log(
+        "Tournament created successfully!",
+        description,
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    info!(
+        "Created tournament {} for guild {}",
+        new_tournament_id, guild_id
+    );
+
+    Ok(())
+}
+
+async fn start_tournament(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament_id: i32,
+    map: &BrawlMap,
+    wins_required: Option<i32>,
+    round_wins: &str,
+    draw: DrawSettings,
+) -> Result<(), BotError> {
+    if wins_required.is_some_and(|wins| wins < 1) {
+        ctx.send(CreateReply::default().content("Aborting operation: the number of required wins must not be less than 1!").ephemeral(true)).await?;
+        return Ok(());
+    }
+    let round_wins = match parse_round_wins(round_wins) {
+        Ok(round_wins) => round_wins,
+        Err(e) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("Aborting operation: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    if draw.rounds.is_some_and(|rounds| rounds < 1) {
+        ctx.send(CreateReply::default().content("Aborting operation: the number of rounds must not be less than 1!").ephemeral(true)).await?;
+        return Ok(());
+    }
+    if draw.group_size < 2 {
+        ctx.send(CreateReply::default().content("Aborting operation: the number of players per group must not be less than 2!").ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Tournament not found")
+                    .description("The tournament with the given ID was not found.")
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let report =
+        match launch_tournament(ctx.http(), &ctx.data().database, &tournament, &draw).await? {
+            Ok(report) => report,
+            Err(reason) => {
+                ctx.prompt(
+                    msg,
+                    CreateEmbed::default()
+                        .title("Unable to start the tournament")
+                        .description(reason)
+                        .color(Colour::RED),
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+    let wins_required = wins_required.unwrap_or(tournament.wins_required);
+    ctx.data()
+        .database
+        .set_wins_required(&tournament_id, &wins_required)
+        .await?;
+    for (round, wins) in &round_wins {
+        ctx.data()
+            .database
+            .set_round_wins_required(tournament_id, *round, *wins)
+            .await?;
+    }
+    ctx.data().database.set_map(tournament_id, map).await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::default()
+            .title("Tournament started!")
+            .description(format!(
+                "Successfully started tournament with ID {}.\n\nTotal number of matches in the first round (including byes): {}",
+                tournament_id, report.matches_count
+            ))
+            .color(Colour::DARK_GREEN),
+        None,
+    ).await?;
+    let description = format!(
+        r#"{}Wins required per match: {}
+Wins required per round: {}
+Started by: {}
+    "#,
+        report.describe(&tournament),
+        wins_required,
+        match round_wins.is_empty() {
+            true => "Default".to_string(),
+            false => round_wins
+                .iter()
+                .map(|(round, wins)| match round {
+                    0 => format!("final={}", wins),
+                    _ => format!("{}={}", round, wins),
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+        },
+        ctx.author().name
+    );
+    ctx.log(
+        "Tournament started successfully!",
+        description,
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// How the first round of a tournament is drawn.
+pub(crate) struct DrawSettings {
+    pub seeding: SeedingMethod,
+    /// The Discord IDs of the players from the first seed, for manual seeding.
+    pub seeds: Vec<String>,
+    /// The number of rounds of a Swiss tournament. None to play enough rounds to find a winner.
+    pub rounds: Option<i32>,
+    /// The number of players per group of a Round Robin tournament.
+    pub group_size: i32,
+}
+
+/// What happened when a tournament was started.
+pub(crate) struct StartReport {
+    pub seeding: SeedingMethod,
+    pub draw_seed: Option<i64>,
+    pub rounds_count: i32,
+    /// The number of matches or lobbies in the first round, byes included.
+    pub matches_count: usize,
+    /// The players removed for not checking in.
+    pub dropped: Vec<Player>,
+}
+
+impl StartReport {
+    /// Describes the start of the tournament for the log channel.
+    pub fn describe(&self, tournament: &Tournament) -> String {
+        format!(
+            r#"
+Tournament ID: {}
+Tournament name: {}
+Format: {}
+Seeding: {}
+Draw seed: {}
+Rounds: {}
+Number of matches: {}
+Dropped at check-in: {}
+"#,
+            tournament.tournament_id,
+            tournament.name,
+            tournament.format,
+            self.seeding,
+            self.draw_seed
+                .map(|draw_seed| draw_seed.to_string())
+                .unwrap_or("None".to_string()),
+            self.rounds_count,
+            self.matches_count,
+            match self.dropped.is_empty() {
+                true => "None".to_string(),
+                false => self
+                    .dropped
+                    .iter()
+                    .map(|player| format!("{} ({})", player.discord_name, player.discord_id))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            },
+        )
+    }
+}
+
+/// Draws the first round of a tournament and moves it from registration to the first round.
+///
+/// This is shared by managers starting a tournament and by the scheduler, so it only needs the
+/// HTTP client and the database. Returns the reason the tournament cannot start instead of a
+/// report if it is not ready to be started.
+pub(crate) async fn launch_tournament(
+    http: &serenity::Http,
+    database: &PgDatabase,
+    tournament: &Tournament,
+    draw: &DrawSettings,
+) -> Result<Result<StartReport, String>, BotError> {
+    let tournament_id = tournament.tournament_id;
+    if tournament.format == TournamentFormat::Showdown && !tournament.mode.is_showdown() {
+        return Ok(Err(
+            "Showdown Lobbies tournaments must be played in a Showdown mode.".to_string(),
+        ));
+    }
+    if !tournament.status.before_start() {
+        return Ok(Err(
+            "The tournament has already started or ended. You can't start it again.".to_string(),
+        ));
+    }
+    if let Some(check_in_end) = tournament.check_in_end {
+        if tournament.check_in_open(chrono::Utc::now().timestamp()) {
+            return Ok(Err(format!(
+                "The tournament can be started once the check-in closes <t:{}:R>.",
+                check_in_end
+            )));
+        }
+    }
+    // Claimed before the draw so that a manager and the scheduler can never both start it
+    if !database.claim_tournament_start(tournament_id).await? {
+        return Ok(Err(
+            "The tournament has already started or ended. You can't start it again.".to_string(),
+        ));
+    }
+    let report = draw_tournament(http, database, tournament, draw).await;
+    if !matches!(report, Ok(Ok(_))) {
+        // Back to registration so that it can be started again
+        database
+            .set_tournament_status(tournament_id, tournament.status)
+            .await?;
+    }
+    report
+}
+
+/// Drops the players who did not check in, seeds the others and creates the first round of a
+/// tournament that was claimed by `launch_tournament`.
+///
+/// The draw is written in one transaction, so a draw that fails leaves nothing behind but the
+/// claimed status, which `launch_tournament` restores.
+async fn draw_tournament(
+    http: &serenity::Http,
+    database: &PgDatabase,
+    tournament: &Tournament,
+    draw: &DrawSettings,
+) -> Result<Result<StartReport, String>, BotError> {
+    let tournament_id = tournament.tournament_id;
+    let tournament_players = database.get_tournament_players(tournament_id).await?;
+
+    // Only players who checked in are bracketed once a check-in has been held
+    let (tournament_players, dropped): (Vec<Player>, Vec<Player>) = match tournament.check_in_end {
+        Some(_) => {
+            let checked_in = database.get_checked_in_players(tournament_id).await?;
+            tournament_players
+                .into_iter()
+                .partition(|player| checked_in.contains(&player.discord_id))
+        }
+        None => (tournament_players, vec![]),
+    };
+
+    if tournament_players.len() < 2 {
+        return Ok(Err(format!(
+            "There are not enough players to start the tournament with ID {}.",
+            tournament_id
+        )));
+    }
+
+    let draw_seed = match draw.seeding {
+        SeedingMethod::Random => Some(rand::random::<i64>()),
+        _ => None,
+    };
+    // The order the random draw shuffles the players from, published along with its seed
+    let mut discord_ids: Vec<String> = tournament_players
+        .iter()
+        .map(|player| player.discord_id.clone())
+        .collect();
+    discord_ids.sort_by_key(|discord_id| draw_order(discord_id));
+    let tournament_players = match seed_players(
+        tournament_players,
+        draw.seeding,
+        &draw.seeds,
+        draw_seed.unwrap_or_default(),
+    ) {
+        Ok(players) => players,
+        Err(e) => return Ok(Err(format!("Invalid seed list: {}", e))),
+    };
+
+    let winners_rounds_count = (tournament_players.len() as f64).log2().ceil() as i32;
+    let groups: Vec<Vec<String>> = match tournament.format {
+        TournamentFormat::RoundRobin => {
+            let groups_count = tournament_players.len().div_ceil(draw.group_size as usize);
+            let mut groups = vec![Vec::new(); groups_count];
+            // Snake the seeds through the groups so that every group is equally strong
+            for (index, player) in tournament_players.iter().enumerate() {
+                let column = index % groups_count;
+                let group = match (index / groups_count) % 2 {
+                    0 => column,
+                    _ => groups_count - 1 - column,
+                };
+                groups[group].push(player.discord_id.clone());
+            }
+            groups
+        }
+        _ => Vec::new(),
+    };
+    let rounds_count = match tournament.format {
+        TournamentFormat::SingleElimination => winners_rounds_count,
+        // The losers bracket runs one round behind the winners bracket, followed by the grand
+        // final and its possible reset.
+        TournamentFormat::DoubleElimination => 2 * winners_rounds_count + 1,
+        // Everyone has faced everyone else after one round less than there are players
+        TournamentFormat::Swiss => draw
+            .rounds
+            .unwrap_or(winners_rounds_count)
+            .min(tournament_players.len() as i32 - 1),
+        // Playoff rounds are added when the group stage is closed
+        TournamentFormat::RoundRobin => group_stage_rounds(&groups),
+        TournamentFormat::Showdown => showdown_rounds(tournament_players.len()),
+    };
+
+    let seeded: Vec<String> = tournament_players
+        .iter()
+        .map(|player| player.discord_id.clone())
+        .collect();
+    let mut standings: Vec<Standing> = Vec::new();
+    let mut lobbies: Vec<Lobby> = Vec::new();
+    let matches = match tournament.format {
+        TournamentFormat::Swiss => {
+            standings = Standing::calculate(tournament_id, &seeded, &[]);
+            generate_swiss_round(&standings, &[], tournament_id, 1)?
+        }
+        TournamentFormat::RoundRobin => generate_round_robin_round(&groups, tournament_id, 1),
+        TournamentFormat::Showdown => {
+            lobbies = generate_lobbies(&seeded, tournament_id, 1);
+            Vec::new()
+        }
+        _ => generate_matches_new_tournament(tournament_players, tournament_id, 1)?,
+    };
+
+    let matches_count = matches.len() + lobbies.len();
+
+    database
+        .draw_tournament(&TournamentDraw {
+            tournament_id,
+            dropped: dropped
+                .iter()
+                .map(|player| player.discord_id.clone())
+                .collect(),
+            seeding: draw.seeding,
+            draw_seed,
+            seeds: seeded,
+            groups,
+            standings,
+            matches,
+            lobbies,
+            rounds: rounds_count,
+            deadline: tournament.round_deadline(chrono::Utc::now().timestamp()),
+        })
+        .await?;
+
+    // The draw is already saved, so failing to announce its seed must not undo the start
+    if let Some(draw_seed) = draw_seed {
+        if let Err(e) = announce_draw_seed(http, tournament, draw_seed, &discord_ids).await {
+            error!(
+                "Error announcing the draw seed of tournament {}: {}",
+                tournament_id, e
+            );
+        }
+    }
+
+    Ok(Ok(StartReport {
+        seeding: draw.seeding,
+        draw_seed,
+        rounds_count,
+        matches_count,
+        dropped,
+    }))
+}
+
+/// Publishes the seed of a random draw with its participants so that anyone can verify the draw.
+async fn announce_draw_seed(
+    http: &serenity::Http,
+    tournament: &Tournament,
+    draw_seed: i64,
+    discord_ids: &[String],
+) -> Result<(), BotError> {
+    ChannelId::new(tournament.announcement_channel_id.parse()?)
+        .send_message(
+            http,
+            CreateMessage::default()
+                .embed(
+                    CreateEmbed::default()
+                        .title(format!("Random draw for {}", tournament.name))
+                        .description(format!(
+                            "The bracket was drawn with the seed `{}`.\n\nThe {} participants listed in the attached file are shuffled with a ChaCha8 RNG seeded with this value, so anyone can re-derive the draw.",
+                            draw_seed,
+                            discord_ids.len()
+                        ))
+                        .color(Colour::GOLD),
+                )
+                .add_file(CreateAttachment::bytes(
+                    discord_ids.join("\n"),
+                    "participants.txt",
+                )),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Set the scoring of a Showdown tournament before it starts.
+async fn set_showdown_scoring(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament_id: i32,
+    points: &str,
+    games: i32,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Tournament not found")
+                    .description("The tournament with the given ID was not found.")
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let error = if tournament.format != TournamentFormat::Showdown {
+        Some("Only Showdown tournaments are scored by placement.".to_string())
+    } else if !tournament.status.before_start() {
+        Some("The scoring can only be changed before the tournament starts.".to_string())
+    } else if games < 1 {
+        Some("The number of games per lobby must not be less than 1!".to_string())
+    } else {
+        None
+    };
+    let placement_points = match error {
+        Some(error) => Err(anyhow!(error)),
+        None => parse_placement_points(points),
+    };
+    let placement_points = match placement_points {
+        Ok(placement_points) => placement_points,
+        Err(e) => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Unable to set the scoring")
+                    .description(e.to_string())
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    ctx.data()
+        .database
+        .set_showdown_scoring(tournament_id, &placement_points, games)
+        .await?;
+    let table = placement_points
+        .iter()
+        .enumerate()
+        .map(|(index, points)| format!("#{}: {} points", index + 1, points))
+        .collect::<Vec<String>>()
+        .join("\n");
+    ctx.prompt(
+        msg,
+        CreateEmbed::default()
+            .title("Scoring updated")
+            .description(format!("Games per lobby: {}\n\n{}", games, table))
+            .color(Colour::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Showdown scoring updated",
+        format!(
+            "Tournament ID: {}\nPoints: {:?}\nGames per lobby: {}\nUpdated by: {}",
+            tournament_id,
+            placement_points,
+            games,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Open the check-in window of a tournament that has not started yet.
+async fn open_check_in(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament_id: i32,
+    minutes: i64,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Tournament not found")
+                    .description("The tournament with the given ID was not found.")
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let error = if !tournament.status.before_start() {
+        Some("Check-in can only be opened before the tournament starts.")
+    } else if minutes < 1 {
+        Some("The check-in must stay open for at least 1 minute!")
+    } else {
+        None
+    };
+    if let Some(error) = error {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Unable to open the check-in")
+                .description(error)
+                .color(Colour::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let check_in_end = ctx.now().timestamp() + minutes * 60;
+    ctx.data()
+        .database
+        .open_check_in(tournament_id, check_in_end)
+        .await?;
+    tournament
+        .announcement_channel(&ctx)
+        .await?
+        .send_message(
+            ctx,
+            CreateMessage::default()
+                .content(format!("<@&{}>", tournament.tournament_role_id))
+                .embed(
+                    CreateEmbed::default()
+                        .title(format!("Check-in for {} is open!", tournament.name))
+                        .description(format!(
+                            "Check in from `/menu` before <t:{}:t> (<t:{}:R>). Players who don't check in will be removed from the tournament when it starts.",
+                            check_in_end, check_in_end
+                        ))
+                        .color(Colour::GOLD),
+                ),
+        )
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::default()
+            .title("Check-in opened")
+            .description(format!("The check-in closes <t:{}:R>.", check_in_end))
+            .color(Colour::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Check-in opened",
+        format!(
+            "Tournament ID: {}\nCloses at: <t:{}:F>\nOpened by: {}",
+            tournament_id,
+            check_in_end,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Close the group stage of a Round Robin tournament.
+///
+/// The top players of each group are seeded into a single elimination playoff bracket that starts
+/// in the round after the group stage.
+async fn close_group_stage(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament_id: i32,
+    advancing: i32,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Tournament not found")
+                    .description("The tournament with the given ID was not found.")
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let groups = ctx.data().database.get_groups(tournament_id).await?;
+    let group_rounds = group_stage_rounds(&groups);
+    type ConditionFn = fn(&Tournament, i32) -> bool;
+    type Condition<'a> = (ConditionFn, &'a str, &'a str);
+    let conditions: Vec<Condition> = vec![
+        (|t, _| t.format != TournamentFormat::RoundRobin, "Not a Round Robin tournament!", "Only Round Robin tournaments have a group stage."),
+        (|t, _| t.status != TournamentStatus::Started, "Non active tournament!", "This tournament is not currently active. Please try again when the tournament is active again."),
+        (|t, r| t.rounds != r, "Group stage already closed!", "The playoff bracket of this tournament has already started."),
+        (|t, r| t.current_round != r, "Group stage still in progress!", "Please advance the tournament to the last round of the group stage first."),
+    ];
+    for (predicate, title, message) in conditions {
+        if predicate(&tournament, group_rounds) {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title(title)
+                    .description(message)
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    }
+
+    let unfinished = ctx
+        .data()
         .database
-        .get_tournament_players(tournament_id)
+        .get_matches_by_tournament(tournament_id, Some(tournament.current_round))
+        .await?
+        .into_iter()
+        .any(|bracket| bracket.winner.is_none());
+    if unfinished {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Group stage still in progress!")
+                .description("Some players have not finished their group stage matches yet!")
+                .color(Colour::RED),
+            None,
+        )
         .await?;
-
-    if tournament_players.len() < 2 {
-        ctx.send(
-            CreateReply::default()
-                .content(format!(
-                    "There are not enough players to start the tournament with ID {}.",
-                    tournament_id
-                ))
-                .ephemeral(true),
+        return Ok(());
+    }
+    if !disputed_matches(&ctx.data().database, &tournament)
+        .await?
+        .is_empty()
+    {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Disputed results!")
+                .description("Some group stage results are disputed. Please resolve the disputes before closing the group stage.")
+                .color(Colour::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    if ctx
+        .data()
+        .database
+        .get_round_decided_at(tournament_id, tournament.current_round)
+        .await?
+        .is_some_and(|decided_at| decided_at + DISPUTE_WINDOW > ctx.now().timestamp())
+    {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Group stage just finished!")
+                .description("The last group stage results can still be disputed. Please try again in a few minutes.")
+                .color(Colour::RED),
+            None,
         )
         .await?;
         return Ok(());
     }
 
-    let rounds_count = (tournament_players.len() as f64).log2().ceil() as i32;
-
-    let matches = generate_matches_new_tournament(tournament_players, tournament_id)?;
-
-    let matches_count = matches.len();
-
-    for bracket in matches {
-        for player in bracket.match_players {
-            ctx.data()
-                .database
-                .enter_match(&bracket.match_id, &player.discord_id, PlayerType::Player)
-                .await?;
+    // Group winners are listed first, then the runners-up and so on
+    let standings = ctx.data().database.get_group_standings(tournament_id).await?;
+    let mut placed: Vec<(usize, i32, String)> = Vec::new();
+    let mut place = 0;
+    let mut current_group = None;
+    for standing in standings {
+        if current_group != Some(standing.group_number) {
+            current_group = Some(standing.group_number);
+            place = 0;
         }
+        if place < advancing.max(1) as usize {
+            placed.push((place, standing.group_number, standing.discord_id));
+        }
+        place += 1;
     }
+    placed.sort();
 
-    ctx.data()
+    let mut players = ctx
+        .data()
         .database
-        .set_tournament_status(tournament_id, TournamentStatus::Started)
+        .get_tournament_players(tournament_id)
         .await?;
+    let advancing_players: Vec<Player> = placed
+        .into_iter()
+        .filter_map(|(_, _, discord_id)| {
+            players
+                .iter()
+                .position(|player| player.discord_id == discord_id)
+                .map(|index| players.swap_remove(index))
+        })
+        .collect();
 
-    ctx.data()
+    if advancing_players.len() < 2 {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Not enough players!")
+                .description("At least 2 players must advance to the playoff bracket.")
+                .color(Colour::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let playoff_rounds = (advancing_players.len() as f64).log2().ceil() as i32;
+    let advancing_count = advancing_players.len();
+    let round = group_rounds + 1;
+    let matches = generate_matches_new_tournament(advancing_players, tournament_id, round)?;
+    let matches_count = matches.len();
+
+    let deadline = tournament.round_deadline(ctx.now().timestamp());
+    if !ctx
+        .data()
         .database
-        .set_rounds(tournament_id, rounds_count)
+        .start_playoffs(
+            tournament_id,
+            group_rounds,
+            group_rounds + playoff_rounds,
+            &matches,
+            deadline,
+        )
+        .await?
+    {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Group stage already closed!")
+                .description(
+                    "The group stage was closed or the tournament advanced in the meantime.",
+                )
+                .color(Colour::RED),
+            None,
+        )
         .await?;
-    ctx.data().database.set_map(tournament_id, map).await?;
+        return Ok(());
+    }
     ctx.prompt(
         msg,
         CreateEmbed::default()
-            .title("Tournament started!")
+            .title("Group stage closed!")
             .description(format!(
-                "Successfully started tournament with ID {}.\n\nTotal number of matches in the first round (including byes): {}",
-                tournament_id, matches_count
+                "{} players advanced to the playoff bracket of tournament {}.\n\nTotal number of matches in the first playoff round (including byes): {}",
+                advancing_count, tournament.name, matches_count
             ))
             .color(Colour::DARK_GREEN),
         None,
-    ).await?;
+    )
+    .await?;
     let description = format!(
         r#"
 Tournament ID: {}
 Tournament name: {}
-Rounds: {}
-Number of matches: {}
-Wins required per match: {}
-Started by: {}
+Players advancing per group: {}
+Players advancing: {}
+Playoff rounds: {}
+Closed by: {}
     "#,
         tournament_id,
         tournament.name,
-        rounds_count,
-        matches_count,
-        wins_required,
+        advancing,
+        advancing_count,
+        playoff_rounds,
         ctx.author().name
     );
     ctx.log(
-        "Tournament started successfully!",
+        "Group stage closed successfully!",
         description,
         log::State::SUCCESS,
         log::Model::TOURNAMENT,
@@ -436,6 +2157,8 @@ async fn manager_menu(ctx: BotContext<'_>) -> Result<(), BotError> {
 🛠️: Set configurations for the tournament.
 ➕: Create a new tournament.        
 ▶️: Start a tournament.
+✏️: Edit the settings of a tournament.
+📋: Save a tournament as a template, or create a tournament from a template or another tournament.
 "#,
         )
         .color(Colour::GOLD);
@@ -449,6 +2172,12 @@ async fn manager_menu(ctx: BotContext<'_>) -> Result<(), BotError> {
         CreateButton::new("start")
             .style(serenity::ButtonStyle::Primary)
             .label("▶️"),
+        CreateButton::new("edit")
+            .style(serenity::ButtonStyle::Primary)
+            .label("✏️"),
+        CreateButton::new("templates")
+            .style(serenity::ButtonStyle::Primary)
+            .label("📋"),
     ])];
     let builder = CreateReply::default()
         .embed(embed)
@@ -474,6 +2203,14 @@ async fn manager_menu(ctx: BotContext<'_>) -> Result<(), BotError> {
                 mci.defer(ctx.http()).await?;
                 return step_by_step_start_tournament(&ctx, &msg).await;
             }
+            "edit" => {
+                mci.defer(ctx.http()).await?;
+                return step_by_step_edit_tournament(&ctx, &msg).await;
+            }
+            "templates" => {
+                mci.defer(ctx.http()).await?;
+                return step_by_step_templates(&ctx, &msg).await;
+            }
             _ => {
                 continue;
             }
@@ -572,14 +2309,487 @@ Log channel: <#{log}>.
         }
     };
 
-    set_config(*ctx, msg, m, a, l).await?;
-    Ok(())
+    set_config(*ctx, msg, m, a, l).await?;
+    Ok(())
+}
+
+async fn step_by_step_create_tournament(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Tournament Name"]
+    struct TournamentName {
+        #[name = "Name the tournament here"]
+        #[placeholder = ""]
+        #[min_length = 4]
+        #[max_length = 10]
+        name: String,
+
+        #[name = "Minimum wins required to win a match"]
+        #[placeholder = "Write the number of wins required to win a match here or leave it blank for 3!"]
+        wins_required: Option<String>,
+
+        #[name = "Players per team"]
+        #[placeholder = "Write 3 for a team tournament or leave it blank for a solo tournament!"]
+        team_size: Option<String>,
+
+        #[name = "Start time (UTC)"]
+        #[placeholder = "e.g. 2026-10-20 18:00, or leave it blank to start the tournament manually"]
+        start_time: Option<String>,
+    }
+    let team_size = |m: &TournamentName| {
+        m.team_size
+            .as_ref()
+            .map(|t| t.parse::<i32>().unwrap_or(1).clamp(1, 3))
+            .unwrap_or(1)
+    };
+    let embed = |m: &TournamentName, f: &TournamentFormat, r: &Role, a: &Channel, n: &Channel| {
+        CreateEmbed::default()
+            .title("Tournament Confirmation")
+            .description(format!(
+                r#"Please confirm the following tournament:
+- **Tournament name:** {}
+- **Format:** {format}
+- **Role:** <@&{role}>,
+- **Announcement channel:** <#{ann}>,
+- **Notification channel:** <#{not}>.
+- **Wins required:** {win}.
+- **Players per team:** {team}.
+- **Start time (UTC):** {start}.
+"#,
+                m.name,
+                format = f,
+                role = r.id.get(),
+                ann = a.id().get(),
+                not = n.id().get(),
+                win = m
+                    .wins_required
+                    .as_ref()
+                    .map(|w| w.parse::<i32>().unwrap_or(3).max(1))
+                    .unwrap_or(3),
+                team = team_size(m),
+                start = m.start_time.as_deref().unwrap_or("Manual")
+            ))
+            .color(Colour::GOLD)
+    };
+    let (m, mode, format, a, n, r) = loop {
+        let m_embed = CreateEmbed::new()
+            .title("Creating a new tournament")
+            .description("Please provide the name of the tournament.");
+        let modal = modal::<TournamentName>(ctx, msg, m_embed.clone()).await?;
+        let mode = select_options::<Mode>(
+            ctx,
+            msg,
+            CreateEmbed::default()
+                .title("Select Mode")
+                .description("Please select the mode for the tournament."),
+            None,
+            &Mode::all(),
+        )
+        .await?;
+        let format = TournamentFormat::from_string(
+            select_options::<TournamentFormat>(
+                ctx,
+                msg,
+                CreateEmbed::default()
+                    .title("Select Format")
+                    .description("Please select the format for the tournament."),
+                None,
+                &TournamentFormat::all(),
+            )
+            .await?,
+        );
+        splash(ctx, msg).await?;
+        let announcement_channel = select_channel(
+            ctx,
+            msg,
+            "Select Announcement Channel",
+            "Please select the channel where the bot will announce the progress of the tournament.",
+        )
+        .await?;
+        splash(ctx, msg).await?;
+        let notification_channel = select_channel(ctx, msg, "Select Notification Channel", "Please select the channel where the bot will send notifications to players about their progress and matches.").await?;
+        let role = select_role(
+            ctx,
+            msg,
+            "Select Role",
+            "Please select the role for the tournament.",
+        )
+        .await?;
+        if ctx
+            .confirmation(
+                msg,
+                embed(
+                    &modal,
+                    &format,
+                    &role,
+                    &announcement_channel,
+                    &notification_channel,
+                ),
+            )
+            .await?
+        {
+            break (
+                modal,
+                mode,
+                format,
+                announcement_channel,
+                notification_channel,
+                role,
+            );
+        }
+    };
+    let start_time = match m
+        .start_time
+        .as_deref()
+        .map(|input| parse_future_time(input, ctx.now().timestamp()))
+        .transpose()
+    {
+        Ok(start_time) => start_time,
+        Err(e) => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Invalid start time")
+                    .description(e.to_string())
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let team_size = team_size(&m);
+    let wins_required = m
+        .wins_required
+        .map(|x| x.parse::<i32>().unwrap_or(3).max(1))
+        .unwrap_or(3);
+    let settings = TournamentSettings {
+        name: m.name.clone(),
+        mode: Mode::from_string(mode),
+        format,
+        wins_required,
+        team_size,
+        start_time,
+        tournament_role_id: r.id.to_string(),
+        announcement_channel_id: a.id().to_string(),
+        notification_channel_id: n.id().to_string(),
+    };
+    create_tournament(*ctx, msg, settings).await
+}
+
+/// Edit the settings of a tournament one at a time.
+///
+/// The mode and the wins required lock once the tournament has started, since the matches in
+/// progress are played and checked with them. Every change is written to the log channel.
+async fn step_by_step_edit_tournament(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Tournament Name"]
+    struct TournamentName {
+        #[name = "Name the tournament here"]
+        #[placeholder = ""]
+        #[min_length = 4]
+        #[max_length = 10]
+        name: String,
+    }
+    #[derive(Debug, Modal)]
+    #[name = "Wins Required"]
+    struct WinsRequired {
+        #[name = "Number of wins required to win a match"]
+        #[placeholder = "e.g. 3"]
+        wins_required: String,
+    }
+    let guild_id = ctx
+        .guild_id()
+        .ok_or(anyhow!("No guild id found"))?
+        .to_string();
+    let tournaments = ctx.data().database.get_all_tournaments(&guild_id).await?;
+    if tournaments.is_empty() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::default()
+                .title("Edit Tournament")
+                .description("There are no tournaments to edit.")
+                .color(Colour::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let id = select_options::<Tournament>(
+        ctx,
+        msg,
+        CreateEmbed::default()
+            .title("Edit Tournament")
+            .description("Select the tournament you want to edit"),
+        None,
+        &tournaments,
+    )
+    .await?
+    .parse::<i32>()?;
+    loop {
+        // Fetched again for every change, since the tournament may have started in the meantime
+        let tournament = ctx
+            .data()
+            .database
+            .get_tournament(&guild_id, id)
+            .await?
+            .ok_or(anyhow!("Tournament {} was not found", id))?;
+        let locked = !tournament.status.before_start();
+        let embed = CreateEmbed::default()
+            .title(format!("Edit {}", tournament.name))
+            .description(format!(
+                r#"Select the setting you want to change.{}
+- **Tournament name:** {}
+- **Mode:** {}
+- **Role:** <@&{}>
+- **Announcement channel:** <#{}>
+- **Notification channel:** <#{}>
+- **Wins required:** {}
+"#,
+                match locked {
+                    true => " The mode and the wins required cannot change once the tournament has started.",
+                    false => "",
+                },
+                tournament.name,
+                tournament.mode,
+                tournament.tournament_role_id,
+                tournament.announcement_channel_id,
+                tournament.notification_channel_id,
+                tournament.wins_required
+            ))
+            .color(Colour::GOLD);
+        let components = vec![
+            CreateActionRow::Buttons(vec![
+                CreateButton::new("edit_name")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Name"),
+                CreateButton::new("edit_mode")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Mode")
+                    .disabled(locked),
+                CreateButton::new("edit_role")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Role"),
+                CreateButton::new("edit_wins")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Wins Required")
+                    .disabled(locked),
+            ]),
+            CreateActionRow::Buttons(vec![
+                CreateButton::new("edit_announcement")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Announcement Channel"),
+                CreateButton::new("edit_notification")
+                    .style(serenity::ButtonStyle::Primary)
+                    .label("Notification Channel"),
+                CreateButton::new("edit_done")
+                    .style(serenity::ButtonStyle::Success)
+                    .label("Done"),
+            ]),
+        ];
+        msg.edit(
+            *ctx,
+            CreateReply::default().embed(embed).components(components),
+        )
+        .await?;
+        let mut ic = ctx.create_interaction_collector(msg).await?;
+        let interaction = match ic.next().await {
+            Some(interaction) => interaction,
+            None => return Ok(()),
+        };
+        interaction.defer(ctx.http()).await?;
+        let action = interaction.data.custom_id.as_str();
+        if locked && (action == "edit_mode" || action == "edit_wins") {
+            continue;
+        }
+        let (setting, before, after) = match action {
+            "edit_name" => {
+                let name = modal::<TournamentName>(
+                    ctx,
+                    msg,
+                    CreateEmbed::new()
+                        .title("Rename the tournament")
+                        .description("Please provide the new name of the tournament."),
+                )
+                .await?
+                .name;
+                ctx.data().database.set_tournament_name(id, &name).await?;
+                ("Name", tournament.name.clone(), name)
+            }
+            "edit_mode" => {
+                let mode: GameMode = ctx.mode_selection(msg).await?.into();
+                let mode: Mode = mode.into();
+                if !has_not_started(ctx, &guild_id, id).await? {
+                    continue;
+                }
+                ctx.data().database.set_tournament_mode(id, mode).await?;
+                ("Mode", tournament.mode.to_string(), mode.to_string())
+            }
+            "edit_role" => {
+                splash(ctx, msg).await?;
+                let role = select_role(
+                    ctx,
+                    msg,
+                    "Select Role",
+                    "Please select the role for the tournament.",
+                )
+                .await?;
+                ctx.data()
+                    .database
+                    .set_tournament_role(id, &role.id.to_string())
+                    .await?;
+                (
+                    "Role",
+                    format!("<@&{}>", tournament.tournament_role_id),
+                    format!("<@&{}>", role.id),
+                )
+            }
+            "edit_announcement" => {
+                splash(ctx, msg).await?;
+                let channel = select_channel(
+                    ctx,
+                    msg,
+                    "Select Announcement Channel",
+                    "Please select the channel where the bot will announce the progress of the tournament.",
+                )
+                .await?;
+                ctx.data()
+                    .database
+                    .set_announcement_channel(id, &channel.id().to_string())
+                    .await?;
+                (
+                    "Announcement channel",
+                    format!("<#{}>", tournament.announcement_channel_id),
+                    format!("<#{}>", channel.id()),
+                )
+            }
+            "edit_notification" => {
+                splash(ctx, msg).await?;
+                let channel = select_channel(
+                    ctx,
+                    msg,
+                    "Select Notification Channel",
+                    "Please select the channel where the bot will send notifications to players about their progress and matches.",
+                )
+                .await?;
+                ctx.data()
+                    .database
+                    .set_notification_channel(id, &channel.id().to_string())
+                    .await?;
+                (
+                    "Notification channel",
+                    format!("<#{}>", tournament.notification_channel_id),
+                    format!("<#{}>", channel.id()),
+                )
+            }
+            "edit_wins" => {
+                let input = modal::<WinsRequired>(
+                    ctx,
+                    msg,
+                    CreateEmbed::new()
+                        .title("Change the wins required")
+                        .description("Please provide the number of wins required to win a match."),
+                )
+                .await?;
+                let wins_required = match input.wins_required.trim().parse::<i32>() {
+                    Ok(wins) if wins >= 1 => wins,
+                    _ => {
+                        ctx.prompt(
+                            msg,
+                            CreateEmbed::default()
+                                .title("Invalid number")
+                                .description("The number of wins required must be at least 1!")
+                                .color(Colour::RED),
+                            None,
+                        )
+                        .await?;
+                        continue;
+                    }
+                };
+                if !has_not_started(ctx, &guild_id, id).await? {
+                    continue;
+                }
+                ctx.data()
+                    .database
+                    .set_wins_required(&id, &wins_required)
+                    .await?;
+                (
+                    "Wins required",
+                    tournament.wins_required.to_string(),
+                    wins_required.to_string(),
+                )
+            }
+            "edit_done" => {
+                ctx.prompt(
+                    msg,
+                    CreateEmbed::default()
+                        .title("Edit Tournament")
+                        .description(format!("You have finished editing {}.", tournament.name))
+                        .color(Colour::DARK_GREEN),
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+            _ => continue,
+        };
+        ctx.log(
+            "Tournament edited",
+            format!(
+                "Tournament: {} ({})\nSetting: {}\nBefore: {}\nAfter: {}\nEdited by: {}",
+                tournament.name,
+                tournament.tournament_id,
+                setting,
+                before,
+                after,
+                ctx.author().name
+            ),
+            log::State::SUCCESS,
+            log::Model::TOURNAMENT,
+        )
+        .await?;
+    }
+}
+
+/// Checks that a tournament has not started, right before a setting that locks at the start is
+/// changed, in case it started while the manager was picking the new value.
+async fn has_not_started(
+    ctx: &BotContext<'_>,
+    guild_id: &str,
+    tournament_id: i32,
+) -> Result<bool, BotError> {
+    Ok(ctx
+        .data()
+        .database
+        .get_tournament(guild_id, tournament_id)
+        .await?
+        .is_some_and(|tournament| tournament.status.before_start()))
 }
 
-async fn step_by_step_create_tournament(
+/// Save a tournament as a template, or create a new tournament from a template or by cloning an
+/// existing tournament.
+///
+/// New tournaments get the mode, format, map, map rotation, wins required, role, channels,
+/// brawler bans, entry requirements, participant cap and scoring of their source, but none of
+/// its players or progress.
+async fn step_by_step_templates(
     ctx: &BotContext<'_>,
     msg: &ReplyHandle<'_>,
 ) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Template Name"]
+    struct TemplateName {
+        #[name = "Name the template here"]
+        #[placeholder = "e.g. Weekly Cup. A template with the same name is replaced."]
+        #[min_length = 1]
+        #[max_length = 32]
+        name: String,
+    }
     #[derive(Debug, Modal)]
     #[name = "Tournament Name"]
     struct TournamentName {
@@ -589,97 +2799,310 @@ async fn step_by_step_create_tournament(
         #[max_length = 10]
         name: String,
 
-        #[name = "Minimum wins required to win a match"]
-        #[placeholder = "Write the number of wins required to win a match here or leave it blank for 3!"]
-        wins_required: Option<String>,
+        #[name = "Start time (UTC)"]
+        #[placeholder = "e.g. 2026-10-20 18:00, or leave it blank to start the tournament manually"]
+        start_time: Option<String>,
     }
-    let embed = |m: &TournamentName, r: &Role, a: &Channel, n: &Channel| {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or(anyhow!("No guild id found"))?
+        .to_string();
+    let buttons = vec![
+        CreateButton::new("template_save")
+            .style(serenity::ButtonStyle::Primary)
+            .label("Save as template"),
+        CreateButton::new("template_use")
+            .style(serenity::ButtonStyle::Primary)
+            .label("Create from template"),
+        CreateButton::new("template_clone")
+            .style(serenity::ButtonStyle::Primary)
+            .label("Clone tournament"),
+    ];
+    ctx.prompt(
+        msg,
         CreateEmbed::default()
-            .title("Tournament Confirmation")
-            .description(format!(
-                r#"Please confirm the following tournament:
-- **Tournament name:** {}
-- **Role:** <@&{role}>,
-- **Announcement channel:** <#{ann}>,
-- **Notification channel:** <#{not}>.
-- **Wins required:** {win}.
+            .title("Tournament Templates")
+            .description(
+                r#"Select an option from the menu below.
+- **Save as template:** Save the settings of a tournament under a name.
+- **Create from template:** Create a new tournament with the settings of a template.
+- **Clone tournament:** Create a new tournament with the settings of an existing tournament.
 "#,
-                m.name,
-                role = r.id.get(),
-                ann = a.id().get(),
-                not = n.id().get(),
-                win = m
-                    .wins_required
-                    .as_ref()
-                    .map(|w| w.parse::<i32>().unwrap_or(3).max(1))
-                    .unwrap_or(3)
-            ))
-            .color(Colour::GOLD)
+            )
+            .color(Colour::GOLD),
+        buttons,
+    )
+    .await?;
+    let mut ic = ctx.create_interaction_collector(msg).await?;
+    let action = match ic.next().await {
+        Some(interaction) => {
+            interaction.defer(ctx.http()).await?;
+            interaction.data.custom_id
+        }
+        None => return Ok(()),
     };
-    let (m, mode, a, n, r) = loop {
-        let m_embed = CreateEmbed::new()
-            .title("Creating a new tournament")
-            .description("Please provide the name of the tournament.");
-        let modal = modal::<TournamentName>(ctx, msg, m_embed.clone()).await?;
-        let mode = select_options::<Mode>(
-            ctx,
-            msg,
-            CreateEmbed::default()
-                .title("Select Mode")
-                .description("Please select the mode for the tournament."),
-            None,
-            &Mode::all(),
-        )
-        .await?;
-        splash(ctx, msg).await?;
-        let announcement_channel = select_channel(
-            ctx,
-            msg,
-            "Select Announcement Channel",
-            "Please select the channel where the bot will announce the progress of the tournament.",
-        )
-        .await?;
-        splash(ctx, msg).await?;
-        let notification_channel = select_channel(ctx, msg, "Select Notification Channel", "Please select the channel where the bot will send notifications to players about their progress and matches.").await?;
-        let role = select_role(
-            ctx,
-            msg,
-            "Select Role",
-            "Please select the role for the tournament.",
-        )
-        .await?;
-        if ctx
-            .confirmation(
+    let no_tournaments = CreateEmbed::default()
+        .title("Tournament Templates")
+        .description("There are no tournaments in this server.")
+        .color(Colour::RED);
+    match action.as_str() {
+        "template_save" => {
+            let tournaments = ctx.data().database.get_all_tournaments(&guild_id).await?;
+            if tournaments.is_empty() {
+                ctx.prompt(msg, no_tournaments, None).await?;
+                return Ok(());
+            }
+            let tournament_id = select_options::<Tournament>(
+                ctx,
                 msg,
-                embed(&modal, &role, &announcement_channel, &notification_channel),
+                CreateEmbed::default()
+                    .title("Save as template")
+                    .description("Select the tournament whose settings you want to save"),
+                None,
+                &tournaments,
             )
             .await?
-        {
-            break (
-                modal,
-                mode,
-                announcement_channel,
-                notification_channel,
-                role,
-            );
+            .parse::<i32>()?;
+            let name = modal::<TemplateName>(
+                ctx,
+                msg,
+                CreateEmbed::new()
+                    .title("Save as template")
+                    .description("Please provide the name of the template."),
+            )
+            .await?
+            .name;
+            let template_id = ctx
+                .data()
+                .database
+                .save_template(tournament_id, &name)
+                .await?;
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Template saved")
+                    .description(format!(
+                        "The settings of tournament {} are saved as the template {}.",
+                        tournament_id, name
+                    ))
+                    .color(Colour::DARK_GREEN),
+                None,
+            )
+            .await?;
+            ctx.log(
+                "Template saved",
+                format!(
+                    "Template: {} ({})\nFrom tournament: {}\nSaved by: {}",
+                    name,
+                    template_id,
+                    tournament_id,
+                    ctx.author().name
+                ),
+                log::State::SUCCESS,
+                log::Model::TOURNAMENT,
+            )
+            .await?;
+            Ok(())
+        }
+        "template_use" => {
+            let templates = ctx.data().database.get_templates(&guild_id).await?;
+            if templates.is_empty() {
+                ctx.prompt(
+                    msg,
+                    CreateEmbed::default()
+                        .title("Tournament Templates")
+                        .description("There are no templates in this server. Save a tournament as a template first!")
+                        .color(Colour::RED),
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+            let template_id = select_options::<TournamentTemplate>(
+                ctx,
+                msg,
+                CreateEmbed::default()
+                    .title("Create from template")
+                    .description("Select the template of the new tournament"),
+                None,
+                &templates,
+            )
+            .await?
+            .parse::<i32>()?;
+            let template = templates
+                .iter()
+                .find(|t| t.template_id == template_id)
+                .ok_or(anyhow!("Template {} was not found", template_id))?;
+            let m = modal::<TournamentName>(
+                ctx,
+                msg,
+                CreateEmbed::new()
+                    .title("Create from template")
+                    .description(format!(
+                        r#"Please provide the name of the new tournament.
+- **Mode:** {}
+- **Format:** {}
+- **Wins required:** {}
+- **Players per team:** {}
+- **Role:** <@&{}>
+- **Announcement channel:** <#{}>
+- **Notification channel:** <#{}>
+"#,
+                        template.mode,
+                        template.format,
+                        template.wins_required,
+                        template.team_size,
+                        template.tournament_role_id,
+                        template.announcement_channel_id,
+                        template.notification_channel_id
+                    )),
+            )
+            .await?;
+            let Some(start_time) = template_start_time(ctx, msg, m.start_time.as_deref()).await?
+            else {
+                return Ok(());
+            };
+            let tournament_id = ctx
+                .data()
+                .database
+                .create_tournament_from_template(template_id, &m.name)
+                .await?;
+            announce_copied_tournament(
+                ctx,
+                msg,
+                tournament_id,
+                &m.name,
+                format!("Template: {} ({})", template.name, template_id),
+                start_time,
+            )
+            .await
         }
+        "template_clone" => {
+            let tournaments = ctx.data().database.get_all_tournaments(&guild_id).await?;
+            if tournaments.is_empty() {
+                ctx.prompt(msg, no_tournaments, None).await?;
+                return Ok(());
+            }
+            let source_id = select_options::<Tournament>(
+                ctx,
+                msg,
+                CreateEmbed::default()
+                    .title("Clone tournament")
+                    .description("Select the tournament you want to clone"),
+                None,
+                &tournaments,
+            )
+            .await?
+            .parse::<i32>()?;
+            let m = modal::<TournamentName>(
+                ctx,
+                msg,
+                CreateEmbed::new()
+                    .title("Clone tournament")
+                    .description("Please provide the name of the new tournament."),
+            )
+            .await?;
+            let Some(start_time) = template_start_time(ctx, msg, m.start_time.as_deref()).await?
+            else {
+                return Ok(());
+            };
+            let tournament_id = ctx
+                .data()
+                .database
+                .clone_tournament(source_id, &m.name)
+                .await?;
+            announce_copied_tournament(
+                ctx,
+                msg,
+                tournament_id,
+                &m.name,
+                format!("Cloned from tournament: {}", source_id),
+                start_time,
+            )
+            .await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parses the optional start time of a tournament created from a template or a clone.
+///
+/// Returns None after telling the manager if the start time is invalid, and Some(None) if the
+/// tournament starts manually.
+async fn template_start_time(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    input: Option<&str>,
+) -> Result<Option<Option<i64>>, BotError> {
+    match input.map(|input| parse_future_time(input, ctx.now().timestamp())) {
+        Some(Err(e)) => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::default()
+                    .title("Invalid start time")
+                    .description(e.to_string())
+                    .color(Colour::RED),
+                None,
+            )
+            .await?;
+            Ok(None)
+        }
+        Some(Ok(start_time)) => Ok(Some(Some(start_time))),
+        None => Ok(Some(None)),
+    }
+}
+
+/// Schedules the start of a tournament created from a template or a clone, then tells the
+/// manager and the log channel about it.
+async fn announce_copied_tournament(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament_id: i32,
+    name: &str,
+    source: String,
+    start_time: Option<i64>,
+) -> Result<(), BotError> {
+    if start_time.is_some() {
+        ctx.data()
+            .database
+            .set_start_time(tournament_id, start_time)
+            .await?;
+    }
+    let start = match start_time {
+        Some(start_time) => format!("<t:{}:F>", start_time),
+        None => "Manual".to_string(),
     };
-    let name = m.name;
-    let wins_required = m
-        .wins_required
-        .map(|x| x.parse::<i32>().unwrap_or(3).max(1))
-        .unwrap_or(3);
-    create_tournament(
-        *ctx,
+    ctx.prompt(
         msg,
-        name,
-        Mode::from_string(mode),
-        r,
-        a,
-        n,
-        wins_required,
+        CreateEmbed::new()
+            .title("Successfully create a new tournament")
+            .description(format!(
+                "Tournament id: {}\n{}\nStarts: {}",
+                tournament_id, source, start
+            )),
+        None,
     )
-    .await
+    .await?;
+    ctx.log(
+        "Tournament created successfully!",
+        format!(
+            "Tournament ID: {}\nTournament name: {}\n{}\nStart time: {}\nCreated by: {}",
+            tournament_id,
+            name,
+            source,
+            start,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    info!(
+        "Created tournament {} for guild {}",
+        tournament_id,
+        ctx.guild_id().map(|id| id.to_string()).unwrap_or_default()
+    );
+    Ok(())
 }
 
 async fn step_by_step_start_tournament(
@@ -692,6 +3115,23 @@ async fn step_by_step_start_tournament(
         #[name = "Number of wins required to win a match"]
         #[placeholder = "Write the number of wins required to win a match here or leave it blank for 3!"]
         wins_required: Option<String>,
+
+        #[name = "Number of rounds (Swiss only)"]
+        #[placeholder = "Leave it blank to play enough rounds to find a single winner"]
+        rounds: Option<String>,
+
+        #[name = "Players per group (Round Robin only)"]
+        #[placeholder = "Leave it blank for groups of 4"]
+        group_size: Option<String>,
+
+        #[name = "Seed list (Manual seeding only)"]
+        #[placeholder = "Player tags or mentions from the first seed to the last"]
+        #[paragraph]
+        seeds: Option<String>,
+
+        #[name = "Wins required in specific rounds"]
+        #[placeholder = "e.g. 1=2, 2=2, final=3"]
+        round_wins: Option<String>,
     }
     let guild_id = ctx
         .guild_id()
@@ -715,37 +3155,199 @@ async fn step_by_step_start_tournament(
     let collector = modal::<More>(ctx, msg, embed).await?;
     let mode: GameMode = ctx.mode_selection(msg).await?.into();
     let map = ctx.map_selection(msg, &mode.into()).await?;
+    let seeding = SeedingMethod::from_string(
+        select_options::<SeedingMethod>(
+            ctx,
+            msg,
+            CreateEmbed::default()
+                .title("Select Seeding")
+                .description("Please select how the players should be seeded."),
+            None,
+            &SeedingMethod::all(),
+        )
+        .await?,
+    );
     let wins_required = collector
         .wins_required
         .map(|x| x.parse::<i32>().unwrap_or(3).max(1));
-    start_tournament(*ctx, msg, id, &map.into(), wins_required).await
+    let rounds = collector
+        .rounds
+        .and_then(|x| x.parse::<i32>().ok())
+        .map(|x| x.max(1));
+    let group_size = collector
+        .group_size
+        .and_then(|x| x.parse::<i32>().ok())
+        .map(|x| x.max(2));
+    let draw = DrawSettings {
+        seeding,
+        seeds: parse_seeds(&collector.seeds.unwrap_or_default()),
+        rounds,
+        group_size: group_size.unwrap_or(4),
+    };
+    start_tournament(
+        *ctx,
+        msg,
+        id,
+        &map.into(),
+        wins_required,
+        &collector.round_wins.unwrap_or_default(),
+        draw,
+    )
+    .await
 }
 
-/// Contains the logic for generating matches for a newly started tournament.
+/// Splits a manager-supplied seed list into its entries.
+fn parse_seeds(seeds: &str) -> Vec<String> {
+    seeds
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.to_string())
+        .collect()
+}
+
+/// Parses the manager-supplied wins required in specific rounds, e.g. `1=2, 2=2, final=3`.
 ///
-/// Returns a Vector of tuples.
-/// Each tuple contains a Match and a Vector of Users.
-fn generate_matches_new_tournament(
-    mut tournament_players: Vec<Player>,
-    tournament_id: i32,
-) -> Result<Vec<Match>, BotError> {
-    let rounds_count = (tournament_players.len() as f64).log2().ceil() as u32;
+/// Returns pairs of round and wins required, where round 0 stands for the final.
+fn parse_round_wins(round_wins: &str) -> Result<Vec<(i32, i32)>, BotError> {
+    parse_seeds(round_wins)
+        .iter()
+        .map(|entry| {
+            let invalid = || anyhow!("{} is not a valid round setting. Use round=wins, e.g. final=3.", entry);
+            let (round, wins) = entry.split_once('=').ok_or_else(invalid)?;
+            let round = match round.trim().to_lowercase().as_str() {
+                "final" => 0,
+                round => round.parse::<i32>().ok().filter(|r| *r >= 1).ok_or_else(invalid)?,
+            };
+            let wins = wins.trim().parse::<i32>().ok().filter(|w| *w >= 1).ok_or_else(invalid)?;
+            Ok((round, wins))
+        })
+        .collect()
+}
 
-    let match_count = 2_u32.pow(rounds_count - 1);
+/// Parses a manager-supplied points table, ordered from first place to last.
+fn parse_placement_points(points: &str) -> Result<Vec<i32>, BotError> {
+    let placement_points = parse_seeds(points)
+        .iter()
+        .map(|entry| {
+            entry
+                .parse::<i32>()
+                .ok()
+                .filter(|points| *points >= 0)
+                .ok_or(anyhow!("{} is not a valid number of points.", entry))
+        })
+        .collect::<Result<Vec<i32>, BotError>>()?;
+    if placement_points.is_empty() || placement_points.len() > Lobby::MAX_PLAYERS {
+        return Err(anyhow!(
+            "Please give between 1 and {} placements.",
+            Lobby::MAX_PLAYERS
+        ));
+    }
+    Ok(placement_points)
+}
 
-    let mut matches = Vec::new();
+/// Sorts Discord IDs numerically, which is the order participants are in before a random draw.
+fn draw_order(discord_id: &str) -> (usize, String) {
+    (discord_id.len(), discord_id.to_string())
+}
 
-    for i in 0..match_count {
-        let mut players: Vec<MatchPlayer> = Vec::new();
-        // Not guaranteed to have a player, this would be a bye round if there is no player
-        if (match_count as usize) <= tournament_players.len() {
-            players.push(tournament_players.pop().ok_or(anyhow!("Error generation matches for new tournament: the match count ({}), does not match the number of players ({})", match_count, tournament_players.len()))?.into());
+/// Orders the players of a tournament from the first seed to the last.
+///
+/// Manual seeding takes the player tags or Discord mentions of the players in order. Players
+/// missing from the list are seeded after the listed ones by trophies.
+///
+/// A random draw sorts the players by Discord ID and shuffles them with a ChaCha8 RNG seeded with
+/// `draw_seed`, so the same seed and participants always give the same draw.
+pub(super) fn seed_players(
+    mut players: Vec<Player>,
+    method: SeedingMethod,
+    manual_seeds: &[String],
+    draw_seed: i64,
+) -> Result<Vec<Player>, BotError> {
+    players.sort_by_key(|player| std::cmp::Reverse(player.trophies));
+    match method {
+        SeedingMethod::Trophies => Ok(players),
+        SeedingMethod::Random => {
+            players.sort_by_key(|player| draw_order(&player.discord_id));
+            players.shuffle(&mut ChaCha8Rng::seed_from_u64(draw_seed as u64));
+            Ok(players)
+        }
+        SeedingMethod::Manual => {
+            let normalize = |entry: &str| {
+                entry
+                    .trim_start_matches("<@")
+                    .trim_start_matches('!')
+                    .trim_end_matches('>')
+                    .trim_start_matches('#')
+                    .to_uppercase()
+            };
+            let mut seeded = Vec::with_capacity(players.len());
+            for entry in manual_seeds {
+                let key = normalize(entry);
+                let index = players
+                    .iter()
+                    .position(|p| p.discord_id == key || normalize(&p.player_tag) == key)
+                    .ok_or(anyhow!(
+                        "Unable to find a player in this tournament matching {}. They may be listed twice or not have joined the tournament.",
+                        entry
+                    ))?;
+                seeded.push(players.remove(index));
+            }
+            seeded.append(&mut players);
+            Ok(seeded)
         }
-        // Guaranteed to have a player
-        players.push(tournament_players.pop().ok_or(anyhow!("Error generation matches for new tournament: the match count ({}), does not match the number of players ({})", match_count, tournament_players.len()))?.into());
+    }
+}
+
+/// The standard order of seeds in a bracket, e.g. `[1, 8, 4, 5, 2, 7, 3, 6]` for 8 slots.
+///
+/// Pairing neighbouring slots puts the first seed against the last one, and the top seeds can only
+/// meet each other in the later rounds.
+fn bracket_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![1];
+    while positions.len() < size {
+        let mirror = positions.len() * 2 + 1;
+        positions = positions
+            .into_iter()
+            .flat_map(|seed| [seed, mirror - seed])
+            .collect();
+    }
+    positions
+}
 
-        matches.push(Match::new(tournament_id, 1, (i + 1) as i32, players, "0-0"));
+/// Contains the logic for generating matches for a newly started tournament.
+///
+/// The players must be ordered from the first seed to the last. Slots left empty in the bracket
+/// become byes, which go to the top seeds.
+///
+/// The matches are placed in the given round, which is only later than the first round when the
+/// bracket follows a group stage.
+pub(super) fn generate_matches_new_tournament(
+    tournament_players: Vec<Player>,
+    tournament_id: i32,
+    round: i32,
+) -> Result<Vec<Match>, BotError> {
+    if tournament_players.len() < 2 {
+        return Err(anyhow!("Error generation matches for new tournament: at least 2 players are needed, but only {} were given", tournament_players.len()));
     }
+    let rounds_count = (tournament_players.len() as f64).log2().ceil() as u32;
+    let bracket_size = 2_usize.pow(rounds_count);
+
+    let mut slots: Vec<Option<Player>> = tournament_players.into_iter().map(Some).collect();
+    slots.resize(bracket_size, None);
+
+    let matches = bracket_positions(bracket_size)
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(i, seeds)| {
+            // The higher seed always takes the first slot
+            let players: Vec<MatchPlayer> = seeds
+                .iter()
+                .filter_map(|seed| slots[seed - 1].take())
+                .map(MatchPlayer::from)
+                .collect();
+            Match::new(tournament_id, round, (i + 1) as i32, players, "0-0")
+        })
+        .collect();
 
     Ok(matches)
 }
@@ -753,7 +3355,10 @@ fn generate_matches_new_tournament(
 /// Test for the match generation for new tournaments.
 #[cfg(test)]
 mod tests {
-    use super::{generate_matches_new_tournament, models::Player};
+    use super::{
+        bracket_positions, generate_matches_new_tournament, models::Player,
+        parse_placement_points, parse_round_wins, seed_players, SeedingMethod,
+    };
 
     fn create_dummies(count: i32) -> Vec<Player> {
         let mut users: Vec<Player> = Vec::with_capacity(count as usize);
@@ -773,13 +3378,14 @@ mod tests {
 
         println!("{:?}", users);
 
-        let matches = generate_matches_new_tournament(users, -1).unwrap();
+        let matches = generate_matches_new_tournament(users, -1, 1).unwrap();
 
         assert_eq!(matches.len(), 2);
+        // Seed 1 faces seed 4 and seed 2 faces seed 3
         matches.iter().enumerate().for_each(|(i, game_match)| {
             assert_eq!(game_match.match_players.len(), 2);
             assert_eq!(game_match.match_players[0].discord_id, i.to_string());
-            assert_eq!(game_match.match_players[1].discord_id, (i + 2).to_string());
+            assert_eq!(game_match.match_players[1].discord_id, (3 - i).to_string());
         });
     }
 
@@ -790,15 +3396,16 @@ mod tests {
 
         println!("{:?}", users);
 
-        let matches = generate_matches_new_tournament(users, -2).unwrap();
+        let matches = generate_matches_new_tournament(users, -2, 1).unwrap();
 
         println!("{:?}", matches);
 
         assert_eq!(matches.len(), 2);
-        assert!(matches[0].match_players.get(0).is_some());
-        assert!(matches[0].match_players.get(1).is_some());
+        // The bye goes to the first seed
+        assert_eq!(matches[0].match_players[0].discord_id, "0");
+        assert!(matches[0].match_players.get(1).is_none());
         assert!(matches[1].match_players.get(0).is_some());
-        assert!(matches[1].match_players.get(1).is_none());
+        assert!(matches[1].match_players.get(1).is_some());
     }
 
     #[tokio::test]
@@ -806,15 +3413,16 @@ mod tests {
         const USERCOUNT: i32 = 6;
         let users = create_dummies(USERCOUNT);
 
-        let matches = generate_matches_new_tournament(users, -3).unwrap();
+        let matches = generate_matches_new_tournament(users, -3, 1).unwrap();
 
         println!("{:?}", matches);
 
         assert_eq!(matches.len(), 4);
 
+        // The byes go to the first and second seeds, who are in opposite halves of the bracket
         matches.iter().enumerate().for_each(|(i, gm)| match i {
-            2.. => {
-                assert!(gm.match_players.get(0).is_some());
+            0 | 2 => {
+                assert_eq!(gm.match_players[0].discord_id, (i / 2).to_string());
                 assert!(gm.match_players.get(1).is_none());
             }
             _ => {
@@ -823,4 +3431,68 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn places_seeds_in_standard_bracket_order() {
+        assert_eq!(bracket_positions(2), vec![1, 2]);
+        assert_eq!(bracket_positions(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn seeds_by_trophies_and_manual_list() {
+        let mut users = create_dummies(4);
+        users.iter_mut().enumerate().for_each(|(i, user)| {
+            user.trophies = i as i32 * 100;
+            user.player_tag = format!("#TAG{}", i);
+        });
+
+        let seeded = seed_players(users.clone(), SeedingMethod::Trophies, &[], 0).unwrap();
+        let order: Vec<&str> = seeded.iter().map(|p| p.discord_id.as_str()).collect();
+        assert_eq!(order, vec!["3", "2", "1", "0"]);
+
+        let manual = vec!["#tag1".to_string(), "<@0>".to_string()];
+        let seeded = seed_players(users.clone(), SeedingMethod::Manual, &manual, 0).unwrap();
+        let order: Vec<&str> = seeded.iter().map(|p| p.discord_id.as_str()).collect();
+        assert_eq!(order, vec!["1", "0", "3", "2"]);
+
+        let unknown = vec!["#NOPE".to_string()];
+        assert!(seed_players(users, SeedingMethod::Manual, &unknown, 0).is_err());
+    }
+
+    #[test]
+    fn random_draw_is_reproducible_from_seed() {
+        let users = create_dummies(16);
+        let mut reversed = users.clone();
+        reversed.reverse();
+
+        let draw = seed_players(users, SeedingMethod::Random, &[], 42).unwrap();
+        let replay = seed_players(reversed.clone(), SeedingMethod::Random, &[], 42).unwrap();
+        let other = seed_players(reversed, SeedingMethod::Random, &[], 43).unwrap();
+
+        let ids = |players: &[Player]| -> Vec<String> {
+            players.iter().map(|p| p.discord_id.clone()).collect()
+        };
+        assert_eq!(ids(&draw), ids(&replay));
+        assert_ne!(ids(&draw), ids(&other));
+    }
+
+    #[test]
+    fn parses_placement_points() {
+        assert_eq!(parse_placement_points("10, 8,6 4").unwrap(), vec![10, 8, 6, 4]);
+        assert!(parse_placement_points("").is_err());
+        assert!(parse_placement_points("10,-1").is_err());
+        assert!(parse_placement_points("1,1,1,1,1,1,1,1,1,1,1").is_err());
+    }
+
+    #[test]
+    fn parses_round_wins() {
+        assert_eq!(
+            parse_round_wins("1=2, 2=2 final=3").unwrap(),
+            vec![(1, 2), (2, 2), (0, 3)]
+        );
+        assert!(parse_round_wins("").unwrap().is_empty());
+        assert!(parse_round_wins("final").is_err());
+        assert!(parse_round_wins("0=2").is_err());
+        assert!(parse_round_wins("semis=2").is_err());
+    }
 }
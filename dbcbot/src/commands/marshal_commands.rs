@@ -1,20 +1,36 @@
+use std::collections::{HashMap, HashSet};
+
+use super::manager_commands::{generate_matches_new_tournament, seed_players};
+use super::user_commands::{draft_summary, finish_if_decided};
 use super::{checks::is_marshal_or_higher, CommandsContainer};
-use crate::database::models::{Match, PlayerType, Tournament, TournamentStatus};
-use crate::database::{MatchDatabase, TournamentDatabase, UserDatabase};
+
+use crate::database::models::{
+    Bracket, DisputeStatus, Lobby, LobbyStanding, Match, MatchDispute, MatchOverride, MatchPlayer,
+    PlayerType, SeedingMethod, Standing, Tournament, TournamentFormat, TournamentStatus,
+};
+use crate::database::{LobbyDatabase, MatchDatabase, PgDatabase, TournamentDatabase, UserDatabase};
 use crate::{
-    log::{self, Log},
-    utils::shorthand::BotContextExt,
+    log::{self, Log, SystemContext},
+    utils::discord::{modal, select_options},
+    utils::shorthand::{update_standings, BotContextExt},
     BotContext, BotData, BotError,
 };
 use anyhow::anyhow;
 use chrono::DateTime;
+use futures::StreamExt;
 use poise::{
-    serenity_prelude::{CreateEmbed, User},
-    CreateReply,
+    serenity_prelude::{
+        ButtonStyle, ChannelId, Colour, CreateButton, CreateEmbed, CreateMessage, Http, User,
+    },
+    CreateReply, Modal, ReplyHandle,
 };
 use prettytable::{row, Table};
 use tracing::{instrument, warn};
 
+/// How long after the last result of a round the round waits before it advances by itself, in
+/// seconds. Players can dispute that result in the meantime.
+pub(crate) const DISPUTE_WINDOW: i64 = 10 * 60;
+
 /// CommandsContainer for the Marshal commands
 pub struct MarshalCommands;
 
@@ -26,12 +42,19 @@ impl CommandsContainer for MarshalCommands {
         vec![
             get_tournament(),
             list_active_tournaments(),
+            get_standings(),
+            verify_draw(),
             next_round(),
             pause_tournament(),
             unpause_tournament(),
             get_match(),
             set_map(),
+            ban_brawler(),
+            unban_brawler(),
             disqualify(),
+            override_result(),
+            review_dispute(),
+            reset_match(),
         ]
     }
 }
@@ -61,6 +84,8 @@ async fn get_tournament(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), B
                     .embed(CreateEmbed::new().title(tournament.name).fields(
                         vec![("ID", tournament.tournament_id.to_string(), true),
                         ("Status", tournament.status.to_string(), true),
+                        ("Format", tournament.format.to_string(), true),
+                        ("Seeding", tournament.seeding_method.map(|m| m.to_string()).unwrap_or("Not seeded yet".to_string()), true),
                         ("Rounds", tournament.rounds.to_string(), true),
                         ("Current Round", tournament.current_round.to_string(), true),
                         ("Wins Required Per Round", tournament.wins_required.to_string(), true),
@@ -146,6 +171,61 @@ async fn list_active_tournaments(ctx: BotContext<'_>) -> Result<(), BotError> {
     Ok(())
 }
 
+/// Get the standings of a Swiss tournament.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn get_standings(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotError> {
+    // Keeps the table within Discord's message length limit
+    const MAX_SHOWN: usize = 20;
+    let standings = ctx.data().database.get_standings(tournament_id).await?;
+
+    if standings.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("There are no standings for this tournament. Standings are only kept for Swiss tournaments that have started.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let names: HashMap<String, String> = ctx
+        .data()
+        .database
+        .get_tournament_players(tournament_id)
+        .await?
+        .into_iter()
+        .map(|player| (player.discord_id, player.discord_name))
+        .collect();
+
+    let mut table = Table::new();
+    table.set_titles(row!["Rank", "Player", "W-L", "Byes", "Buchholz", "OWP"]);
+    standings.iter().take(MAX_SHOWN).enumerate().for_each(|(index, standing)| {
+        table.add_row(row![
+            index + 1,
+            names.get(&standing.discord_id).unwrap_or(&standing.discord_id),
+            format!("{}-{}", standing.wins, standing.losses),
+            standing.byes,
+            standing.buchholz,
+            format!("{:.1}%", standing.opponent_win_percentage * 100.0),
+        ]);
+    });
+
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Here are the top {} players of tournament {}\n```\n{}\n```",
+                MAX_SHOWN.min(standings.len()),
+                tournament_id,
+                table
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
 /// Set the map for a given tournament.
 #[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
 #[instrument]
@@ -213,6 +293,280 @@ Set by {}."#,
     Ok(())
 }
 
+/// Ban a brawler from a tournament. Battles played with it are not accepted as results.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn ban_brawler(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotError> {
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading brawlers..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                &msg,
+                CreateEmbed::new()
+                    .title("Tournament not found")
+                    .description(format!(
+                        "A tournament with the ID {} was not found. Please try again with another ID",
+                        tournament_id
+                    )),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let brawler = ctx.brawler_selection(&msg, None).await?;
+    ctx.data()
+        .database
+        .ban_brawler(tournament_id, &brawler.name)
+        .await?;
+    let banned = ctx
+        .data()
+        .database
+        .get_banned_brawlers(tournament_id)
+        .await?;
+    ctx.prompt(
+        &msg,
+        CreateEmbed::new()
+            .title("Brawler banned")
+            .description(format!(
+                "**{}** is now banned from {}.\n\nBanned brawlers: {}",
+                brawler.name,
+                tournament.name,
+                banned.join(", ")
+            )),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Brawler banned",
+        format!(
+            "Tournament: {} ({})\nBrawler: {}\nBanned by: {}",
+            tournament.name,
+            tournament_id,
+            brawler.name,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Lift the ban of a brawler in a tournament.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn unban_brawler(
+    ctx: BotContext<'_>,
+    tournament_id: i32,
+    #[description = "The name of the banned brawler"] brawler: String,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!(
+                        "A tournament with the ID {} was not found. Please try again with another ID",
+                        tournament_id
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    if !ctx
+        .data()
+        .database
+        .unban_brawler(tournament_id, &brawler)
+        .await?
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "{} is not banned in {}.",
+                    brawler, tournament.name
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "{} can be played in {} again.",
+                brawler, tournament.name
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    ctx.log(
+        "Brawler unbanned",
+        format!(
+            "Tournament: {} ({})\nBrawler: {}\nUnbanned by: {}",
+            tournament.name,
+            tournament_id,
+            brawler,
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Replay the random draw of a tournament from its published seed and check it against the bracket.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn verify_draw(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(
+                CreateReply::default()
+                    .content("A tournament with that id was not found")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let draw_seed = match (tournament.seeding_method, tournament.draw_seed) {
+        (Some(SeedingMethod::Random), Some(draw_seed)) => draw_seed,
+        _ => {
+            ctx.send(
+                CreateReply::default()
+                    .content("This tournament was not drawn randomly, so there is no draw to verify.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let recorded = ctx
+        .data()
+        .database
+        .get_seeded_players(tournament_id)
+        .await?;
+    let replayed = seed_players(recorded.clone(), SeedingMethod::Random, &[], draw_seed)?;
+    let seeds_match = recorded
+        .iter()
+        .zip(replayed.iter())
+        .all(|(recorded, replayed)| recorded.discord_id == replayed.discord_id);
+
+    // Only elimination brackets are drawn directly from the seeds
+    let bracket_matches = match tournament.format {
+        TournamentFormat::SingleElimination | TournamentFormat::DoubleElimination => {
+            let players = |bracket: &Match| -> Vec<String> {
+                bracket
+                    .match_players
+                    .iter()
+                    .map(|player| player.discord_id.clone())
+                    .collect()
+            };
+            let expected: HashMap<String, Vec<String>> =
+                generate_matches_new_tournament(replayed, tournament_id, 1)?
+                    .iter()
+                    .map(|bracket| (bracket.match_id.clone(), players(bracket)))
+                    .collect();
+            let actual: HashMap<String, Vec<String>> = ctx
+                .data()
+                .database
+                .get_matches_by_tournament(tournament_id, Some(1))
+                .await?
+                .iter()
+                .map(|bracket| {
+                    let mut players = players(bracket);
+                    players.sort();
+                    (bracket.match_id.clone(), players)
+                })
+                .collect();
+            Some(expected.into_iter().all(|(match_id, mut players)| {
+                players.sort();
+                actual.get(&match_id) == Some(&players)
+            }))
+        }
+        _ => None,
+    };
+
+    let verified = seeds_match && bracket_matches.unwrap_or(true);
+    let check = |passed: bool| if passed { "✅ Matches" } else { "❌ Does not match" };
+    let mut fields = vec![
+        ("Seed", draw_seed.to_string(), true),
+        ("Participants", recorded.len().to_string(), true),
+        ("Seeds", check(seeds_match).to_string(), true),
+    ];
+    if let Some(bracket_matches) = bracket_matches {
+        fields.push(("First round", check(bracket_matches).to_string(), true));
+    }
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title(if verified {
+                        "Draw verified"
+                    } else {
+                        "Draw could not be verified"
+                    })
+                    .description(format!(
+                        "Replayed the random draw of {} from its published seed.",
+                        tournament.name
+                    ))
+                    .fields(fields),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    ctx.log(
+        "Draw verification",
+        format!(
+            "The draw of tournament {} (ID {}) was {} by {}",
+            tournament.name,
+            tournament_id,
+            if verified { "verified" } else { "found not to match its seed" },
+            ctx.author().name
+        ),
+        if verified {
+            log::State::SUCCESS
+        } else {
+            log::State::FAILURE
+        },
+        log::Model::MARSHAL,
+    )
+    .await?;
+
+    Ok(())
+}
+
 /// Get the information about a match from a match ID or user.
 #[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
 async fn get_match(
@@ -265,6 +619,29 @@ async fn get_match(
 
     match bracket {
         Some(bracket) => {
+            let bans = ctx
+                .data()
+                .database
+                .get_match_bans(&bracket.match_id)
+                .await?;
+            let overrides = ctx
+                .data()
+                .database
+                .get_match_overrides(&bracket.match_id)
+                .await?;
+            let overrides = match overrides.is_empty() {
+                true => "None".to_string(),
+                false => overrides
+                    .iter()
+                    .map(|o| {
+                        format!(
+                            "<t:{}:f> <@{}> won {} (set by <@{}>): {}",
+                            o.created_at, o.winner, o.score, o.marshal_id, o.reason
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            };
             ctx.send(
                 CreateReply::default()
                     .content("")
@@ -274,6 +651,7 @@ async fn get_match(
                             .fields(vec![
                                 ("Tournament ID", bracket.tournament()?.to_string(), false),
                                 ("Round", bracket.round()?.to_string(), false),
+                                ("Bracket", bracket.bracket()?.to_string(), false),
                                 (
                                     "Player 1",
                                     format!(
@@ -296,6 +674,8 @@ async fn get_match(
                                     false,
                                 ),
                                 ("Winner", format!("<@{:#?}>", bracket.winner), false),
+                                ("Draft", draft_summary(&bans), false),
+                                ("Overrides", overrides, false),
                             ]),
                     )
                     .ephemeral(true),
@@ -449,10 +829,23 @@ async fn disqualify(ctx: BotContext<'_>, tournament_id: i32, player: User) -> Re
     };
 
     let opponent = bracket.get_opponent(&player.id.to_string())?;
-    ctx.data()
+    if !ctx
+        .data()
         .database
         .set_winner(&bracket.match_id, &opponent.discord_id, "n/a")
+        .await?
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Match {} was decided in the meantime. Use `/override_result` to correct its result instead.",
+                    bracket.match_id
+                ))
+                .ephemeral(true),
+        )
         .await?;
+        return Ok(());
+    }
 
     ctx.send(
         CreateReply::default()
@@ -482,44 +875,787 @@ Disqualified by: {disqualified_by}."#,
         log::Model::MARSHAL,
     )
     .await?;
-    Ok(())
+    conclude_match(
+        ctx.http(),
+        &ctx.data().database,
+        &guild_id,
+        tournament_id,
+        &bracket.match_id,
+    )
+    .await
 }
 
-/// List all currently active tournaments.
+/// Set or correct the winner and score of a match.
+///
+/// When the winner of an elimination match changes after the next round was drawn, the new
+/// winner takes the place of the old one in the later matches, and the other way around. This is
+/// refused while one of those matches is decided. Overrides are stored with their marshal and
+/// reason.
 #[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
 #[instrument]
-async fn next_round(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotError> {
-    let msg = ctx
-        .send(
-            CreateReply::default().embed(CreateEmbed::default().description("Running commands...")),
-        )
-        .await?;
+async fn override_result(
+    ctx: BotContext<'_>,
+    #[description = "The ID of the match, e.g. 1.2.3"] match_id: String,
+    #[description = "The player who won the match"] winner: User,
+    #[description = "The score counted from the winner's side, e.g. 2-1"] score: String,
+    #[description = "Why the result is set by hand"] reason: String,
+) -> Result<(), BotError> {
     let guild_id = ctx.guild_id().unwrap().to_string();
-
+    let not_found = CreateReply::default()
+        .content(format!(
+            "A match with the ID {} was not found. Try again with an existing match ID.",
+            match_id
+        ))
+        .ephemeral(true);
+    let bracket = match ctx.data().database.get_match_by_id(&match_id).await? {
+        Some(bracket) => bracket,
+        None => {
+            ctx.send(not_found).await?;
+            return Ok(());
+        }
+    };
     let tournament = match ctx
         .data()
         .database
-        .get_tournament(&guild_id, tournament_id)
+        .get_tournament(&guild_id, bracket.tournament()?)
         .await?
     {
         Some(tournament) => tournament,
         None => {
-            ctx.prompt(
-                &msg,
-                CreateEmbed::new()
-                    .title("No ID was given")
-                    .description("Try again with an existing tournament ID."),
-                None,
+            ctx.send(not_found).await?;
+            return Ok(());
+        }
+    };
+    let winner_id = winner.id.to_string();
+    if bracket.get_player(&winner_id).is_err() {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "<@{}> is not a player of match {}.",
+                    winner_id, bracket.match_id
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let score = match parse_score(&score) {
+        Ok(score) => score,
+        Err(e) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("Aborting operation: {}", e))
+                    .ephemeral(true),
             )
             .await?;
             return Ok(());
         }
     };
-    type ConditionFn = fn(&Tournament) -> bool;
-    type Condition<'a> = (ConditionFn, &'a str, &'a str);
-    let conditions: Vec<Condition> = vec![
-        (|t| t.status != TournamentStatus::Started, "Non active tournament!","This tournament is not currently active. Please try again when the tournament is active again."),
-        (|t| t.current_round == t.rounds, "No more rounds!","Unable to advance to the next round. This tournament is currently on its final round.")
+
+    let moves = match apply_result_override(&ctx, &tournament, &bracket, &winner_id, &score, &reason)
+        .await?
+    {
+        Ok(moves) => moves,
+        Err(refusal) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("Aborting operation: {}", refusal))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let previous = match &bracket.winner {
+        Some(previous_winner) => format!("<@{}> won {}", previous_winner, bracket.score),
+        None => "Undecided".to_string(),
+    };
+    let description = format!(
+        r#"Match ID: {match_id}.
+Tournament ID: {tournament_id}.
+Tournament name: {tournament_name}.
+Before: {previous}.
+After: <@{winner}> won {score}.
+Reason: {reason}
+{moves}
+Set by: {marshal}."#,
+        match_id = bracket.match_id,
+        tournament_id = tournament.tournament_id,
+        tournament_name = tournament.name,
+        previous = previous,
+        winner = winner_id,
+        score = score,
+        reason = reason,
+        moves = moves.join("\n"),
+        marshal = ctx.author().name
+    );
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Successfully set the result of match {}.\n{}",
+                bracket.match_id,
+                moves.join("\n")
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    ctx.log(
+        "Match result overridden",
+        description,
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    conclude_match(
+        ctx.http(),
+        &ctx.data().database,
+        &guild_id,
+        tournament.tournament_id,
+        &bracket.match_id,
+    )
+    .await
+}
+
+/// Reset a match of the current round so that it is played again.
+///
+/// The result, ready status and recorded battles of the match are cleared, and only battles played
+/// from now on count at the next submission. Both players are notified.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn reset_match(
+    ctx: BotContext<'_>,
+    #[description = "The ID of the match, e.g. 1.2.3"] match_id: String,
+    #[description = "Why the match is played again, e.g. a disconnect"] reason: String,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let not_found = CreateReply::default()
+        .content(format!(
+            "A match with the ID {} was not found. Try again with an existing match ID.",
+            match_id
+        ))
+        .ephemeral(true);
+    let bracket = match ctx.data().database.get_match_by_id(&match_id).await? {
+        Some(bracket) => bracket,
+        None => {
+            ctx.send(not_found).await?;
+            return Ok(());
+        }
+    };
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, bracket.tournament()?)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.send(not_found).await?;
+            return Ok(());
+        }
+    };
+    if !matches!(
+        tournament.status,
+        TournamentStatus::Started | TournamentStatus::Paused
+    ) {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Aborting operation: {} is not in progress, so its matches cannot be played again.",
+                    tournament.name
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    if bracket.round()? != tournament.current_round || !bracket.is_valid() {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Aborting operation: only matches between two players in the current round ({}) of {} can be played again. Use `/override_result` to correct older matches.",
+                    tournament.current_round, tournament.name
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let disputes = ctx
+        .data()
+        .database
+        .get_match_disputes(&bracket.match_id)
+        .await?;
+    if let Some(dispute) = disputes
+        .iter()
+        .find(|dispute| dispute.status == DisputeStatus::Open)
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Aborting operation: the result of this match is disputed. Resolve it with `/review_dispute {}` first.",
+                    dispute.dispute_id
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let start = ctx.now().timestamp();
+    ctx.data()
+        .database
+        .reset_match(&bracket.match_id, start)
+        .await?;
+
+    let players = bracket
+        .match_players
+        .iter()
+        .map(|player| format!("<@{}>", player.discord_id))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let previous = match &bracket.winner {
+        Some(winner) => format!("<@{}> won {}", winner, bracket.score),
+        None => "Undecided".to_string(),
+    };
+    ChannelId::new(tournament.notification_channel_id.parse()?)
+        .send_message(
+            ctx,
+            CreateMessage::default().content(&players).embed(
+                CreateEmbed::new()
+                    .title(format!("Match {} will be played again", bracket.match_id))
+                    .description(format!(
+                        "{}\n\nOnly battles played from <t:{}:F> on count. Get ready again from `/menu` and submit once you are done.",
+                        reason, start
+                    ))
+                    .color(Colour::GOLD),
+            ),
+        )
+        .await?;
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Successfully reset match {}. The players have been notified.",
+                bracket.match_id
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    let description = format!(
+        r#"Match ID: {match_id}.
+Tournament ID: {tournament_id}.
+Tournament name: {tournament_name}.
+Result before the reset: {previous}.
+Reason: {reason}
+Reset by: {marshal}."#,
+        match_id = bracket.match_id,
+        tournament_id = tournament.tournament_id,
+        tournament_name = tournament.name,
+        previous = previous,
+        reason = reason,
+        marshal = ctx.author().name
+    );
+    ctx.log(
+        "Match reset",
+        description,
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Review an open result dispute, then accept or reject it.
+///
+/// Accepting a dispute gives the match to the player who opened it with the score entered by the
+/// marshal, the same way `/override_result` does. Both players are notified of the decision.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn review_dispute(
+    ctx: BotContext<'_>,
+    #[description = "The ID of the dispute. Default: pick one of the open disputes"]
+    dispute_id: Option<i32>,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Dispute Resolution"]
+    struct DisputeResolution {
+        #[name = "Resolution"]
+        #[paragraph]
+        #[placeholder = "Explain the decision to the players"]
+        resolution: String,
+
+        #[name = "Score"]
+        #[placeholder = "Needed to accept: the score from the disputing player's side, e.g. 2-1"]
+        score: Option<String>,
+    }
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let msg = ctx
+        .send(
+            CreateReply::default()
+                .embed(CreateEmbed::default().description("Loading disputes..."))
+                .ephemeral(true),
+        )
+        .await?;
+    let not_found = CreateEmbed::new()
+        .title("Dispute not found")
+        .description("The dispute was not found. Try again with an existing dispute ID.");
+    let dispute = match dispute_id {
+        Some(dispute_id) => ctx.data().database.get_dispute(dispute_id).await?,
+        None => {
+            let open = ctx.data().database.get_open_disputes(&guild_id).await?;
+            if open.is_empty() {
+                ctx.prompt(
+                    &msg,
+                    CreateEmbed::new()
+                        .title("No open disputes")
+                        .description("There are no result disputes waiting for a review."),
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+            let selected = select_options(
+                &ctx,
+                &msg,
+                CreateEmbed::new()
+                    .title("Open disputes")
+                    .description("Select the dispute you want to review, starting from the oldest."),
+                None,
+                &open,
+            )
+            .await?
+            .parse::<i32>()?;
+            open.into_iter()
+                .find(|dispute| dispute.dispute_id == selected)
+        }
+    };
+    let dispute = match dispute {
+        Some(dispute) => dispute,
+        None => {
+            ctx.prompt(&msg, not_found, None).await?;
+            return Ok(());
+        }
+    };
+    let bracket = match ctx.data().database.get_match_by_id(&dispute.match_id).await? {
+        Some(bracket) => bracket,
+        None => {
+            ctx.prompt(&msg, not_found, None).await?;
+            return Ok(());
+        }
+    };
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, bracket.tournament()?)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(&msg, not_found, None).await?;
+            return Ok(());
+        }
+    };
+    if dispute.status != DisputeStatus::Open {
+        ctx.prompt(&msg, dispute_embed(&dispute, &bracket), None)
+            .await?;
+        return Ok(());
+    }
+
+    let buttons = vec![
+        CreateButton::new("dispute_accept")
+            .label("Accept")
+            .style(ButtonStyle::Success),
+        CreateButton::new("dispute_reject")
+            .label("Reject")
+            .style(ButtonStyle::Danger),
+    ];
+    ctx.prompt(&msg, dispute_embed(&dispute, &bracket), buttons)
+        .await?;
+    let mut ic = ctx.create_interaction_collector(&msg).await?;
+    let status = match ic.next().await {
+        Some(interaction) => {
+            interaction.defer(ctx.http()).await?;
+            match interaction.data.custom_id.as_str() {
+                "dispute_accept" => DisputeStatus::Accepted,
+                _ => DisputeStatus::Rejected,
+            }
+        }
+        None => return Ok(()),
+    };
+    let form = modal::<DisputeResolution>(
+        &ctx,
+        &msg,
+        CreateEmbed::new()
+            .title(format!("{} the dispute", match status {
+                DisputeStatus::Accepted => "Accept",
+                _ => "Reject",
+            }))
+            .description("Please explain the decision. The players will see it."),
+    )
+    .await?;
+
+    let score = match status {
+        DisputeStatus::Accepted => {
+            // The score of the match is counted from the side of the previous winner, so it
+            // cannot be kept when the winner changes
+            let score = match form
+                .score
+                .as_deref()
+                .map(str::trim)
+                .filter(|score| !score.is_empty())
+            {
+                Some(score) => parse_score(score),
+                None => Err(anyhow!(
+                    "A score is needed to accept the dispute. Write it from the side of <@{}>, e.g. 2-1.",
+                    dispute.discord_id
+                )),
+            };
+            match score {
+                Ok(score) => Some(score),
+                Err(e) => {
+                    ctx.prompt(
+                        &msg,
+                        CreateEmbed::new()
+                            .title("Invalid score")
+                            .description(e.to_string())
+                            .color(Colour::RED),
+                        None,
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            }
+        }
+        _ => None,
+    };
+    // Claim the dispute before the result changes, so that only one marshal can resolve it
+    if !ctx
+        .data()
+        .database
+        .resolve_dispute(
+            dispute.dispute_id,
+            status,
+            &ctx.author().id.to_string(),
+            &form.resolution,
+            ctx.now().timestamp(),
+        )
+        .await?
+    {
+        ctx.prompt(
+            &msg,
+            CreateEmbed::new()
+                .title("Dispute already resolved")
+                .description("Another marshal resolved this dispute in the meantime."),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut outcome = match &bracket.winner {
+        Some(winner) => format!("<@{}> won {}", winner, bracket.score),
+        None => "Undecided".to_string(),
+    };
+    let mut moves = Vec::new();
+    if let Some(score) = score {
+        let reason = format!("Dispute {}: {}", dispute.dispute_id, form.resolution);
+        let applied = apply_result_override(
+            &ctx,
+            &tournament,
+            &bracket,
+            &dispute.discord_id,
+            &score,
+            &reason,
+        )
+        .await;
+        moves = match applied {
+            Ok(Ok(moves)) => moves,
+            Ok(Err(refusal)) => {
+                ctx.data()
+                    .database
+                    .reopen_dispute(dispute.dispute_id)
+                    .await?;
+                ctx.prompt(
+                    &msg,
+                    CreateEmbed::new()
+                        .title("Unable to accept the dispute")
+                        .description(format!("The dispute is still open: {}", refusal))
+                        .color(Colour::RED),
+                    None,
+                )
+                .await?;
+                return Ok(());
+            }
+            Err(e) => {
+                ctx.data()
+                    .database
+                    .reopen_dispute(dispute.dispute_id)
+                    .await?;
+                return Err(e);
+            }
+        };
+        outcome = format!("<@{}> won {}", dispute.discord_id, score);
+    }
+
+    let players = bracket
+        .match_players
+        .iter()
+        .map(|player| format!("<@{}>", player.discord_id))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let decision = CreateEmbed::new()
+        .title(format!(
+            "The result dispute of match {} was {}",
+            bracket.match_id,
+            status.to_string().to_lowercase()
+        ))
+        .description(&form.resolution)
+        .field("Result", &outcome, false)
+        .color(match status {
+            DisputeStatus::Accepted => Colour::DARK_GREEN,
+            _ => Colour::RED,
+        });
+    ChannelId::new(tournament.notification_channel_id.parse()?)
+        .send_message(
+            ctx,
+            CreateMessage::default()
+                .content(&players)
+                .embed(decision.clone()),
+        )
+        .await?;
+    if let Some(thread_id) = &dispute.thread_id {
+        ChannelId::new(thread_id.parse()?)
+            .send_message(
+                ctx,
+                CreateMessage::default().embed(
+                    decision
+                        .clone()
+                        .field("Resolved by", format!("<@{}>", ctx.author().id), false),
+                ),
+            )
+            .await?;
+    }
+    ctx.prompt(&msg, decision, None).await?;
+    ctx.log(
+        "Dispute resolved",
+        format!(
+            "Dispute ID: {}\nMatch ID: {}\nTournament: {} ({})\nDecision: {}\nResult: {}\nResolution: {}\n{}\nResolved by: {}",
+            dispute.dispute_id,
+            bracket.match_id,
+            tournament.name,
+            tournament.tournament_id,
+            status,
+            outcome,
+            form.resolution,
+            moves.join("\n"),
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    conclude_match(
+        ctx.http(),
+        &ctx.data().database,
+        &guild_id,
+        tournament.tournament_id,
+        &bracket.match_id,
+    )
+    .await
+}
+
+/// Describes a result dispute for the marshals.
+pub(crate) fn dispute_embed(dispute: &MatchDispute, bracket: &Match) -> CreateEmbed {
+    let evidence = match dispute.evidence.is_empty() {
+        true => "None".to_string(),
+        false => dispute.evidence.join("\n"),
+    };
+    let result = match &bracket.winner {
+        Some(winner) => format!("<@{}> won {}", winner, bracket.score),
+        None => "Undecided".to_string(),
+    };
+    let embed = CreateEmbed::new()
+        .title(format!("Result dispute {}", dispute.dispute_id))
+        .description(format!(
+            "<@{}> disputes the result of match {} on <t:{}:F>.",
+            dispute.discord_id, dispute.match_id, dispute.created_at
+        ))
+        .fields(vec![
+            (
+                "Players",
+                bracket
+                    .match_players
+                    .iter()
+                    .map(|player| format!("<@{}>", player.discord_id))
+                    .collect::<Vec<String>>()
+                    .join(" vs "),
+                false,
+            ),
+            ("Result", result, false),
+            ("Statement", dispute.statement.clone(), false),
+            ("Evidence", evidence, false),
+            ("Status", dispute.status.to_string(), true),
+        ])
+        .color(Colour::ORANGE);
+    match (&dispute.resolved_by, &dispute.resolution) {
+        (Some(marshal), Some(resolution)) => embed.field(
+            "Resolution",
+            format!("{} (by <@{}>)", resolution, marshal),
+            false,
+        ),
+        _ => embed,
+    }
+}
+
+/// Sets the winner and score of a match on behalf of a marshal and stores the override.
+///
+/// When the winner of an elimination match changes, the result flows through to the later matches
+/// the players were drawn into. Returns the changes made to those matches, or why the result
+/// cannot be changed when one of them is already decided.
+pub(crate) async fn apply_result_override(
+    ctx: &BotContext<'_>,
+    tournament: &Tournament,
+    bracket: &Match,
+    winner_id: &str,
+    score: &str,
+    reason: &str,
+) -> Result<Result<Vec<String>, String>, BotError> {
+    let mut moves = Vec::new();
+    let mut replacements = Vec::new();
+    let flows_through = match tournament.format {
+        TournamentFormat::SingleElimination | TournamentFormat::DoubleElimination => true,
+        TournamentFormat::RoundRobin => bracket.bracket()? != Bracket::Group,
+        TournamentFormat::Swiss | TournamentFormat::Showdown => false,
+    };
+    if let Some(previous_winner) = bracket.winner.as_deref().filter(|w| *w != winner_id) {
+        if flows_through {
+            let brackets = ctx
+                .data()
+                .database
+                .get_matches_by_tournament(tournament.tournament_id, None)
+                .await?;
+            let later = later_matches_with(bracket, &brackets, [previous_winner, winner_id])?;
+            let decided: Vec<&str> = later
+                .iter()
+                .filter(|later| later.winner.is_some())
+                .map(|later| later.match_id.as_str())
+                .collect();
+            if !decided.is_empty() {
+                return Ok(Err(format!(
+                    "the result of match {} was carried into matches that are already decided: {}. Correct those matches first.",
+                    bracket.match_id,
+                    decided.join(", ")
+                )));
+            }
+            for later in later {
+                let (from, to) = match later.get_player(previous_winner) {
+                    Ok(_) => (previous_winner, winner_id),
+                    Err(_) => (winner_id, previous_winner),
+                };
+                replacements.push((later.match_id.clone(), from.to_string(), to.to_string()));
+                moves.push(format!(
+                    "<@{}> takes the place of <@{}> in match {}.",
+                    to, from, later.match_id
+                ));
+            }
+        }
+    }
+    let record = MatchOverride {
+        override_id: 0,
+        match_id: bracket.match_id.clone(),
+        previous_winner: bracket.winner.clone(),
+        previous_score: bracket.score.clone(),
+        winner: winner_id.to_string(),
+        score: score.to_string(),
+        reason: reason.to_string(),
+        marshal_id: ctx.author().id.to_string(),
+        created_at: ctx.now().timestamp(),
+    };
+    ctx.data()
+        .database
+        .override_result(&record, &replacements)
+        .await?;
+    if tournament.format == TournamentFormat::Swiss {
+        update_standings(&ctx.data().database, tournament.tournament_id).await?;
+    }
+    Ok(Ok(moves))
+}
+
+/// Checks a score typed by a marshal, such as `2-1`. The wins of the winner come first.
+fn parse_score(input: &str) -> Result<String, BotError> {
+    let invalid = || {
+        anyhow!(
+            "`{}` is not a valid score. Write the wins of the winner first, e.g. 2-1.",
+            input.trim()
+        )
+    };
+    let (wins, losses) = input.trim().split_once('-').ok_or_else(invalid)?;
+    let wins = wins.trim().parse::<u32>().map_err(|_| invalid())?;
+    let losses = losses.trim().parse::<u32>().map_err(|_| invalid())?;
+    if wins <= losses {
+        return Err(anyhow!(
+            "The winner must have won more games than the loser, e.g. 2-1."
+        ));
+    }
+    Ok(format!("{}-{}", wins, losses))
+}
+
+/// The matches of the rounds after a match that either of the given players was drawn into.
+fn later_matches_with<'a>(
+    bracket: &Match,
+    brackets: &'a [Match],
+    players: [&str; 2],
+) -> Result<Vec<&'a Match>, BotError> {
+    let round = bracket.round()?;
+    let mut later = Vec::new();
+    for other in brackets {
+        if other.round()? > round
+            && other
+                .match_players
+                .iter()
+                .any(|p| players.contains(&p.discord_id.as_str()))
+        {
+            later.push(other);
+        }
+    }
+    Ok(later)
+}
+
+/// List all currently active tournaments.
+#[poise::command(slash_command, guild_only, check = "is_marshal_or_higher")]
+#[instrument]
+async fn next_round(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotError> {
+    let msg = ctx
+        .send(
+            CreateReply::default().embed(CreateEmbed::default().description("Running commands...")),
+        )
+        .await?;
+    let guild_id = ctx.guild_id().unwrap().to_string();
+
+    let tournament = match ctx
+        .data()
+        .database
+        .get_tournament(&guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => {
+            ctx.prompt(
+                &msg,
+                CreateEmbed::new()
+                    .title("No ID was given")
+                    .description("Try again with an existing tournament ID."),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    type ConditionFn = fn(&Tournament) -> bool;
+    type Condition<'a> = (ConditionFn, &'a str, &'a str);
+    let conditions: Vec<Condition> = vec![
+        (|t| t.status != TournamentStatus::Started, "Non active tournament!","This tournament is not currently active. Please try again when the tournament is active again."),
+        (|t| t.current_round == t.rounds, "No more rounds!","Unable to advance to the next round. This tournament is currently on its final round.")
     ];
 
     for (predicate, title, message) in conditions {
@@ -534,6 +1670,10 @@ async fn next_round(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotEr
         }
     }
 
+    if tournament.format == TournamentFormat::Showdown {
+        return next_lobby_round(ctx, &msg, &tournament).await;
+    }
+
     let brackets = ctx
         .data()
         .database
@@ -550,26 +1690,41 @@ async fn next_round(ctx: BotContext<'_>, tournament_id: i32) -> Result<(), BotEr
         return Ok(());
     }
 
-    let round = tournament.current_round + 1;
-    let mut next_round_brackets = generate_next_round(with_winners, round)?;
-    let new_brackets_count = next_round_brackets.len();
-    while let Some(bracket) = next_round_brackets.pop() {
-        ctx.data()
-            .database
-            .create_match(bracket.tournament()?, bracket.round()?, bracket.sequence()?)
-            .await?;
-
-        for player in bracket.match_players.iter() {
-            let match_id = Match::generate_id(tournament_id, bracket.round()?, bracket.sequence()?);
-
-            ctx.data()
-                .database
-                .enter_match(&match_id, &player.discord_id, PlayerType::Player)
-                .await?;
+    let disputed = disputed_matches(&ctx.data().database, &tournament).await?;
+    if !disputed.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "Unable to advance to the next round. The results of these matches are disputed: {}. Review them with `/review_dispute` first!",
+                    disputed.join(", ")
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let next_round_brackets = match advance_round(&ctx.data().database, &tournament, with_winners)
+        .await?
+    {
+        Some(brackets) => brackets,
+        None => {
+            ctx.send(CreateReply::default().content("Unable to advance to the next round. The tournament has already been advanced in the meantime.").ephemeral(true)).await?;
+            return Ok(());
         }
+    };
+    if next_round_brackets.is_empty() {
+        ctx.prompt(
+            &msg,
+            CreateEmbed::new().title("No more rounds!").description(
+                "Unable to advance to the next round. There are no more matches to be played in this tournament.",
+            ),
+            None,
+        )
+        .await?;
+        return Ok(());
     }
-
-    ctx.data().database.next_round(tournament_id).await?;
+    let round = tournament.current_round + 1;
     if ctx
         .confirmation(
             &msg,
@@ -603,7 +1758,287 @@ Advanced by: {}."#,
         round,
         tournament.tournament_id,
         tournament.name,
-        new_brackets_count,
+        next_round_brackets.len(),
+        ctx.author().name
+    );
+    ctx.log(
+        "Tournament advanced!",
+        description,
+        log::State::SUCCESS,
+        log::Model::MARSHAL,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Finishes the tournament if the decided match was the one that settles it, and advances it
+/// otherwise.
+///
+/// Runs after a match is decided by anything other than a submission, which finishes the
+/// tournament by itself.
+pub(crate) async fn conclude_match(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    tournament_id: i32,
+    match_id: &str,
+) -> Result<(), BotError> {
+    if finish_if_decided(http, database, guild_id, match_id).await? {
+        return Ok(());
+    }
+    auto_advance(http, database, guild_id, tournament_id).await
+}
+
+/// Advances a tournament by itself once the last match of its current round is decided.
+///
+/// Does nothing if the tournament advances manually, has matches left to play in the current round
+/// or is already on its last round. The round also waits for [`DISPUTE_WINDOW`] after its last
+/// result so that the result can still be disputed, after which the scheduler advances it. This
+/// runs after match results from commands as well as from the scheduler, so it only needs the HTTP
+/// client and the database.
+pub(crate) async fn auto_advance(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    tournament_id: i32,
+) -> Result<(), BotError> {
+    // The round may have moved on since the caller fetched the tournament
+    let tournament = match database.get_tournament(guild_id, tournament_id).await? {
+        Some(tournament) => tournament,
+        None => return Ok(()),
+    };
+    if !tournament.auto_advance
+        || tournament.status != TournamentStatus::Started
+        || tournament.current_round >= tournament.rounds
+        || tournament.format == TournamentFormat::Showdown
+    {
+        return Ok(());
+    }
+    let mut brackets = database
+        .get_matches_by_tournament(tournament_id, Some(tournament.current_round))
+        .await?;
+    // Byes are otherwise only settled when their player looks at the match
+    for bracket in brackets
+        .iter_mut()
+        .filter(|bracket| bracket.winner.is_none() && bracket.match_players.len() == 1)
+    {
+        let winner = bracket.match_players[0].discord_id.clone();
+        database
+            .set_winner(&bracket.match_id, &winner, "bye")
+            .await?;
+        bracket.winner = Some(winner);
+    }
+    if brackets.iter().any(|bracket| bracket.winner.is_none())
+        || !disputed_matches(database, &tournament).await?.is_empty()
+    {
+        return Ok(());
+    }
+    if database
+        .get_round_decided_at(tournament_id, tournament.current_round)
+        .await?
+        .is_some_and(|decided_at| decided_at + DISPUTE_WINDOW > chrono::Utc::now().timestamp())
+    {
+        return Ok(());
+    }
+
+    let next_round_brackets = match advance_round(database, &tournament, brackets).await? {
+        Some(brackets) if !brackets.is_empty() => brackets,
+        _ => return Ok(()),
+    };
+    let round = tournament.current_round + 1;
+    ChannelId::new(tournament.notification_channel_id.parse()?)
+        .send_message(
+            http,
+            CreateMessage::default()
+                .content(format!("<@&{}>", tournament.tournament_role_id))
+                .embed(
+                    CreateEmbed::default()
+                        .title(format!("Round {} of {} has begun!", round, tournament.name))
+                        .description("Every match of the previous round has been decided. Check your next match from `/menu`. Good luck!")
+                        .field("Matches", next_round_brackets.len().to_string(), true),
+                ),
+        )
+        .await?;
+    let description = format!(
+        r#"The tournament has advanced to round {}.
+Tournament ID: {}.
+Tournament name: {}.
+Number of matches: {}.
+Advanced by: Automatic advancement."#,
+        round,
+        tournament.tournament_id,
+        tournament.name,
+        next_round_brackets.len(),
+    );
+    SystemContext {
+        http,
+        database,
+        guild_id,
+    }
+    .log(
+        "Tournament advanced!",
+        description,
+        log::State::SUCCESS,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// The IDs of the matches of a tournament whose result is disputed.
+///
+/// A tournament cannot advance or finish while the result of any of its matches is disputed, since
+/// resolving the dispute may change who plays in the rounds after it.
+pub(crate) async fn disputed_matches(
+    database: &PgDatabase,
+    tournament: &Tournament,
+) -> Result<Vec<String>, BotError> {
+    let mut disputed = Vec::new();
+    for dispute in database.get_open_disputes(&tournament.guild_id).await? {
+        let bracket = match database.get_match_by_id(&dispute.match_id).await? {
+            Some(bracket) => bracket,
+            None => continue,
+        };
+        if bracket.tournament()? == tournament.tournament_id {
+            disputed.push(bracket.match_id);
+        }
+    }
+    Ok(disputed)
+}
+
+/// Creates the matches of the round after the current one and moves the tournament to it.
+///
+/// Every match of the current round must already have a winner. Returns the new matches, which is
+/// empty if there was nothing left to play, or None if the tournament was advanced in the
+/// meantime.
+async fn advance_round(
+    database: &PgDatabase,
+    tournament: &Tournament,
+    with_winners: Vec<Match>,
+) -> Result<Option<Vec<Match>>, BotError> {
+    let tournament_id = tournament.tournament_id;
+    let round = tournament.current_round + 1;
+    let next_round_brackets = match tournament.format {
+        TournamentFormat::SingleElimination => generate_next_round(with_winners, round)?,
+        TournamentFormat::DoubleElimination => {
+            // Losers bracket matches are fed by rounds before the current one, so every match in
+            // the tournament is needed.
+            let all_brackets = database
+                .get_matches_by_tournament(tournament_id, None)
+                .await?;
+            generate_next_round_double_elimination(all_brackets, tournament_id, tournament.rounds, round)?
+        }
+        TournamentFormat::Swiss => {
+            let standings = update_standings(database, tournament_id).await?;
+            let all_brackets = database
+                .get_matches_by_tournament(tournament_id, None)
+                .await?;
+            generate_swiss_round(&standings, &all_brackets, tournament_id, round)?
+        }
+        TournamentFormat::RoundRobin => {
+            let groups = database.get_groups(tournament_id).await?;
+            if round <= group_stage_rounds(&groups) {
+                generate_round_robin_round(&groups, tournament_id, round)
+            } else {
+                generate_next_round(with_winners, round)?
+            }
+        }
+        // Handled by next_lobby_round
+        TournamentFormat::Showdown => Vec::new(),
+    };
+    if next_round_brackets.is_empty() {
+        return Ok(Some(next_round_brackets));
+    }
+    let deadline = tournament.round_deadline(chrono::Utc::now().timestamp());
+    if !database
+        .start_round(tournament_id, round, &next_round_brackets, &[], deadline)
+        .await?
+    {
+        return Ok(None);
+    }
+    Ok(Some(next_round_brackets))
+}
+
+/// Advances a showdown tournament by moving the best placed players of every lobby into the
+/// lobbies of the next round.
+async fn next_lobby_round(
+    ctx: BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<(), BotError> {
+    let lobbies = ctx
+        .data()
+        .database
+        .get_lobbies(tournament.tournament_id, tournament.current_round)
+        .await?;
+    if lobbies
+        .iter()
+        .any(|lobby| lobby.games_played < tournament.lobby_games)
+    {
+        ctx.send(CreateReply::default().content("Unable to advance to the next round. Some lobbies have not finished all their games yet!").ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    let mut ranked_lobbies = Vec::with_capacity(lobbies.len());
+    for lobby in &lobbies {
+        let results = ctx
+            .data()
+            .database
+            .get_lobby_results(&lobby.lobby_id)
+            .await?;
+        let standings = LobbyStanding::calculate(&lobby.players, &results);
+        ranked_lobbies.push(
+            standings
+                .into_iter()
+                .take(Lobby::advancing(lobby.players.len()))
+                .map(|standing| standing.discord_id)
+                .collect::<Vec<String>>(),
+        );
+    }
+    // Order the advancing players by their placement within their lobby so that the winners of
+    // the previous lobbies are spread across the new ones
+    let most_advancing = ranked_lobbies.iter().map(Vec::len).max().unwrap_or(0);
+    let advancing: Vec<String> = (0..most_advancing)
+        .flat_map(|rank| {
+            ranked_lobbies
+                .iter()
+                .filter_map(move |players| players.get(rank).cloned())
+        })
+        .collect();
+
+    let round = tournament.current_round + 1;
+    let next_lobbies = generate_lobbies(&advancing, tournament.tournament_id, round);
+    if !ctx
+        .data()
+        .database
+        .start_round(tournament.tournament_id, round, &[], &next_lobbies, None)
+        .await?
+    {
+        ctx.send(CreateReply::default().content("Unable to advance to the next round. The tournament has already been advanced in the meantime.").ephemeral(true)).await?;
+        return Ok(());
+    }
+
+    ctx.prompt(
+        msg,
+        CreateEmbed::new().description(format!(
+            "Successfully advanced the tournament with ID {} to the round {}.",
+            tournament.tournament_id, round
+        )),
+        None,
+    )
+    .await?;
+    let description = format!(
+        r#"The tournament has advanced to round {}.
+Tournament ID: {}.
+Tournament name: {}.
+Number of lobbies: {}.
+Players advancing: {}.
+Advanced by: {}."#,
+        round,
+        tournament.tournament_id,
+        tournament.name,
+        next_lobbies.len(),
+        advancing.len(),
         ctx.author().name
     );
     ctx.log(
@@ -617,12 +2052,33 @@ Advanced by: {}."#,
 }
 
 /// Generates the matches for the next round.
+///
+/// When the semifinals are done, the final is created along with a third place match between the
+/// semifinal losers. The third place match is skipped if either semifinal was a bye.
 fn generate_next_round(brackets: Vec<Match>, round: i32) -> Result<Vec<Match>, BotError> {
-    let mut next_round_brackets = Vec::with_capacity(brackets.len() / 2);
+    let next_round_count = brackets.len() / 2;
+    let mut next_round_brackets = Vec::with_capacity(next_round_count + 1);
     let tournament_id = brackets[0].tournament()?;
+    let semifinal_losers: Vec<MatchPlayer> = match next_round_count {
+        1 => brackets
+            .iter()
+            .filter_map(|bracket| bracket.get_losing_player().cloned())
+            .collect(),
+        _ => Vec::new(),
+    };
+    if semifinal_losers.len() == 2 {
+        next_round_brackets.push(Match::new_in_bracket(
+            tournament_id,
+            round,
+            1,
+            Bracket::ThirdPlace,
+            semifinal_losers,
+            "0-0",
+        ));
+    }
     let mut brackets_iter = brackets.into_iter();
 
-    for _i in 1..=next_round_brackets.len() {
+    for _i in 1..=next_round_count {
         let prev_bracket_1 = brackets_iter.next().ok_or(anyhow!("Error advancing to the next round: Ran out of brackets from the previous round while generating the next round."))?;
         let prev_bracket_2 = brackets_iter.next().ok_or(anyhow!("Error advancing to the next round: Ran out of brackets from the previous round while generating the next round."))?;
 
@@ -658,3 +2114,729 @@ fn generate_next_round(brackets: Vec<Match>, round: i32) -> Result<Vec<Match>, B
 
     Ok(next_round_brackets)
 }
+
+/// How many pairings the Swiss pairing search tries before settling for rematches.
+const SWISS_PAIRING_ATTEMPTS: usize = 10_000;
+
+/// Generates the matches for the next round of a Swiss tournament.
+///
+/// Players are paired in order of their standings with the closest ranked player they have not
+/// faced yet, so players with equal records meet whenever possible. Rematches are only allowed if
+/// there is no other way to pair everyone. With an odd number of players, the lowest ranked player
+/// who has not had a bye yet gets one.
+pub(super) fn generate_swiss_round(
+    standings: &[Standing],
+    brackets: &[Match],
+    tournament_id: i32,
+    round: i32,
+) -> Result<Vec<Match>, BotError> {
+    let played: HashSet<(&str, &str)> = brackets
+        .iter()
+        .filter(|bracket| bracket.is_valid())
+        .flat_map(|bracket| {
+            let (player_1, player_2) = (
+                bracket.match_players[0].discord_id.as_str(),
+                bracket.match_players[1].discord_id.as_str(),
+            );
+            [(player_1, player_2), (player_2, player_1)]
+        })
+        .collect();
+
+    let mut players: Vec<&Standing> = standings.iter().collect();
+    let bye = if players.len() % 2 == 1 {
+        let index = players
+            .iter()
+            .rposition(|standing| standing.byes == 0)
+            .unwrap_or(players.len() - 1);
+        Some(players.remove(index))
+    } else {
+        None
+    };
+
+    /// Backtracks through the pairings without rematches. Gives up once `attempts` run out, as the
+    /// search is exponential when no such pairing exists.
+    fn pair<'a>(
+        players: &[&'a Standing],
+        played: &HashSet<(&str, &str)>,
+        attempts: &mut usize,
+    ) -> Option<Vec<(&'a Standing, &'a Standing)>> {
+        let (first, rest) = match players.split_first() {
+            Some(split) => split,
+            None => return Some(Vec::new()),
+        };
+        for (index, opponent) in rest.iter().enumerate() {
+            if played.contains(&(first.discord_id.as_str(), opponent.discord_id.as_str())) {
+                continue;
+            }
+            if *attempts == 0 {
+                return None;
+            }
+            *attempts -= 1;
+            let mut remaining = rest.to_vec();
+            remaining.remove(index);
+            if let Some(mut pairs) = pair(&remaining, played, attempts) {
+                pairs.insert(0, (*first, *opponent));
+                return Some(pairs);
+            }
+        }
+        None
+    }
+
+    let mut attempts = SWISS_PAIRING_ATTEMPTS;
+    let pairs = pair(&players, &played, &mut attempts).unwrap_or_else(|| {
+        warn!(
+            "Unable to avoid rematches in round {} of tournament {}",
+            round, tournament_id
+        );
+        // Pair everyone from the top with the closest opponent they have not faced yet, and only
+        // fall back to a rematch when every remaining opponent has been faced already
+        let mut remaining = players.clone();
+        let mut pairs = Vec::new();
+        while remaining.len() >= 2 {
+            let first = remaining.remove(0);
+            let index = remaining
+                .iter()
+                .position(|opponent| {
+                    !played.contains(&(first.discord_id.as_str(), opponent.discord_id.as_str()))
+                })
+                .unwrap_or(0);
+            pairs.push((first, remaining.remove(index)));
+        }
+        pairs
+    });
+
+    let mut next_round_brackets: Vec<Match> = pairs
+        .into_iter()
+        .map(|(player_1, player_2)| vec![player_1, player_2])
+        .chain(bye.map(|player| vec![player]))
+        .enumerate()
+        .map(|(index, players)| {
+            Match::new(
+                tournament_id,
+                round,
+                index as i32 + 1,
+                players
+                    .into_iter()
+                    .map(|standing| MatchPlayer {
+                        match_id: Match::generate_id(tournament_id, round, index as i32 + 1),
+                        discord_id: standing.discord_id.clone(),
+                        player_type: PlayerType::Player,
+                        ready: false,
+                    })
+                    .collect(),
+                "0-0",
+            )
+        })
+        .collect();
+    // A bye is an automatic win
+    if let Some(bracket) = next_round_brackets.last_mut().filter(|b| !b.is_valid()) {
+        bracket.winner = Some(bracket.match_players[0].discord_id.clone());
+        bracket.score = "bye".to_string();
+    }
+
+    Ok(next_round_brackets)
+}
+
+/// The number of rounds needed for every player to face everyone else in their group.
+pub(super) fn group_stage_rounds(groups: &[Vec<String>]) -> i32 {
+    groups
+        .iter()
+        // Odd groups need an extra round because one player sits out every round
+        .map(|group| (group.len() + group.len() % 2) as i32 - 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Generates the group stage matches for a round of a round robin tournament.
+///
+/// Uses the circle method: the first player of each group stays in place while the others rotate
+/// by one position every round, so everyone faces each other exactly once. In groups with an odd
+/// number of players, the player paired with the empty slot sits out the round.
+pub(super) fn generate_round_robin_round(
+    groups: &[Vec<String>],
+    tournament_id: i32,
+    round: i32,
+) -> Vec<Match> {
+    let mut brackets = Vec::new();
+    for group in groups {
+        let mut slots: Vec<Option<&String>> = group.iter().map(Some).collect();
+        if slots.len() % 2 == 1 {
+            slots.push(None);
+        }
+        // Smaller groups finish their matches before the others
+        if slots.len() < 2 || round as usize >= slots.len() {
+            continue;
+        }
+        slots[1..].rotate_right(round as usize - 1);
+        for index in 0..slots.len() / 2 {
+            if let (Some(player_1), Some(player_2)) = (slots[index], slots[slots.len() - 1 - index]) {
+                let sequence = brackets.len() as i32 + 1;
+                let match_id =
+                    Match::generate_bracket_id(tournament_id, round, sequence, Bracket::Group);
+                brackets.push(Match::new_in_bracket(
+                    tournament_id,
+                    round,
+                    sequence,
+                    Bracket::Group,
+                    [player_1, player_2]
+                        .into_iter()
+                        .map(|discord_id| MatchPlayer {
+                            match_id: match_id.clone(),
+                            discord_id: discord_id.clone(),
+                            player_type: PlayerType::Player,
+                            ready: false,
+                        })
+                        .collect(),
+                    "0-0",
+                ));
+            }
+        }
+    }
+    brackets
+}
+
+/// Splits players into showdown lobbies of at most [`Lobby::MAX_PLAYERS`] players.
+///
+/// The players are snaked through the lobbies in the given order so that every lobby is equally
+/// strong and lobby sizes differ by at most one.
+pub(super) fn generate_lobbies(players: &[String], tournament_id: i32, round: i32) -> Vec<Lobby> {
+    let lobbies_count = players.len().div_ceil(Lobby::MAX_PLAYERS).max(1);
+    let mut lobbies = vec![Vec::new(); lobbies_count];
+    for (index, discord_id) in players.iter().enumerate() {
+        let column = index % lobbies_count;
+        let lobby = match (index / lobbies_count) % 2 {
+            0 => column,
+            _ => lobbies_count - 1 - column,
+        };
+        lobbies[lobby].push(discord_id.clone());
+    }
+    lobbies
+        .into_iter()
+        .enumerate()
+        .map(|(index, players)| Lobby::new(tournament_id, round, index as i32 + 1, players))
+        .collect()
+}
+
+/// The number of rounds a showdown tournament needs until a single final lobby is left.
+pub(super) fn showdown_rounds(players: usize) -> i32 {
+    let mut remaining = players;
+    let mut rounds = 1;
+    while remaining > Lobby::MAX_PLAYERS {
+        let lobbies_count = remaining.div_ceil(Lobby::MAX_PLAYERS);
+        remaining = (0..lobbies_count)
+            .map(|index| {
+                let size = remaining / lobbies_count + usize::from(index < remaining % lobbies_count);
+                Lobby::advancing(size)
+            })
+            .sum();
+        rounds += 1;
+    }
+    rounds
+}
+
+/// Generates the matches for the next round of a double elimination tournament.
+///
+/// Rounds are shared between the brackets. With `k` winners bracket rounds, winners bracket round
+/// `i` is played in round `i`, losers bracket round `j` is played in round `j + 1`, the grand final
+/// is played in round `2k` and its reset (only needed if the losers bracket champion wins the grand
+/// final) in round `2k + 1`.
+///
+/// Losers of the first winners bracket round are paired against each other. Losers of every later
+/// winners bracket round drop down in reverse order to face the survivors of the losers bracket,
+/// which avoids immediate rematches.
+///
+/// Returns no matches if the tournament is already decided.
+fn generate_next_round_double_elimination(
+    brackets: Vec<Match>,
+    tournament_id: i32,
+    rounds: i32,
+    round: i32,
+) -> Result<Vec<Match>, BotError> {
+    let winners_rounds = (rounds - 1) / 2;
+    let losers_rounds = 2 * (winners_rounds - 1);
+    let brackets: HashMap<(Bracket, i32, i32), Match> = brackets
+        .into_iter()
+        .map(|bracket| Ok(((bracket.bracket()?, bracket.round()?, bracket.sequence()?), bracket)))
+        .collect::<Result<_, BotError>>()?;
+    // The first losers bracket round is played in round 2 and is fed by the losers of the first
+    // round, so a pairing of two byes leaves both of its slots empty
+    let empty_slot_allowed =
+        |bracket: Bracket, round: i32| bracket == Bracket::Losers && round == 2;
+    let decided = |bracket: Bracket, round: i32, sequence: i32| -> Result<Option<&Match>, BotError> {
+        match brackets.get(&(bracket, round, sequence)) {
+            None if empty_slot_allowed(bracket, round) => Ok(None),
+            None => Err(anyhow!(
+                "Error advancing to the next round: Match {} of round {} in the {} of tournament {} is missing",
+                sequence,
+                round,
+                bracket,
+                tournament_id
+            )),
+            Some(m) if m.winner.is_none() && m.is_valid() => Err(anyhow!(
+                "Error advancing to the next round: Match {} does not have a winner yet",
+                m.match_id
+            )),
+            Some(m) => Ok(Some(m)),
+        }
+    };
+    let winner = |bracket: Bracket, round: i32, sequence: i32| -> Result<Option<MatchPlayer>, BotError> {
+        Ok(decided(bracket, round, sequence)?.and_then(|m| m.get_winning_player().cloned()))
+    };
+    let loser = |bracket: Bracket, round: i32, sequence: i32| -> Result<Option<MatchPlayer>, BotError> {
+        Ok(decided(bracket, round, sequence)?.and_then(|m| m.get_losing_player().cloned()))
+    };
+
+    let mut next_round_brackets = Vec::new();
+    // A match with a single player is a bye
+    let mut push = |bracket: Bracket,
+                    sequence: i32,
+                    players: Vec<Option<MatchPlayer>>|
+     -> Result<(), BotError> {
+        let players: Vec<MatchPlayer> = players.into_iter().flatten().collect();
+        if players.is_empty() {
+            if empty_slot_allowed(bracket, round) {
+                return Ok(());
+            }
+            return Err(anyhow!(
+                "Error advancing to the next round: Match {} of round {} in the {} of tournament {} has no players",
+                sequence,
+                round,
+                bracket,
+                tournament_id
+            ));
+        }
+        next_round_brackets.push(Match::new_in_bracket(
+            tournament_id,
+            round,
+            sequence,
+            bracket,
+            players,
+            "0-0",
+        ));
+        Ok(())
+    };
+
+    // Winners bracket
+    if (2..=winners_rounds).contains(&round) {
+        let count = 1 << (winners_rounds - round);
+        for sequence in 1..=count {
+            push(
+                Bracket::Winners,
+                sequence,
+                vec![
+                    winner(Bracket::Winners, round - 1, 2 * sequence - 1)?,
+                    winner(Bracket::Winners, round - 1, 2 * sequence)?,
+                ],
+            )?;
+        }
+    }
+
+    // Losers bracket
+    let losers_round = round - 1;
+    if (1..=losers_rounds).contains(&losers_round) {
+        if losers_round == 1 {
+            let count = 1 << (winners_rounds - 2);
+            for sequence in 1..=count {
+                push(
+                    Bracket::Losers,
+                    sequence,
+                    vec![
+                        loser(Bracket::Winners, 1, 2 * sequence - 1)?,
+                        loser(Bracket::Winners, 1, 2 * sequence)?,
+                    ],
+                )?;
+            }
+        } else if losers_round % 2 == 0 {
+            // Players dropping down from the winners bracket
+            let dropped_round = losers_round / 2 + 1;
+            let count = 1 << (winners_rounds - dropped_round);
+            for sequence in 1..=count {
+                push(
+                    Bracket::Losers,
+                    sequence,
+                    vec![
+                        winner(Bracket::Losers, round - 1, sequence)?,
+                        loser(Bracket::Winners, dropped_round, count - sequence + 1)?,
+                    ],
+                )?;
+            }
+        } else {
+            let count = 1 << (winners_rounds - (losers_round + 1) / 2 - 1);
+            for sequence in 1..=count {
+                push(
+                    Bracket::Losers,
+                    sequence,
+                    vec![
+                        winner(Bracket::Losers, round - 1, 2 * sequence - 1)?,
+                        winner(Bracket::Losers, round - 1, 2 * sequence)?,
+                    ],
+                )?;
+            }
+        }
+    }
+
+    // Grand final and its reset
+    if round == 2 * winners_rounds {
+        let losers_champion = if winners_rounds == 1 {
+            loser(Bracket::Winners, 1, 1)?
+        } else {
+            winner(Bracket::Losers, round - 1, 1)?
+        };
+        push(
+            Bracket::GrandFinal,
+            1,
+            vec![winner(Bracket::Winners, winners_rounds, 1)?, losers_champion],
+        )?;
+    } else if round == 2 * winners_rounds + 1 {
+        let winners_champion = winner(Bracket::Winners, winners_rounds, 1)?;
+        let grand_final = decided(Bracket::GrandFinal, round - 1, 1)?.ok_or(anyhow!(
+            "Error advancing to the next round: Unable to find the grand final of tournament {}",
+            tournament_id
+        ))?;
+        // The winners bracket champion has not lost yet, so the grand final is only replayed if
+        // they lose it
+        if grand_final.winner != winners_champion.map(|p| p.discord_id) {
+            push(
+                Bracket::GrandFinal,
+                1,
+                grand_final.match_players.iter().cloned().map(Some).collect(),
+            )?;
+        }
+    }
+
+    Ok(next_round_brackets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{
+        generate_lobbies, generate_next_round, generate_next_round_double_elimination,
+        generate_round_robin_round, generate_swiss_round, group_stage_rounds, later_matches_with,
+        parse_score, showdown_rounds, Bracket, LobbyStanding, Match, MatchPlayer, PlayerType,
+        Standing,
+    };
+    use crate::database::models::LobbyResult;
+
+    fn create_match(round: i32, sequence: i32, bracket: Bracket, players: &[&str]) -> Match {
+        let players = players
+            .iter()
+            .map(|id| MatchPlayer {
+                match_id: String::new(),
+                discord_id: id.to_string(),
+                player_type: PlayerType::Player,
+                ready: false,
+            })
+            .collect();
+        Match::new_in_bracket(1, round, sequence, bracket, players, "0-0")
+    }
+
+    fn with_winner(mut bracket: Match, winner: &str) -> Match {
+        bracket.winner = Some(winner.to_string());
+        bracket
+    }
+
+    fn player_ids(bracket: &Match) -> Vec<&str> {
+        bracket
+            .match_players
+            .iter()
+            .map(|p| p.discord_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn double_elimination_four_players() {
+        // 2 winners bracket rounds, 2 losers bracket rounds, the grand final and its reset
+        const ROUNDS: i32 = 5;
+        let mut matches = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            with_winner(create_match(1, 2, Bracket::Winners, &["2", "3"]), "2"),
+        ];
+
+        let round_2 = generate_next_round_double_elimination(matches.clone(), 1, ROUNDS, 2).unwrap();
+        assert_eq!(round_2.len(), 2);
+        assert_eq!(round_2[0].bracket().unwrap(), Bracket::Winners);
+        assert_eq!(player_ids(&round_2[0]), vec!["0", "2"]);
+        assert_eq!(round_2[1].bracket().unwrap(), Bracket::Losers);
+        assert_eq!(player_ids(&round_2[1]), vec!["1", "3"]);
+        matches.push(with_winner(round_2[0].clone(), "0"));
+        matches.push(with_winner(round_2[1].clone(), "1"));
+
+        let round_3 = generate_next_round_double_elimination(matches.clone(), 1, ROUNDS, 3).unwrap();
+        assert_eq!(round_3.len(), 1);
+        assert_eq!(round_3[0].bracket().unwrap(), Bracket::Losers);
+        assert_eq!(player_ids(&round_3[0]), vec!["1", "2"]);
+        matches.push(with_winner(round_3[0].clone(), "2"));
+
+        let round_4 = generate_next_round_double_elimination(matches.clone(), 1, ROUNDS, 4).unwrap();
+        assert_eq!(round_4.len(), 1);
+        assert_eq!(round_4[0].bracket().unwrap(), Bracket::GrandFinal);
+        assert_eq!(player_ids(&round_4[0]), vec!["0", "2"]);
+
+        // The winners bracket champion wins the grand final, so there is no reset
+        let mut no_reset = matches.clone();
+        no_reset.push(with_winner(round_4[0].clone(), "0"));
+        let round_5 = generate_next_round_double_elimination(no_reset, 1, ROUNDS, 5).unwrap();
+        assert!(round_5.is_empty());
+
+        // The losers bracket champion wins the grand final, so it is played again
+        matches.push(with_winner(round_4[0].clone(), "2"));
+        let round_5 = generate_next_round_double_elimination(matches, 1, ROUNDS, 5).unwrap();
+        assert_eq!(round_5.len(), 1);
+        assert_eq!(round_5[0].bracket().unwrap(), Bracket::GrandFinal);
+        assert_eq!(round_5[0].round().unwrap(), 5);
+    }
+
+    #[test]
+    fn double_elimination_byes_leave_only_first_losers_round_slots_empty() {
+        // 5 players in an 8 player bracket, so 3 of the 4 first round matches are byes
+        const ROUNDS: i32 = 7;
+        let mut matches = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            create_match(1, 2, Bracket::Winners, &["2"]),
+            create_match(1, 3, Bracket::Winners, &["3"]),
+            create_match(1, 4, Bracket::Winners, &["4"]),
+        ];
+        for bracket in matches.iter_mut().skip(1) {
+            bracket.winner = Some(bracket.match_players[0].discord_id.clone());
+        }
+
+        let round_2 = generate_next_round_double_elimination(matches.clone(), 1, ROUNDS, 2).unwrap();
+        let losers: Vec<&Match> = round_2
+            .iter()
+            .filter(|b| b.bracket().unwrap() == Bracket::Losers)
+            .collect();
+        // The losers of two byes have nobody to play, so that match is left out
+        assert_eq!(losers.len(), 1);
+        assert_eq!(player_ids(losers[0]), vec!["1"]);
+
+        // Any other missing match is an error rather than an empty slot
+        matches.remove(1);
+        assert!(generate_next_round_double_elimination(matches, 1, ROUNDS, 2).is_err());
+    }
+
+    #[test]
+    fn double_elimination_requires_finished_matches() {
+        let matches = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            create_match(1, 2, Bracket::Winners, &["2", "3"]),
+        ];
+
+        assert!(generate_next_round_double_elimination(matches, 1, 5, 2).is_err());
+    }
+
+    fn discord_ids(count: i32) -> Vec<String> {
+        (0..count).map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn swiss_gives_bye_to_lowest_ranked_player() {
+        let standings = Standing::calculate(1, &discord_ids(5), &[]);
+        let matches = generate_swiss_round(&standings, &[], 1, 1).unwrap();
+
+        assert_eq!(matches.len(), 3);
+        assert_eq!(player_ids(&matches[0]), vec!["0", "1"]);
+        assert_eq!(player_ids(&matches[1]), vec!["2", "3"]);
+        assert_eq!(player_ids(&matches[2]), vec!["4"]);
+        assert_eq!(matches[2].winner.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn swiss_pairs_equal_records_without_rematches() {
+        let round_1 = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            with_winner(create_match(1, 2, Bracket::Winners, &["2", "3"]), "2"),
+            with_winner(create_match(1, 3, Bracket::Winners, &["4", "5"]), "4"),
+            with_winner(create_match(1, 4, Bracket::Winners, &["6", "7"]), "6"),
+        ];
+        let standings = Standing::calculate(1, &discord_ids(8), &round_1);
+        let round_2 = generate_swiss_round(&standings, &round_1, 1, 2).unwrap();
+
+        assert_eq!(round_2.len(), 4);
+        for bracket in &round_2 {
+            let players = player_ids(bracket);
+            let wins: Vec<i32> = players
+                .iter()
+                .map(|id| standings.iter().find(|s| s.discord_id == *id).unwrap().wins)
+                .collect();
+            assert_eq!(wins[0], wins[1]);
+            assert!(!round_1.iter().any(|m| {
+                let previous = player_ids(m);
+                previous.contains(&players[0]) && previous.contains(&players[1])
+            }));
+        }
+    }
+
+    #[test]
+    fn swiss_settles_for_the_fewest_rematches() {
+        // Player 0 has already faced everyone else
+        let matches = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            with_winner(create_match(2, 1, Bracket::Winners, &["0", "2"]), "0"),
+            with_winner(create_match(3, 1, Bracket::Winners, &["0", "3"]), "0"),
+        ];
+        let standings = Standing::calculate(1, &discord_ids(4), &matches);
+        let round_4 = generate_swiss_round(&standings, &matches, 1, 4).unwrap();
+
+        assert_eq!(round_4.len(), 2);
+        let rematches = round_4
+            .iter()
+            .filter(|bracket| {
+                let players = player_ids(bracket);
+                matches.iter().any(|m| {
+                    let previous = player_ids(m);
+                    previous.contains(&players[0]) && previous.contains(&players[1])
+                })
+            })
+            .count();
+        assert_eq!(rematches, 1);
+    }
+
+    #[test]
+    fn swiss_standings_use_tiebreakers() {
+        let matches = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["0", "1"]), "0"),
+            with_winner(create_match(1, 2, Bracket::Winners, &["2", "3"]), "2"),
+            with_winner(create_match(2, 1, Bracket::Winners, &["0", "2"]), "0"),
+            with_winner(create_match(2, 2, Bracket::Winners, &["1", "3"]), "3"),
+        ];
+        let standings = Standing::calculate(1, &discord_ids(4), &matches);
+        let order: Vec<&str> = standings.iter().map(|s| s.discord_id.as_str()).collect();
+
+        // Players 2 and 3 are tied on wins, but player 2 faced the stronger opponents
+        assert_eq!(order, vec!["0", "2", "3", "1"]);
+        assert_eq!(standings[1].buchholz, 3);
+        assert_eq!(standings[2].buchholz, 1);
+        assert!(standings[1].opponent_win_percentage > standings[2].opponent_win_percentage);
+    }
+
+    #[test]
+    fn round_robin_plays_everyone_once() {
+        let groups = vec![discord_ids(4), (4..9).map(|id| id.to_string()).collect()];
+        let rounds = group_stage_rounds(&groups);
+        assert_eq!(rounds, 5);
+
+        let mut pairs = HashSet::new();
+        for round in 1..=rounds {
+            let matches = generate_round_robin_round(&groups, 1, round);
+            let mut seen = HashSet::new();
+            for bracket in &matches {
+                assert_eq!(bracket.bracket().unwrap(), Bracket::Group);
+                let mut players = player_ids(bracket);
+                assert!(players.iter().all(|id| seen.insert(id.to_string())));
+                players.sort();
+                assert!(pairs.insert((players[0].to_string(), players[1].to_string())));
+            }
+        }
+        // The group of 4 finishes after 3 rounds without any rematches
+        assert_eq!(pairs.len(), 6 + 10);
+    }
+
+    #[test]
+    fn showdown_lobbies_are_balanced() {
+        let players: Vec<String> = (1..=23).map(|i| i.to_string()).collect();
+        let lobbies = generate_lobbies(&players, 1, 1);
+        let sizes: Vec<usize> = lobbies.iter().map(|lobby| lobby.players.len()).collect();
+        assert_eq!(sizes, vec![7, 8, 8]);
+        assert_eq!(lobbies[0].lobby_id, "1.1.1");
+        // Snake seeding: the top seeds are spread across the lobbies
+        assert_eq!(lobbies[0].players[..2], ["1".to_string(), "6".to_string()]);
+        assert_eq!(lobbies[2].players[..2], ["3".to_string(), "4".to_string()]);
+
+        assert_eq!(showdown_rounds(10), 1);
+        // 23 players: 3 lobbies -> 4 + 4 + 4 = 12 players -> 2 lobbies -> 6 players in the final
+        assert_eq!(showdown_rounds(23), 3);
+    }
+
+    #[test]
+    fn showdown_lobby_standings_use_points_then_best_placement() {
+        let result = |game_number: i32, discord_id: &str, placement: i32, points: i32| LobbyResult {
+            lobby_id: "1.1.1".to_string(),
+            game_number,
+            discord_id: discord_id.to_string(),
+            placement,
+            points,
+            battle_time: game_number as i64,
+        };
+        let players = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let results = vec![
+            result(1, "a", 1, 10),
+            result(1, "b", 2, 8),
+            result(1, "c", 3, 7),
+            result(2, "c", 1, 10),
+            result(2, "b", 2, 8),
+            result(2, "a", 3, 7),
+            result(3, "c", 1, 10),
+            result(3, "b", 2, 8),
+            result(3, "a", 3, 7),
+        ];
+        let standings = LobbyStanding::calculate(&players, &results);
+        let order: Vec<&str> = standings.iter().map(|s| s.discord_id.as_str()).collect();
+        // a and b are tied on 24 points, but only a has won a game
+        assert_eq!(order, vec!["c", "a", "b"]);
+        assert_eq!(standings[0].points, 27);
+        assert_eq!(standings[2].best_placement, 2);
+    }
+
+    #[test]
+    fn semifinal_losers_play_for_third_place() {
+        let semifinals = vec![
+            with_winner(create_match(2, 1, Bracket::Winners, &["1", "4"]), "1"),
+            with_winner(create_match(2, 2, Bracket::Winners, &["2", "3"]), "3"),
+        ];
+        let matches = generate_next_round(semifinals, 3).unwrap();
+        let third_place = matches
+            .iter()
+            .find(|m| m.bracket().unwrap() == Bracket::ThirdPlace)
+            .unwrap();
+        assert_eq!(third_place.match_id, "1.3.1.3P");
+        assert_eq!(player_ids(third_place), vec!["4", "2"]);
+        let final_match = matches
+            .iter()
+            .find(|m| m.bracket().unwrap() == Bracket::Winners)
+            .unwrap();
+        assert_eq!(player_ids(final_match), vec!["1", "3"]);
+
+        // Earlier rounds and semifinals decided by a bye have no third place match
+        let quarterfinals = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["1", "8"]), "1"),
+            with_winner(create_match(1, 2, Bracket::Winners, &["4", "5"]), "4"),
+            with_winner(create_match(1, 3, Bracket::Winners, &["2", "7"]), "2"),
+            with_winner(create_match(1, 4, Bracket::Winners, &["3", "6"]), "3"),
+        ];
+        assert_eq!(generate_next_round(quarterfinals, 2).unwrap().len(), 2);
+        let semifinals = vec![
+            with_winner(create_match(1, 1, Bracket::Winners, &["1"]), "1"),
+            with_winner(create_match(1, 2, Bracket::Winners, &["2", "3"]), "2"),
+        ];
+        assert_eq!(generate_next_round(semifinals, 2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn marshal_scores_count_from_the_winner() {
+        assert_eq!(parse_score("2-1").unwrap(), "2-1");
+        assert_eq!(parse_score(" 3 - 0 ").unwrap(), "3-0");
+        assert!(parse_score("1-2").is_err());
+        assert!(parse_score("1-1").is_err());
+        assert!(parse_score("2:1").is_err());
+        assert!(parse_score("two-one").is_err());
+    }
+
+    #[test]
+    fn corrections_flow_to_the_later_matches_of_either_player() {
+        let semifinal = with_winner(create_match(1, 1, Bracket::Winners, &["a", "b"]), "a");
+        let brackets = vec![
+            semifinal.clone(),
+            with_winner(create_match(1, 2, Bracket::Winners, &["c", "d"]), "c"),
+            create_match(2, 1, Bracket::ThirdPlace, &["b", "d"]),
+            create_match(2, 2, Bracket::Winners, &["a", "c"]),
+        ];
+        let later: Vec<&str> = later_matches_with(&semifinal, &brackets, ["a", "b"])
+            .unwrap()
+            .into_iter()
+            .map(|bracket| bracket.match_id.as_str())
+            .collect();
+        assert_eq!(later, vec!["1.2.1.3P", "1.2.2"]);
+    }
+}
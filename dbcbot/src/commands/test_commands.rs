@@ -275,7 +275,7 @@ async fn profile_image(
 async fn choose_brawler_command(ctx: BotContext<'_>) -> Result<(), BotError> {
     ctx.defer().await?;
     let msg = ctx.reply("Choose a brawler").await?;
-    let brawler = ctx.brawler_selection(&msg).await?;
+    let brawler = ctx.brawler_selection(&msg, None).await?;
     ctx.say(format!("You chose {}", brawler.name)).await?;
     Ok(())
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::i64;
 
 use anyhow::anyhow;
@@ -9,27 +10,38 @@ use serde_json::json;
 use tokio::join;
 use tracing::{info, instrument};
 use crate::database::models::{
-    BattleRecord, BattleResult, BattleType, Match, Player, TournamentStatus,
+    BattleRecord, BattleResult, BattleType, BrawlMap, Bracket, DisputeStatus, Lobby, LobbyResult,
+    LobbyStanding, Match, MatchBan, Mode, Player, ScheduledMap, Team, TournamentFormat,
+    TournamentStatus,
 };
 use crate::database::{
-    ConfigDatabase, MatchDatabase, TournamentDatabase, UserDatabase,
+    ConfigDatabase, LobbyDatabase, MatchDatabase, PgDatabase, TeamDatabase, TournamentDatabase,
+    UserDatabase,
 };
 
-use crate::api::{images::ImagesAPI, official_brawl_stars::BattleLogItem};
+use crate::api::{
+    images::ImagesAPI,
+    official_brawl_stars::{Battle, BattleLogItem, Brawler, TeamPlayer},
+};
 use crate::{api::APIResult, commands::checks::is_config_set};
 
 use crate::commands::checks::is_tournament_paused;
 
 use crate::database::models::Tournament;
 use crate::log::{self, Log};
-use crate::mail::MailBotCtx;
-use crate::utils::discord::{modal, select_options};
-use crate::utils::shorthand::BotContextExt;
+use crate::mail::{model::Mail, MailBotCtx, MailDatabase};
+use crate::utils::discord::{modal, select_options, select_user};
+use crate::utils::shorthand::{update_standings, BotContextExt};
+use crate::utils::time::parse_future_time;
 
 use crate::{BotContext, BotData, BotError};
 
+use super::marshal_commands::{auto_advance, dispute_embed, disputed_matches, DISPUTE_WINDOW};
 use super::CommandsContainer;
 
+/// The maximum number of players in a team.
+const MAX_TEAM_SIZE: usize = 3;
+
 /// CommandsContainer for the User commands
 pub struct UserCommands;
 
@@ -38,7 +50,7 @@ impl CommandsContainer for UserCommands {
     type Error = BotError;
 
     fn get_all() -> Vec<poise::Command<Self::Data, Self::Error>> {
-        vec![menu(), credit()]
+        vec![menu(), dispute_evidence(), credit()]
     }
 }
 
@@ -96,9 +108,25 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
             &ctx.author().id.to_string(),
         )
         .await?;
+    let mut waitlisted = vec![];
+    for tournament_id in ctx
+        .data()
+        .database
+        .get_waitlisted_tournaments(&ctx.author().id.to_string())
+        .await?
+    {
+        if let Some(tournament) = ctx
+            .data()
+            .database
+            .get_tournament(&ctx.guild_id().unwrap().to_string(), tournament_id)
+            .await?
+        {
+            waitlisted.push(tournament);
+        }
+    }
 
     if player_active_tournaments.is_empty() {
-        let buttons = vec![
+        let mut buttons = vec![
             CreateButton::new("menu_tournaments")
                 .label("Tournaments")
                 .style(ButtonStyle::Primary),
@@ -112,14 +140,49 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
                 .label("Mail")
                 .emoji(ReactionType::Unicode("📧".to_string()))
                 .style(ButtonStyle::Primary),
+            CreateButton::new("team")
+                .label("Team")
+                .style(ButtonStyle::Primary),
         ];
+        let mut description = "Welcome to the menu! You have not joined a tournament yet. Click on the Tournaments button to join one now!".to_string();
+        for tournament in &waitlisted {
+            let waitlist = ctx
+                .data()
+                .database
+                .get_waitlist(tournament.tournament_id)
+                .await?;
+            let position = waitlist
+                .iter()
+                .position(|id| *id == ctx.author().id.to_string())
+                .unwrap_or_default()
+                + 1;
+            description.push_str(&format!(
+                "\n\nYou are on the waitlist of {} at position {}.",
+                tournament.name, position
+            ));
+        }
+        if !waitlisted.is_empty() {
+            buttons.push(
+                CreateButton::new("leave_waitlist")
+                    .label("Leave Waitlist")
+                    .style(ButtonStyle::Danger),
+            );
+        }
         ctx.prompt(
             msg,
-            CreateEmbed::new().title("Main Menu").description("Welcome to the menu! You have not joined a tournament yet. Click on the Tournaments button to join one now!").color(Color::BLUE),
+            CreateEmbed::new().title("Main Menu").description(description).color(Color::BLUE),
             buttons
         ).await?;
     } else if player_active_tournaments.len() == 1 {
-        let embed = CreateEmbed::new()
+        let tournament = &player_active_tournaments[0];
+        let needs_check_in = tournament.check_in_open(ctx.now().timestamp())
+            && !ctx
+                .data()
+                .database
+                .get_checked_in_players(tournament.tournament_id)
+                .await?
+                .contains(&ctx.author().id.to_string());
+        let mut embed = CreateEmbed::new()
             .title("Main Menu")
             .description("You're already in a tournament. Good luck!")
             .fields(vec![
@@ -144,10 +207,27 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
                     false,
                 ),
             ]);
-        let buttons = vec![
-            CreateButton::new("menu_match")
+        // Matches are not drawn before the tournament starts, so the check-in takes their place
+        let first_button = match needs_check_in {
+            true => {
+                embed = embed.field(
+                    "Check-in",
+                    format!(
+                        "Check in before <t:{}:R> or you will be removed from the tournament!",
+                        tournament.check_in_end.unwrap_or_default()
+                    ),
+                    false,
+                );
+                CreateButton::new("check_in")
+                    .label("Check In")
+                    .style(ButtonStyle::Success)
+            }
+            false => CreateButton::new("menu_match")
                 .label("View Match")
                 .style(ButtonStyle::Primary),
+        };
+        let buttons = vec![
+            first_button,
             CreateButton::new("leave_tournament")
                 .label("Leave Tournament")
                 .style(ButtonStyle::Danger),
@@ -157,6 +237,9 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
             CreateButton::new("submit")
                 .label("Submit")
                 .style(ButtonStyle::Primary),
+            CreateButton::new("team")
+                .label("Team")
+                .style(ButtonStyle::Primary),
         ];
         ctx.prompt(msg, embed, buttons).await?;
     } else {
@@ -202,20 +285,35 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
                 )
                 .await?;
 
-                return user_display_match(ctx, msg, player_active_tournaments.remove(0)).await;
+                let tournament = player_active_tournaments.remove(0);
+                if tournament.format == TournamentFormat::Showdown {
+                    return user_display_lobby(ctx, msg, &tournament).await;
+                }
+                return user_display_match(ctx, msg, tournament).await;
+            }
+            "check_in" => {
+                interaction.defer(ctx.http()).await?;
+                return check_in(ctx, msg, &player_active_tournaments[0]).await;
             }
             "leave_tournament" => {
                 interaction.defer(ctx.http()).await?;
                 return leave_tournament(ctx, msg).await;
             }
+            "leave_waitlist" => {
+                interaction.defer(ctx.http()).await?;
+                return leave_waitlist(ctx, msg, &waitlisted).await;
+            }
             "submit" => {
                 interaction.defer(ctx.http()).await?;
+                if player_active_tournaments[0].format == TournamentFormat::Showdown {
+                    return submit_lobby(ctx, msg, &player_active_tournaments[0]).await;
+                }
                 let game_match = ctx
                     .data()
                     .database
                     .get_match_by_player(
                         player_active_tournaments[0].tournament_id,
-                        &ctx.author().to_string(),
+                        &ctx.author().id.to_string(),
                     )
                     .await?;
                 return submit(
@@ -232,6 +330,10 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
                     msg.delete(*ctx).await?;
                 }
             }
+            "team" => {
+                interaction.defer(ctx.http()).await?;
+                return user_display_team(ctx, msg).await;
+            }
             _ => {
                 continue;
             }
@@ -241,6 +343,54 @@ async fn user_display_menu(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Resul
     Ok(())
 }
 
+/// Confirm that the user will show up for the tournament they registered for.
+async fn check_in(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<(), BotError> {
+    if !tournament.check_in_open(ctx.now().timestamp()) {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Check-in closed")
+                .description("The check-in for this tournament is no longer open.")
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.data()
+        .database
+        .check_in(tournament.tournament_id, &ctx.author().id.to_string())
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Checked in!")
+            .description(format!(
+                "You have checked in for {}. Good luck!",
+                tournament.name
+            ))
+            .color(Color::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Player checked in",
+        format!(
+            "User {} has checked in for tournament {}",
+            ctx.author().name,
+            tournament.tournament_id
+        ),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    Ok(())
+}
+
 /// Display match information to the user.
 #[instrument(skip(msg))]
 async fn user_display_match(
@@ -250,6 +400,10 @@ async fn user_display_match(
 ) -> Result<(), BotError> {
     info!("User {} is viewing their current match", ctx.author().name);
 
+    let disputable = disputable_match(ctx, &tournament).await?;
+    let dispute_button = CreateButton::new("match_menu_dispute")
+        .label("Dispute Result")
+        .style(ButtonStyle::Danger);
     let current_match = match ctx
         .data()
         .database
@@ -258,14 +412,36 @@ async fn user_display_match(
     {
         Some(m) => m,
         None => {
+            let embed = CreateEmbed::new().title("Match Not Found").description(
+                "You are not currently in a match. Please wait for the next round to begin.",
+            );
+            let disputable = match disputable {
+                Some(disputable) => disputable,
+                None => {
+                    ctx.prompt(msg, embed, None).await?;
+                    return Ok(());
+                }
+            };
             ctx.prompt(
                 msg,
-                CreateEmbed::new().title("Match Not Found").description(
-                    "You are not currently in a match. Please wait for the next round to begin.",
+                embed.field(
+                    "Last Match",
+                    format!(
+                        "Match {}: <@{}> won {}",
+                        disputable.match_id,
+                        disputable.winner.clone().unwrap_or_default(),
+                        disputable.score
+                    ),
+                    false,
                 ),
-                None,
+                vec![dispute_button],
             )
             .await?;
+            let mut ic = ctx.create_interaction_collector(msg).await?;
+            if let Some(interaction) = ic.next().await {
+                interaction.defer(ctx.http()).await?;
+                return dispute_result(ctx, msg, &tournament, &disputable).await;
+            }
             return Ok(());
         }
     };
@@ -287,6 +463,13 @@ async fn user_display_match(
                 "bye",
             )
             .await?;
+        auto_advance(
+            ctx.http(),
+            &ctx.data().database,
+            &tournament.guild_id,
+            tournament.tournament_id,
+        )
+        .await?;
         ctx.prompt(msg,
             CreateEmbed::new().title("Match Information.")
             .description(
@@ -314,6 +497,12 @@ async fn user_display_match(
         .get_player_from_discord_id(player.discord_id.clone())
         .await?
         .ok_or(anyhow!("Player 2 is not found in the database"))?;
+    let overrides = ctx
+        .data()
+        .database
+        .get_round_wins_required(tournament.tournament_id)
+        .await?;
+    let wins_required = tournament.wins_required_for(&current_match, &overrides)?;
 
     let reply = {
         let image = ctx.data().apis.images.match_image(&p1, &p2).await?;
@@ -327,6 +516,7 @@ async fn user_display_match(
                     ("Tournament", tournament.name.clone(), true),
                     ("Match ID", current_match.match_id.to_owned(), true),
                     ("Round", current_match.round()?.to_string(), true),
+                    ("Wins Required", format!("First to {}", wins_required), true),
                     (
                         "Player 1",
                         format!(
@@ -361,6 +551,60 @@ async fn user_display_match(
                     ),
                 ])
         };
+        let banned = ctx
+            .data()
+            .database
+            .get_banned_brawlers(tournament.tournament_id)
+            .await?;
+        let embed = if banned.is_empty() {
+            embed
+        } else {
+            embed.field("Banned Brawlers", banned.join(", "), false)
+        };
+        let rotation = ctx
+            .data()
+            .database
+            .get_map_rotation(tournament.tournament_id)
+            .await?;
+        let maps = (1..2 * wins_required)
+            .map(|game| tournament.map_for(&current_match, game, &rotation))
+            .collect::<Result<Vec<BrawlMap>, BotError>>()?;
+        let embed = if maps.iter().all(BrawlMap::is_any) {
+            embed
+        } else if maps.iter().all(|map| map.same_as(&maps[0])) {
+            embed.field("Map", maps[0].name.clone(), true)
+        } else {
+            embed.field(
+                "Maps",
+                maps.iter()
+                    .enumerate()
+                    .map(|(index, map)| format!("Game {}: {}", index + 1, map.name))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                false,
+            )
+        };
+        let bans = ctx
+            .data()
+            .database
+            .get_match_bans(&current_match.match_id)
+            .await?;
+        let embed = if bans.is_empty() {
+            embed
+        } else {
+            embed.field("Draft", draft_summary(&bans), false)
+        };
+        let embed = match current_match.end {
+            Some(deadline) => embed.field(
+                "Deadline",
+                format!(
+                    "<t:{}:R>. Get ready before then, or your opponent may win by forfeit!",
+                    deadline
+                ),
+                false,
+            ),
+            None => embed,
+        };
         let buttons = {
             let mut buttons = vec![];
             buttons.push(
@@ -376,6 +620,19 @@ async fn user_display_match(
                         .style(ButtonStyle::Success),
                 );
             }
+            buttons.push(
+                CreateButton::new("match_menu_schedule")
+                    .label("Schedule")
+                    .style(ButtonStyle::Primary),
+            );
+            buttons.push(
+                CreateButton::new("match_menu_draft")
+                    .label("Draft")
+                    .style(ButtonStyle::Primary),
+            );
+            if disputable.is_some() {
+                buttons.push(dispute_button);
+            }
             buttons
         };
         CreateReply::default()
@@ -429,6 +686,20 @@ async fn user_display_match(
                 ctx.compose(msg, p2.user(ctx).await?.id, current_match.match_id.clone())
                     .await?;
             }
+            "match_menu_schedule" => {
+                interaction.defer(ctx.http()).await?;
+                return schedule_match(ctx, msg, &tournament, &current_match).await;
+            }
+            "match_menu_draft" => {
+                interaction.defer(ctx.http()).await?;
+                return draft_match(ctx, msg, &tournament, &current_match).await;
+            }
+            "match_menu_dispute" => {
+                interaction.defer(ctx.http()).await?;
+                if let Some(disputable) = &disputable {
+                    return dispute_result(ctx, msg, &tournament, disputable).await;
+                }
+            }
             _ => {
                 continue;
             }
@@ -437,104 +708,767 @@ async fn user_display_match(
     Ok(())
 }
 
-/// Display all active (and not started) tournaments to the user who has not yet joined a
-/// tournament.
-#[instrument(skip(msg))]
-async fn user_display_tournaments(
+/// Let the players of a match agree on a time to play it.
+///
+/// One player proposes a time, then the opponent either accepts it or proposes another one.
+async fn schedule_match(
     ctx: &BotContext<'_>,
     msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+    current_match: &Match,
 ) -> Result<(), BotError> {
-    info!(
-        "User {} has entered the tournaments menu",
-        ctx.author().name
-    );
-    let guild_id = ctx.guild_id().unwrap().to_string();
-    let tournaments: Vec<Tournament> = ctx
+    let discord_id = ctx.author().id.to_string();
+    let opponent = current_match.get_opponent(&discord_id)?;
+    let schedule = ctx
         .data()
         .database
-        .get_active_tournaments(&guild_id)
-        .await?
-        .into_iter()
-        .filter(|tournament| tournament.status == TournamentStatus::Pending)
-        .collect();
-
-    let mut table = Table::new();
-    table.set_titles(row!["No.", "Name", "Status"]);
-    for (i, tournament) in tournaments.iter().enumerate() {
-        // Add 1 to the loop iteration so that the user-facing tournament numbers start at 1
-        // instead of 0
-        table.add_row(row![
-            i + 1,
-            &tournament.name,
-            &tournament.status.to_string()
-        ]);
-    }
-
-    let selected_tournament = if !tournaments.is_empty() {
-        loop {
-            let selected = select_options(
-                ctx,
-                msg,
-                CreateEmbed::default()
-                    .title("Tournament Enrollment")
-                    .description(
-                        "Here are all the active tournaments in this server.\n\nTo join a tournament, click the button with the number corresponding to the one you wish to join.",
-                    ),
-                    None,
-               &tournaments
-            ).await?;
-            let name = tournaments
-                .iter()
-                .find(|t| t.tournament_id == selected.parse::<i32>().unwrap())
-                .unwrap()
-                .name
-                .clone();
-            let description = format!(
-                r#"Please confirm that you want to participate in the following tournament
-{}"#,
-                name
+        .get_match_schedule(&current_match.match_id)
+        .await?;
+    let mut buttons = vec![];
+    let description = match &schedule {
+        None => "No time has been proposed for this match yet.".to_string(),
+        Some(schedule) if schedule.accepted => format!(
+            "You agreed to play <t:{time}:F> (<t:{time}:R>). You will both be reminded shortly before.",
+            time = schedule.proposed_time
+        ),
+        Some(schedule) if schedule.proposer == discord_id => format!(
+            "You proposed to play <t:{time}:F> (<t:{time}:R>). Waiting for <@{}> to answer.",
+            opponent.discord_id,
+            time = schedule.proposed_time
+        ),
+        Some(schedule) => {
+            buttons.push(
+                CreateButton::new("schedule_accept")
+                    .label("Accept")
+                    .style(ButtonStyle::Success),
             );
-            let embed = CreateEmbed::new()
-                .title("Tournament Enrollment")
-                .description(description);
-            if ctx.confirmation(msg, embed).await? {
-                break selected;
-            }
+            format!(
+                "<@{}> proposed to play <t:{time}:F> (<t:{time}:R>). Accept it or propose another time.",
+                opponent.discord_id,
+                time = schedule.proposed_time
+            )
         }
-    } else {
-        let announcement_channel_id = ctx
-            .data()
-            .database
-            .get_config(&guild_id)
-            .await?
-            .unwrap()
-            .announcement_channel_id;
-        ctx.prompt(
-            msg,
-            CreateEmbed::new()
-                .title("Tournament Enrollment")
-                .description(format!("There are no tournaments currently available. Be sure to check out <#{}> for any new tournaments on the horizon!", announcement_channel_id))
-                .color(Color::RED),
-           None
-        ).await?;
-        return Ok(());
     };
-    match ctx
+    buttons.push(
+        CreateButton::new("schedule_propose")
+            .label(match schedule {
+                None => "Propose a time",
+                Some(_) => "Propose another time",
+            })
+            .style(ButtonStyle::Primary),
+    );
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Match Schedule")
+            .description(description)
+            .color(Color::BLUE),
+        buttons,
+    )
+    .await?;
+    let notification_channel = ChannelId::new(tournament.notification_channel_id.parse()?);
+    let mut ic = ctx.create_interaction_collector(msg).await?;
+    while let Some(interaction) = &ic.next().await {
+        match interaction.data.custom_id.as_str() {
+            "schedule_accept" => {
+                interaction.defer(ctx.http()).await?;
+                let time = schedule.as_ref().map(|s| s.proposed_time).unwrap_or_default();
+                if !ctx
+                    .data()
+                    .database
+                    .accept_match_time(&current_match.match_id, &discord_id, time)
+                    .await?
+                {
+                    ctx.prompt(
+                        msg,
+                        CreateEmbed::new()
+                            .title("Proposal changed")
+                            .description("The proposed time has changed since you opened the schedule. Open it again to see the new proposal.")
+                            .color(Color::RED),
+                        None,
+                    )
+                    .await?;
+                    return Ok(());
+                }
+                notification_channel
+                    .send_message(
+                        ctx,
+                        CreateMessage::default().content(format!(
+                            "<@{}> <@{}>\n\nYou agreed to play match `{}` <t:{time}:F> (<t:{time}:R>). You will both be reminded shortly before it starts.",
+                            opponent.discord_id,
+                            discord_id,
+                            current_match.match_id,
+                            time = time
+                        )),
+                    )
+                    .await?;
+                ctx.prompt(
+                    msg,
+                    CreateEmbed::new()
+                        .title("Time accepted")
+                        .description(format!("See you on the battlefield <t:{}:R>!", time))
+                        .color(Color::DARK_GREEN),
+                    None,
+                )
+                .await?;
+                ctx.log(
+                    "Match time agreed",
+                    format!(
+                        "User {} accepted to play match {} at <t:{}:F>",
+                        ctx.author().name,
+                        current_match.match_id,
+                        time
+                    ),
+                    log::State::SUCCESS,
+                    log::Model::PLAYER,
+                )
+                .await?;
+                return Ok(());
+            }
+            "schedule_propose" => {
+                interaction.defer(ctx.http()).await?;
+                return propose_match_time(ctx, msg, tournament, current_match, &opponent.discord_id)
+                    .await;
+            }
+            _ => {
+                continue;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The last decided match of the player, if they can still dispute its result.
+///
+/// Byes cannot be disputed, and the result of a match can only be disputed once.
+async fn disputable_match(
+    ctx: &BotContext<'_>,
+    tournament: &Tournament,
+) -> Result<Option<Match>, BotError> {
+    if !matches!(
+        tournament.status,
+        TournamentStatus::Started | TournamentStatus::Paused
+    ) {
+        return Ok(None);
+    }
+    let bracket = match ctx
         .data()
         .database
-        .enter_tournament(
-            selected_tournament.parse::<i32>()?,
-            &ctx.author().id.to_string(),
-        )
-        .await
+        .get_last_decided_match(tournament.tournament_id, &ctx.author().id.to_string())
+        .await?
     {
-        Ok(_) => {
-            ctx.log(
-                "Tournament enrollment success",
-                format!(
-                    "User {} has joined tournament {}",
-                    ctx.author().name,
-                    selected_tournament
+        Some(bracket) if bracket.is_valid() && bracket.score != "bye" => bracket,
+        _ => return Ok(None),
+    };
+    let disputes = ctx
+        .data()
+        .database
+        .get_match_disputes(&bracket.match_id)
+        .await?;
+    Ok(disputes.is_empty().then_some(bracket))
+}
+
+/// Let a player dispute the result of their match.
+///
+/// The player explains what went wrong and may link evidence, or upload it afterwards with
+/// `/dispute_evidence`. The marshals are pinged in a thread of the log channel, and the round
+/// cannot advance until one of them reviews the dispute.
+async fn dispute_result(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+    bracket: &Match,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Dispute Result"]
+    struct DisputeStatement {
+        #[name = "What went wrong?"]
+        #[paragraph]
+        #[placeholder = "Explain why the result of the match is wrong"]
+        #[min_length = 10]
+        #[max_length = 1000]
+        statement: String,
+
+        #[name = "Evidence (optional)"]
+        #[paragraph]
+        #[placeholder = "Links to screenshots or videos, one per line"]
+        evidence: Option<String>,
+    }
+    let form = modal::<DisputeStatement>(
+        ctx,
+        msg,
+        CreateEmbed::new()
+            .title("Dispute the result")
+            .description(format!(
+                "Match {}: <@{}> won {}.\nTell the marshals what went wrong. The match is frozen until they review it.",
+                bracket.match_id,
+                bracket.winner.clone().unwrap_or_default(),
+                bracket.score
+            )),
+    )
+    .await?;
+    let evidence = match parse_evidence(form.evidence.as_deref().unwrap_or_default()) {
+        Ok(evidence) => evidence,
+        Err(e) => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Invalid evidence")
+                    .description(e.to_string())
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let discord_id = ctx.author().id.to_string();
+    let dispute_id = match ctx
+        .data()
+        .database
+        .open_dispute(
+            &bracket.match_id,
+            &discord_id,
+            &form.statement,
+            &evidence,
+            ctx.now().timestamp(),
+        )
+        .await?
+    {
+        Some(dispute_id) => dispute_id,
+        None => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Already disputed")
+                    .description("The result of this match has already been disputed. The marshals will review it.")
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let dispute = ctx
+        .data()
+        .database
+        .get_dispute(dispute_id)
+        .await?
+        .ok_or(anyhow!("Dispute {} was not found", dispute_id))?;
+
+    let marshal = ctx
+        .data()
+        .database
+        .get_marshal_role(&tournament.guild_id)
+        .await?;
+    let thread = CreateThread::new(format!("Dispute {}", dispute_id))
+        .kind(ChannelType::PublicThread)
+        .auto_archive_duration(AutoArchiveDuration::OneDay);
+    let thread = ctx
+        .get_log_channel()
+        .await?
+        .create_thread(ctx.http(), thread)
+        .await?;
+    thread
+        .send_message(
+            ctx.http(),
+            CreateMessage::new()
+                .content(marshal.map_or_else(String::new, |role| role.mention().to_string()))
+                .embed(
+                    dispute_embed(&dispute, bracket)
+                        .field(
+                            "Tournament",
+                            format!("{} ({})", tournament.name, tournament.tournament_id),
+                            false,
+                        )
+                        .footer(CreateEmbedFooter::new(format!(
+                            "Review it with /review_dispute {}",
+                            dispute_id
+                        ))),
+                ),
+        )
+        .await?;
+    ctx.data()
+        .database
+        .set_dispute_thread(dispute_id, &thread.id.to_string())
+        .await?;
+
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Your dispute has been sent!")
+            .description(format!("The marshals have been notified. The match is frozen until they review it, and you will be notified of their decision.\n\nYou can add screenshots or videos with `/dispute_evidence {}`.", dispute_id))
+            .color(Color::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Result disputed",
+        format!(
+            "Dispute ID: {}\nMatch ID: {}\nTournament: {} ({})\nDisputed by: {}",
+            dispute_id,
+            bracket.match_id,
+            tournament.name,
+            tournament.tournament_id,
+            ctx.author().name
+        ),
+        log::State::WARNING,
+        log::Model::PLAYER,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Reads the evidence links of a dispute, one per line. Blank lines are ignored.
+fn parse_evidence(input: &str) -> Result<Vec<String>, BotError> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.starts_with("https://") || line.starts_with("http://") {
+            true => Ok(line.to_string()),
+            false => Err(anyhow!(
+                "`{}` is not a link. Add one link to a screenshot or video per line.",
+                line
+            )),
+        })
+        .collect()
+}
+
+/// Let the players of a match take turns banning brawlers before they play.
+///
+/// The brawlers to choose from are the ones owned by either player that are not banned yet.
+async fn draft_match(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+    current_match: &Match,
+) -> Result<(), BotError> {
+    let discord_id = ctx.author().id.to_string();
+    let opponent = current_match.get_opponent(&discord_id)?;
+    let mut bans = ctx
+        .data()
+        .database
+        .get_match_bans(&current_match.match_id)
+        .await?;
+    let embed = CreateEmbed::new().title("Brawler Draft").color(Color::BLUE);
+    match current_match.draft_turn(&bans) {
+        Some(player) if player.discord_id == discord_id => {
+            ctx.prompt(
+                msg,
+                embed.description(format!(
+                    "It is your turn to ban a brawler. Each player bans {} brawlers.\n\n{}",
+                    Match::DRAFT_BANS_PER_PLAYER,
+                    draft_summary(&bans)
+                )),
+                vec![CreateButton::new("draft_ban")
+                    .label("Ban a brawler")
+                    .style(ButtonStyle::Danger)],
+            )
+            .await?;
+        }
+        Some(_) => {
+            return ctx
+                .prompt(
+                    msg,
+                    embed.description(format!(
+                        "Waiting for <@{}> to ban a brawler.\n\n{}",
+                        opponent.discord_id,
+                        draft_summary(&bans)
+                    )),
+                    None,
+                )
+                .await;
+        }
+        None => {
+            return ctx
+                .prompt(
+                    msg,
+                    embed.description(format!(
+                        "The draft is over. These brawlers cannot be played in this match:\n{}",
+                        draft_summary(&bans)
+                    )),
+                    None,
+                )
+                .await;
+        }
+    }
+    let mut ic = ctx.create_interaction_collector(msg).await?;
+    match ic.next().await {
+        Some(interaction) if interaction.data.custom_id == "draft_ban" => {
+            interaction.defer(ctx.http()).await?;
+        }
+        _ => return Ok(()),
+    }
+
+    let banned = ctx
+        .data()
+        .database
+        .get_banned_brawlers(tournament.tournament_id)
+        .await?
+        .into_iter()
+        .chain(bans.iter().map(|ban| ban.brawler.clone()))
+        .collect::<Vec<String>>();
+    let mut pool: Vec<Brawler> = vec![];
+    for player in &current_match.match_players {
+        let owned = ctx
+            .get_player_from_discord_id(player.discord_id.clone())
+            .await?
+            .ok_or(anyhow!("Player not found in the database"))?
+            .brawlers();
+        for brawler in owned {
+            if !pool.iter().any(|b| b.id == brawler.id)
+                && !banned.iter().any(|b| b.eq_ignore_ascii_case(&brawler.name))
+            {
+                pool.push(brawler);
+            }
+        }
+    }
+    if pool.is_empty() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("No brawlers to ban")
+                .description("Neither of you owns a brawler that can still be banned. Please ask a marshal for help.")
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let brawler = ctx.brawler_selection(msg, Some(&pool)).await?;
+    let turn = bans.len() as i32;
+    if !ctx
+        .data()
+        .database
+        .add_match_ban(&current_match.match_id, turn, &discord_id, &brawler.name)
+        .await?
+    {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Draft changed")
+                .description("The draft changed while you were choosing. Please open the draft again.")
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    bans.push(MatchBan {
+        match_id: current_match.match_id.clone(),
+        turn,
+        discord_id: discord_id.clone(),
+        brawler: brawler.name.clone(),
+    });
+
+    let notification_channel = ChannelId::new(tournament.notification_channel_id.parse()?);
+    match current_match.draft_turn(&bans) {
+        Some(next) => {
+            notification_channel
+                .send_message(
+                    ctx,
+                    CreateMessage::default().content(format!(
+                        "<@{}>\n\n<@{}> banned **{}** in match `{}`. It is your turn to ban a brawler from `/menu`.",
+                        next.discord_id, discord_id, brawler.name, current_match.match_id
+                    )),
+                )
+                .await?;
+            ctx.log(
+                "Brawler banned in draft",
+                format!(
+                    "User {} banned {} in match {}",
+                    ctx.author().name,
+                    brawler.name,
+                    current_match.match_id
+                ),
+                log::State::INFO,
+                log::Model::PLAYER,
+            )
+            .await?;
+        }
+        None => {
+            notification_channel
+                .send_message(
+                    ctx,
+                    CreateMessage::default().content(format!(
+                        "<@{}> <@{}>\n\nThe draft of match `{}` is over. These brawlers cannot be played:\n{}",
+                        discord_id,
+                        opponent.discord_id,
+                        current_match.match_id,
+                        draft_summary(&bans)
+                    )),
+                )
+                .await?;
+            ctx.log(
+                "Draft complete",
+                format!(
+                    "Match ID: {}\nTournament: {} ({})\nBans:\n{}",
+                    current_match.match_id,
+                    tournament.name,
+                    tournament.tournament_id,
+                    draft_summary(&bans)
+                ),
+                log::State::SUCCESS,
+                log::Model::MARSHAL,
+            )
+            .await?;
+        }
+    }
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Brawler banned")
+            .description(format!(
+                "**{}** cannot be played in this match.\n\n{}",
+                brawler.name,
+                draft_summary(&bans)
+            ))
+            .color(Color::DARK_GREEN),
+        None,
+    )
+    .await
+}
+
+/// Lists the brawlers banned in the draft of a match, in the order they were banned.
+pub(crate) fn draft_summary(bans: &[MatchBan]) -> String {
+    if bans.is_empty() {
+        return "No brawlers have been banned yet.".to_string();
+    }
+    bans.iter()
+        .map(|ban| {
+            format!(
+                "{}. **{}** by <@{}>",
+                ban.turn + 1,
+                ban.brawler,
+                ban.discord_id
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Propose a time to play a match to the opponent, replacing any earlier proposal.
+async fn propose_match_time(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+    current_match: &Match,
+    opponent_id: &str,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Match Time"]
+    struct MatchTime {
+        #[name = "When do you want to play? (UTC)"]
+        #[placeholder = "e.g. 2026-10-20 18:00"]
+        time: String,
+    }
+    let input = modal::<MatchTime>(
+        ctx,
+        msg,
+        CreateEmbed::new()
+            .title("Propose a time")
+            .description("Enter the time you want to play this match, in UTC."),
+    )
+    .await?;
+    let time = match parse_future_time(&input.time, ctx.now().timestamp()) {
+        Ok(time) => time,
+        Err(e) => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Invalid time")
+                    .description(e.to_string())
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    ctx.data()
+        .database
+        .propose_match_time(&current_match.match_id, &ctx.author().id.to_string(), time)
+        .await?;
+    ChannelId::new(tournament.notification_channel_id.parse()?)
+        .send_message(
+            ctx,
+            CreateMessage::default().content(format!(
+                "<@{}>\n\n<@{}> proposed to play match `{}` <t:{time}:F> (<t:{time}:R>). View your match from `/menu` to accept it or propose another time.",
+                opponent_id,
+                ctx.author().id,
+                current_match.match_id,
+                time = time
+            )),
+        )
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Time proposed")
+            .description(format!(
+                "Your opponent has been asked to play <t:{time}:F> (<t:{time}:R>).",
+                time = time
+            ))
+            .color(Color::DARK_GREEN),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Match time proposed",
+        format!(
+            "User {} proposed to play match {} at <t:{}:F>",
+            ctx.author().name,
+            current_match.match_id,
+            time
+        ),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Display all active (and not started) tournaments to the user who has not yet joined a
+/// tournament.
+#[instrument(skip(msg))]
+async fn user_display_tournaments(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+) -> Result<(), BotError> {
+    info!(
+        "User {} has entered the tournaments menu",
+        ctx.author().name
+    );
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let tournaments: Vec<Tournament> = ctx
+        .data()
+        .database
+        .get_active_tournaments(&guild_id)
+        .await?
+        .into_iter()
+        .filter(|tournament| tournament.status == TournamentStatus::Pending)
+        .collect();
+
+    let mut table = Table::new();
+    table.set_titles(row!["No.", "Name", "Status"]);
+    for (i, tournament) in tournaments.iter().enumerate() {
+        // Add 1 to the loop iteration so that the user-facing tournament numbers start at 1
+        // instead of 0
+        table.add_row(row![
+            i + 1,
+            &tournament.name,
+            &tournament.status.to_string()
+        ]);
+    }
+
+    let selected_tournament = if !tournaments.is_empty() {
+        loop {
+            let selected = select_options(
+                ctx,
+                msg,
+                CreateEmbed::default()
+                    .title("Tournament Enrollment")
+                    .description(
+                        "Here are all the active tournaments in this server.\n\nTo join a tournament, click the button with the number corresponding to the one you wish to join.",
+                    ),
+                    None,
+               &tournaments
+            ).await?;
+            let tournament = tournaments
+                .iter()
+                .find(|t| t.tournament_id == selected.parse::<i32>().unwrap())
+                .unwrap();
+            let mut description = format!(
+                r#"Please confirm that you want to participate in the following tournament
+{}"#,
+                tournament.name
+            );
+            if let Some(requirements) = ctx
+                .data()
+                .database
+                .get_entry_requirements(tournament.tournament_id)
+                .await?
+                .map(|requirements| requirements.describe())
+                .filter(|requirements| !requirements.is_empty())
+            {
+                description.push_str(&format!(
+                    "\n\n**Entry requirements:**\n{}",
+                    requirements.join("\n")
+                ));
+            }
+            let embed = CreateEmbed::new()
+                .title("Tournament Enrollment")
+                .description(description);
+            if ctx.confirmation(msg, embed).await? {
+                break selected;
+            }
+        }
+    } else {
+        let announcement_channel_id = ctx
+            .data()
+            .database
+            .get_config(&guild_id)
+            .await?
+            .unwrap()
+            .announcement_channel_id;
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Tournament Enrollment")
+                .description(format!("There are no tournaments currently available. Be sure to check out <#{}> for any new tournaments on the horizon!", announcement_channel_id))
+                .color(Color::RED),
+           None
+        ).await?;
+        return Ok(());
+    };
+    let tournament = tournaments
+        .iter()
+        .find(|t| t.tournament_id == selected_tournament.parse::<i32>().unwrap())
+        .unwrap();
+    if !meets_entry_requirements(ctx, msg, tournament).await? {
+        return Ok(());
+    }
+    let participants = ctx
+        .data()
+        .database
+        .get_tournament_players(tournament.tournament_id)
+        .await?
+        .len();
+    if tournament.is_full(participants) {
+        return join_waitlist(ctx, msg, tournament).await;
+    }
+    if tournament.team_size > 1 && !register_team(ctx, msg, tournament).await? {
+        return Ok(());
+    }
+    match ctx
+        .data()
+        .database
+        .enter_tournament(tournament.tournament_id, &ctx.author().id.to_string())
+        .await
+    {
+        // The last spot was taken while the user was enrolling
+        Ok(false) => {
+            if let Some(team) = ctx
+                .data()
+                .database
+                .get_tournament_team(tournament.tournament_id, &ctx.author().id.to_string())
+                .await?
+            {
+                ctx.data()
+                    .database
+                    .unregister_team(tournament.tournament_id, team.team_id)
+                    .await?;
+            }
+            return join_waitlist(ctx, msg, tournament).await;
+        }
+        Ok(true) => {
+            ctx.log(
+                "Tournament enrollment success",
+                format!(
+                    "User {} has joined tournament {}",
+                    ctx.author().name,
+                    selected_tournament
                 ),
                 log::State::SUCCESS,
                 log::Model::TOURNAMENT,
@@ -544,39 +1478,805 @@ async fn user_display_tournaments(
                 msg,
                 CreateEmbed::new()
                     .title("Tournament Enrollment")
-                    .description("You have successfully joined the tournament! Good luck!")
-                    .color(Color::DARK_GREEN),
+                    .description("You have successfully joined the tournament! Good luck!")
+                    .color(Color::DARK_GREEN),
+                None,
+            )
+            .await?;
+        }
+        Err(e) => {
+            ctx.log(
+                "Tournament enrollment failure",
+                format!(
+                    "User {} failed to join tournament {}\n Error detail: {}",
+                    ctx.author().name,
+                    selected_tournament,
+                    e
+                ),
+                log::State::FAILURE,
+                log::Model::TOURNAMENT,
+            )
+            .await?;
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Tournament Enrollment")
+                    .description("You have already joined this tournament. Please wait for the tournament to start.")
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks the live game profile of the user, or of every member of their team for a team
+/// tournament, against the entry requirements of a tournament and tells them which ones are not
+/// met.
+async fn meets_entry_requirements(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<bool, BotError> {
+    let players = entrants(
+        &ctx.data().database,
+        tournament,
+        &ctx.author().id.to_string(),
+    )
+    .await?;
+    if players.is_empty() {
+        return Err(anyhow!("Player not found in the database"));
+    }
+    let unmet = match unmet_entry_requirements(ctx, tournament, &players).await? {
+        APIResult::Ok(unmet) => unmet,
+        APIResult::NotFound => {
+            let profile = if players.len() > 1 {
+                "The game profile of one of your team members"
+            } else {
+                "Your game profile"
+            };
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Tournament Enrollment")
+                    .description(format!(
+                        "{} could not be found, so your entry requirements cannot be checked. Please ask a manager for help.",
+                        profile
+                    ))
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+            return Ok(false);
+        }
+        APIResult::Maintenance => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Tournament Enrollment")
+                    .description("Brawl Stars is currently undergoing maintenance, so your entry requirements cannot be checked. Please try again later.")
+                    .color(Color::RED),
+                None,
+            )
+            .await?;
+            return Ok(false);
+        }
+    };
+    if unmet.is_empty() {
+        return Ok(true);
+    }
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Tournament Enrollment")
+            .description(format!(
+                "{} not meet the following entry requirements of {}:\n{}",
+                if players.len() > 1 {
+                    "Your team does"
+                } else {
+                    "You do"
+                },
+                tournament.name,
+                unmet
+                    .iter()
+                    .map(|requirement| format!("- {}", requirement))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ))
+            .color(Color::RED),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Tournament enrollment failure",
+        format!(
+            "User {} does not meet the entry requirements of tournament {}:\n{}",
+            ctx.author().name,
+            tournament.tournament_id,
+            unmet.join("\n")
+        ),
+        log::State::FAILURE,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(false)
+}
+
+/// Gets the players who are checked against the entry requirements when the user enters a
+/// tournament: the whole roster of their team for a team tournament, or only the user otherwise.
+async fn entrants(
+    database: &PgDatabase,
+    tournament: &Tournament,
+    discord_id: &str,
+) -> Result<Vec<Player>, BotError> {
+    if tournament.team_size > 1 {
+        if let Some(team) = database
+            .get_team_by_member(&tournament.guild_id, discord_id)
+            .await?
+        {
+            return database.get_team_members(team.team_id).await;
+        }
+    }
+    Ok(database
+        .get_player_by_discord_id(discord_id)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Lists the entry requirements of a tournament that the given players do not meet, going by
+/// their live game profiles. When several players are checked, each requirement is prefixed with
+/// the name of the player who does not meet it.
+///
+/// Returns `NotFound` or `Maintenance` if a profile could not be fetched, in which case the
+/// requirements could not be checked.
+async fn unmet_entry_requirements(
+    ctx: &BotContext<'_>,
+    tournament: &Tournament,
+    players: &[Player],
+) -> Result<APIResult<Vec<String>>, BotError> {
+    let requirements = match ctx
+        .data()
+        .database
+        .get_entry_requirements(tournament.tournament_id)
+        .await?
+    {
+        Some(requirements) if !requirements.describe().is_empty() => requirements,
+        _ => return Ok(APIResult::Ok(vec![])),
+    };
+    let mut unmet = vec![];
+    for player in players {
+        let profile = match ctx
+            .data()
+            .apis
+            .brawl_stars
+            .get_player(&player.player_tag)
+            .await?
+        {
+            APIResult::Ok(profile) => profile,
+            APIResult::NotFound => return Ok(APIResult::NotFound),
+            APIResult::Maintenance => return Ok(APIResult::Maintenance),
+        };
+        unmet.extend(requirements.unmet(&profile).into_iter().map(
+            |requirement| match players.len() {
+                1 => requirement,
+                _ => format!("{}: {}", player.player_name, requirement),
+            },
+        ));
+    }
+    Ok(APIResult::Ok(unmet))
+}
+
+/// Put the user on the waitlist of a full tournament.
+///
+/// They take the spot of the next participant to leave, unless someone joined the waitlist before
+/// them.
+async fn join_waitlist(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<(), BotError> {
+    let discord_id = ctx.author().id.to_string();
+    ctx.data()
+        .database
+        .join_waitlist(tournament.tournament_id, &discord_id, ctx.now().timestamp())
+        .await?;
+    let waitlist = ctx
+        .data()
+        .database
+        .get_waitlist(tournament.tournament_id)
+        .await?;
+    let position = waitlist
+        .iter()
+        .position(|id| *id == discord_id)
+        .unwrap_or(waitlist.len())
+        + 1;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Tournament Enrollment")
+            .description(format!(
+                "{} is full, so you have been put on its waitlist at position {}. You will be notified as soon as a spot opens up!",
+                tournament.name, position
+            ))
+            .color(Color::GOLD),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Tournament waitlist joined",
+        format!(
+            "User {} joined the waitlist of tournament {} at position {}",
+            ctx.author().name,
+            tournament.tournament_id,
+            position
+        ),
+        log::State::INFO,
+        log::Model::TOURNAMENT,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Let the user leave the waitlist of a tournament.
+async fn leave_waitlist(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournaments: &[Tournament],
+) -> Result<(), BotError> {
+    let tournament = match tournaments {
+        [tournament] => tournament,
+        _ => {
+            let selected = select_options(
+                ctx,
+                msg,
+                CreateEmbed::default()
+                    .title("Leaving a waitlist")
+                    .description("Select the tournament whose waitlist you want to leave"),
+                None,
+                tournaments,
+            )
+            .await?;
+            tournaments
+                .iter()
+                .find(|t| t.tournament_id.to_string() == selected)
+                .ok_or(anyhow!("Tournament {} is not in the list", selected))?
+        }
+    };
+    let embed = CreateEmbed::new()
+        .title("Leave Waitlist")
+        .description(format!(
+            "Confirm that you want to leave the waitlist of the following tournament:\nTournament name: {}",
+            tournament.name
+        ))
+        .color(0xFF0000);
+    if !ctx.confirmation(msg, embed).await? {
+        return ctx
+            .prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Leaving a waitlist (Cancelled)")
+                    .description("You are still on the waitlist."),
+                None,
+            )
+            .await;
+    }
+    ctx.data()
+        .database
+        .leave_waitlist(tournament.tournament_id, &ctx.author().id.to_string())
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Leaving a waitlist")
+            .description("You have successfully left the waitlist."),
+        None,
+    )
+    .await
+}
+
+/// Fills the free spots of a tournament with the players on its waitlist, in the order they
+/// joined it, and notifies them.
+///
+/// Players who have joined another tournament in the meantime, who no longer meet the entry
+/// requirements, or captains who no longer have a team of the right size for a team tournament,
+/// are taken off the waitlist instead. Returns the promoted players.
+pub(crate) async fn promote_from_waitlist(
+    ctx: &BotContext<'_>,
+    tournament: &Tournament,
+) -> Result<Vec<String>, BotError> {
+    let database = &ctx.data().database;
+    let mut participants = database
+        .get_tournament_players(tournament.tournament_id)
+        .await?
+        .len();
+    let mut promoted = vec![];
+    for discord_id in database.get_waitlist(tournament.tournament_id).await? {
+        if !tournament.status.before_start() || tournament.is_full(participants) {
+            break;
+        }
+        let players = entrants(database, tournament, &discord_id).await?;
+        let eligible = !players.is_empty()
+            && match unmet_entry_requirements(ctx, tournament, &players).await? {
+                APIResult::Ok(unmet) => unmet.is_empty(),
+                APIResult::NotFound => false,
+                // Keep the waitlist as it is until the requirements can be checked again
+                APIResult::Maintenance => break,
+            };
+        let team = match tournament.team_size {
+            1 => None,
+            _ => database
+                .get_team_by_member(&tournament.guild_id, &discord_id)
+                .await?
+                .filter(|team| team.captain_id == discord_id),
+        };
+        // Same as enrolling from the menu, the roster size must be exact
+        let roster_size = match &team {
+            Some(team) => database.get_team_members(team.team_id).await?.len(),
+            None => 1,
+        };
+        if !eligible
+            || roster_size != tournament.team_size as usize
+            || !database
+                .get_player_active_tournaments(&tournament.guild_id, &discord_id)
+                .await?
+                .is_empty()
+        {
+            database
+                .leave_waitlist(tournament.tournament_id, &discord_id)
+                .await?;
+            continue;
+        }
+        if !database
+            .enter_tournament(tournament.tournament_id, &discord_id)
+            .await?
+        {
+            // Someone else took the spot in the meantime, so the waitlist stays as it is
+            break;
+        }
+        database
+            .leave_waitlist(tournament.tournament_id, &discord_id)
+            .await?;
+        if let Some(team) = &team {
+            database
+                .register_team(tournament.tournament_id, team.team_id)
+                .await?;
+        }
+        participants += 1;
+        ChannelId::new(tournament.notification_channel_id.parse()?)
+            .send_message(
+                ctx,
+                CreateMessage::default().content(format!(
+                    "<@{}>\n\nA spot opened up in {}, and you were next on the waitlist. You are now in the tournament. Good luck!",
+                    discord_id, tournament.name
+                )),
+            )
+            .await?;
+        ctx.log(
+            "Promoted from the waitlist",
+            format!(
+                "User <@{}> took a free spot in tournament {}",
+                discord_id, tournament.tournament_id
+            ),
+            log::State::SUCCESS,
+            log::Model::TOURNAMENT,
+        )
+        .await?;
+        promoted.push(discord_id);
+    }
+    Ok(promoted)
+}
+
+/// Registers the team of the user for a team tournament.
+///
+/// Only the captain of a full team can register it. Returns whether the team was registered.
+async fn register_team(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<bool, BotError> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let discord_id = ctx.author().id.to_string();
+    let team = match ctx
+        .data()
+        .database
+        .get_team_by_member(&guild_id, &discord_id)
+        .await?
+    {
+        Some(team) if team.captain_id == discord_id => team,
+        _ => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Tournament Enrollment")
+                    .description("This is a team tournament. Only team captains can enroll their team. Create a team from the Team page of the menu!")
+                    .color(Color::RED),
                 None,
             )
             .await?;
+            return Ok(false);
         }
-        Err(e) => {
-            ctx.log(
-                "Tournament enrollment failure",
-                format!(
-                    "User {} failed to join tournament {}\n Error detail: {}",
-                    ctx.author().name,
-                    selected_tournament,
-                    e
-                ),
-                log::State::FAILURE,
-                log::Model::TOURNAMENT,
-            )
-            .await?;
+    };
+    let members = ctx.data().database.get_team_members(team.team_id).await?;
+    // Results are only accepted when both rosters in the battle log match, so sizes must be exact
+    if members.len() != tournament.team_size as usize {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Tournament Enrollment")
+                .description(format!(
+                    "Your team needs exactly {} members to enroll in this tournament but has {}. Manage your roster from the Team page of the menu!",
+                    tournament.team_size,
+                    members.len()
+                ))
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(false);
+    }
+    ctx.data()
+        .database
+        .register_team(tournament.tournament_id, team.team_id)
+        .await?;
+    Ok(true)
+}
+
+/// Display the team of the user, letting the captain manage the roster.
+#[instrument(skip(msg))]
+async fn user_display_team(ctx: &BotContext<'_>, msg: &ReplyHandle<'_>) -> Result<(), BotError> {
+    info!("User {} has entered the team menu", ctx.author().name);
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let discord_id = ctx.author().id.to_string();
+    let team = match ctx
+        .data()
+        .database
+        .get_team_by_member(&guild_id, &discord_id)
+        .await?
+    {
+        Some(team) => team,
+        None => return user_display_team_invites(ctx, msg).await,
+    };
+    let members = ctx.data().database.get_team_members(team.team_id).await?;
+    let enrolled = team_tournament(ctx, &team).await?;
+    let roster_cap = enrolled
+        .as_ref()
+        .map_or(MAX_TEAM_SIZE, |tournament| tournament.team_size as usize);
+    let roster = members
+        .iter()
+        .map(|member| format!("- <@{}> ({})", member.discord_id, member.player_tag))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let embed = CreateEmbed::new()
+        .title(format!("Team {}", team.name))
+        .description(format!(
+            "**Captain:** <@{}>\n**Members ({}/{}):**\n{}",
+            team.captain_id,
+            members.len(),
+            roster_cap,
+            roster
+        ))
+        .color(Color::BLUE);
+    let buttons = if team.captain_id == discord_id {
+        vec![
+            CreateButton::new("team_invite")
+                .label("Invite")
+                .style(ButtonStyle::Primary)
+                .disabled(members.len() >= roster_cap),
+            CreateButton::new("team_remove")
+                .label("Remove Member")
+                .style(ButtonStyle::Danger)
+                .disabled(members.len() < 2),
+            CreateButton::new("team_disband")
+                .label("Disband")
+                .style(ButtonStyle::Danger),
+        ]
+    } else {
+        vec![CreateButton::new("team_leave")
+            .label("Leave Team")
+            .style(ButtonStyle::Danger)]
+    };
+    ctx.prompt(msg, embed, buttons).await?;
+    let mut ic = ctx.create_interaction_collector(msg).await?;
+    while let Some(interaction) = &ic.next().await {
+        interaction.defer(ctx.http()).await?;
+        let action = interaction.data.custom_id.as_str();
+        if enrolled.is_some() {
             ctx.prompt(
                 msg,
                 CreateEmbed::new()
-                    .title("Tournament Enrollment")
-                    .description("You have already joined this tournament. Please wait for the tournament to start.")
+                    .title("Team Locked")
+                    .description("Your team is enrolled in a tournament. The roster cannot change until your team leaves it.")
                     .color(Color::RED),
                 None,
             )
             .await?;
+            return Ok(());
         }
+        return match action {
+            "team_invite" => invite_team_member(ctx, msg, &team).await,
+            "team_remove" => {
+                let others = members
+                    .into_iter()
+                    .filter(|member| member.discord_id != team.captain_id)
+                    .collect::<Vec<Player>>();
+                let selected = select_options(
+                    ctx,
+                    msg,
+                    CreateEmbed::new()
+                        .title("Remove Member")
+                        .description("Select the member you want to remove from your team."),
+                    None,
+                    &others,
+                )
+                .await?;
+                ctx.data()
+                    .database
+                    .remove_team_member(team.team_id, &selected)
+                    .await?;
+                ctx.prompt(
+                    msg,
+                    CreateEmbed::new()
+                        .title("Remove Member")
+                        .description(format!("<@{}> has been removed from your team.", selected))
+                        .color(Color::DARK_GREEN),
+                    None,
+                )
+                .await
+            }
+            "team_disband" => {
+                let embed = CreateEmbed::new()
+                    .title("Disband Team")
+                    .description(format!("Are you sure you want to disband team {}?", team.name))
+                    .color(0xFF0000);
+                if ctx.confirmation(msg, embed).await? {
+                    ctx.data().database.delete_team(team.team_id).await?;
+                    ctx.log(
+                        "Team disbanded",
+                        format!("User {} has disbanded team {}", ctx.author().name, team.name),
+                        log::State::SUCCESS,
+                        log::Model::PLAYER,
+                    )
+                    .await?;
+                }
+                Ok(())
+            }
+            "team_leave" => {
+                let embed = CreateEmbed::new()
+                    .title("Leave Team")
+                    .description(format!("Are you sure you want to leave team {}?", team.name))
+                    .color(0xFF0000);
+                if ctx.confirmation(msg, embed).await? {
+                    ctx.data()
+                        .database
+                        .remove_team_member(team.team_id, &discord_id)
+                        .await?;
+                }
+                Ok(())
+            }
+            _ => continue,
+        };
     }
     Ok(())
 }
 
+/// Lets a user without a team create one or accept one of their pending invites.
+async fn user_display_team_invites(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+) -> Result<(), BotError> {
+    #[derive(Debug, Modal)]
+    #[name = "Team Name"]
+    struct TeamName {
+        #[name = "Name your team here"]
+        #[min_length = 3]
+        #[max_length = 20]
+        name: String,
+    }
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let discord_id = ctx.author().id.to_string();
+    let invites = ctx
+        .data()
+        .database
+        .get_team_invites(&guild_id, &discord_id)
+        .await?;
+    let buttons = vec![CreateActionRow::Buttons(vec![CreateButton::new(
+        "team_create",
+    )
+    .label("Create Team")
+    .style(ButtonStyle::Success)])];
+    let selected = select_options(
+        ctx,
+        msg,
+        CreateEmbed::new()
+            .title("Team")
+            .description(format!(
+                "You are not in a team yet. You have {} pending invite(s).\n\nSelect a team to accept its invite, or create your own team to become its captain.",
+                invites.len()
+            ))
+            .color(Color::BLUE),
+        buttons,
+        &invites,
+    )
+    .await?;
+    if selected == "team_create" {
+        let team_name = modal::<TeamName>(
+            ctx,
+            msg,
+            CreateEmbed::new()
+                .title("Create Team")
+                .description("Please provide the name of your team."),
+        )
+        .await?;
+        let team = ctx
+            .data()
+            .database
+            .create_team(&guild_id, &team_name.name, &discord_id)
+            .await?;
+        ctx.log(
+            "Team created",
+            format!("User {} has created team {}", ctx.author().name, team.name),
+            log::State::SUCCESS,
+            log::Model::PLAYER,
+        )
+        .await?;
+        return ctx
+            .prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Create Team")
+                    .description(format!(
+                        "You have created team {}! Invite your teammates from the Team page of the menu.",
+                        team.name
+                    ))
+                    .color(Color::DARK_GREEN),
+                None,
+            )
+            .await;
+    }
+    let team = invites
+        .iter()
+        .find(|team| team.team_id.to_string() == selected)
+        .ok_or(anyhow!("Team invite {} not found", selected))?;
+    if team_tournament(ctx, team).await?.is_some() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Team Locked")
+                .description(format!(
+                    "Team {} is enrolled in a tournament. Its roster cannot change until it leaves the tournament.",
+                    team.name
+                ))
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let members = ctx.data().database.get_team_members(team.team_id).await?;
+    if members.len() >= MAX_TEAM_SIZE {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Team Full")
+                .description(format!("Team {} is already full.", team.name))
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.data()
+        .database
+        .accept_team_invite(team.team_id, &discord_id)
+        .await?;
+    for other in invites.iter().filter(|other| other.team_id != team.team_id) {
+        ctx.data()
+            .database
+            .remove_team_member(other.team_id, &discord_id)
+            .await?;
+    }
+    ctx.log(
+        "Team invite accepted",
+        format!("User {} has joined team {}", ctx.author().name, team.name),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Team")
+            .description(format!("You have joined team {}!", team.name))
+            .color(Color::DARK_GREEN),
+        None,
+    )
+    .await
+}
+
+/// Lets the captain invite a registered player to their team and notifies the player by mail.
+async fn invite_team_member(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    team: &Team,
+) -> Result<(), BotError> {
+    let user = select_user(
+        ctx,
+        msg,
+        "Invite Member",
+        "Please select the player you want to invite to your team.",
+    )
+    .await?;
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let description = if ctx
+        .get_player_from_discord_id(user.id.to_string())
+        .await?
+        .is_none()
+    {
+        format!("{} has not registered their profile yet.", user.mention())
+    } else if ctx
+        .data()
+        .database
+        .get_team_by_member(&guild_id, &user.id.to_string())
+        .await?
+        .is_some()
+    {
+        format!("{} is already in a team.", user.mention())
+    } else {
+        ctx.data()
+            .database
+            .invite_team_member(team.team_id, &user.id.to_string())
+            .await?;
+        let mail = Mail::new(
+            team.captain_id.clone(),
+            user.id.to_string(),
+            format!("Invite to team {}", team.name),
+            format!(
+                "<@{}> has invited you to join their team {}. Open the Team page of the menu to accept the invite.",
+                team.captain_id, team.name
+            ),
+            None,
+        )
+        .await;
+        ctx.data().database.store(mail).await?;
+        format!("{} has been invited to your team.", user.mention())
+    };
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Invite Member")
+            .description(description)
+            .color(Color::BLUE),
+        None,
+    )
+    .await
+}
+
+/// Finds the active team tournament a team is enrolled in. The roster of an enrolled team is locked.
+async fn team_tournament(
+    ctx: &BotContext<'_>,
+    team: &Team,
+) -> Result<Option<Tournament>, BotError> {
+    let tournaments = ctx
+        .data()
+        .database
+        .get_active_tournaments_from_player(&team.captain_id)
+        .await?;
+    for tournament in tournaments.into_iter().filter(|t| t.team_size > 1) {
+        if ctx
+            .data()
+            .database
+            .get_tournament_team(tournament.tournament_id, &team.captain_id)
+            .await?
+            .is_some()
+        {
+            return Ok(Some(tournament));
+        }
+    }
+    Ok(None)
+}
+
 /// Registers the user's in-game profile with the bot.
 #[instrument(skip(msg, interaction_collector))]
 async fn user_display_registration(
@@ -920,6 +2620,17 @@ Tournament name: {}"#,
                 .database
                 .exit_tournament(&selected_tournament.tournament_id, &discord_id)
                 .await?;
+            if let Some(team) = ctx
+                .data()
+                .database
+                .get_tournament_team(selected_tournament.tournament_id, &discord_id)
+                .await?
+            {
+                ctx.data()
+                    .database
+                    .unregister_team(selected_tournament.tournament_id, team.team_id)
+                    .await?;
+            }
             ctx.prompt(
                 msg,
                 CreateEmbed::new()
@@ -928,6 +2639,7 @@ Tournament name: {}"#,
                 None,
             )
             .await?;
+            promote_from_waitlist(ctx, selected_tournament).await?;
         }
         false => {
             ctx.prompt(
@@ -973,43 +2685,45 @@ async fn submit(
                     game_match.match_id
                 ))
             }
-        };
-        let mut tags = vec![p1.player_tag.clone(), p2.player_tag.clone()];
-        tags.sort();
-        let compare_tag = |s1: &str, s2: &str| {
-            s1.chars()
-                .zip(s2.chars())
-                .all(|(c1, c2)| c1 == c2 || (c1 == 'O' && c2 == '0') || (c1 == '0' && c2 == 'O'))
-                && s1.len() == s2.len()
-        };
+        };
+        let mut rosters = [vec![p1.player_tag.clone()], vec![p2.player_tag.clone()]];
+        if tournament.team_size > 1 {
+            for (roster, captain) in rosters.iter_mut().zip([&p1, &p2]) {
+                let team = ctx
+                    .data()
+                    .database
+                    .get_tournament_team(tournament.tournament_id, &captain.discord_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Cannot find the team of captain {}", captain.discord_id))?;
+                *roster = ctx
+                    .data()
+                    .database
+                    .get_team_members(team.team_id)
+                    .await?
+                    .into_iter()
+                    .map(|member| member.player_tag)
+                    .collect();
+            }
+        }
         let filtered_logs = logs
             .iter()
             .filter(|log| {
-                (log.unix() > game_match.start.unwrap_or(i64::MIN)
-                    || log.unix() < game_match.end.unwrap_or(i64::MAX))
+                // Battles from before the match started, or was reset, do not count
+                log.unix() > game_match.start.unwrap_or(i64::MIN)
                     && (log.battle.mode.eq(&tournament.mode) || log.event.mode.eq(&tournament.mode))
                     && log
                         .battle
                         .battle_type
                         .to_lowercase()
                         .eq(&BattleType::friendly.to_string().to_lowercase())
-                    && {
-                        let mut log_tags = vec![
-                            log.battle.teams[0][0].tag.clone(),
-                            log.battle.teams[1][0].tag.clone(),
-                        ];
-                        log_tags.sort();
-                        tags.iter()
-                            .zip(log_tags.iter())
-                            .all(|(tag1, tag2)| compare_tag(tag1, tag2))
-                    }
+                    && rosters_match(&rosters, &log.battle.teams)
             }).cloned()
             .collect::<Vec<BattleLogItem>>();
         Ok(filtered_logs)
     }
     /// Analyse the battle logs to determine the winner of the match
     /// Returns true if the command caller wins, false if the opponent wins, and None if no conclusion can be made
-    async fn analyze(tournament: &Tournament, battles: &[BattleLogItem]) -> Option<(bool, String)> {
+    async fn analyze(wins_required: i32, battles: &[BattleLogItem]) -> Option<(bool, String)> {
         let mut conclusion: Option<(bool, String)> = None; //true = player 1, false = player 2, None = no conclusion
         let mut victory = 0;
         let mut defeat = 0;
@@ -1023,10 +2737,10 @@ async fn submit(
                 BattleResult::defeat => defeat += 1,
                 _ => {}
             }
-            if defeat == tournament.wins_required && victory < tournament.wins_required {
+            if defeat == wins_required && victory < wins_required {
                 conclusion = Some((false, format!("{}-{}", defeat, victory)));
                 break;
-            } else if victory >= tournament.wins_required {
+            } else if victory >= wins_required {
                 conclusion = Some((true, format!("{}-{}", victory, defeat)));
                 break;
             }
@@ -1036,12 +2750,13 @@ async fn submit(
     async fn handle_not_enough_matches(
         ctx: &BotContext<'_>,
         msg: &ReplyHandle<'_>,
+        wins_required: i32,
     ) -> Result<(), BotError> {
         ctx.prompt(
             msg,
             CreateEmbed::new()
                 .title("Insufficient Matches")
-                .description("You have not played enough matches to submit. You need to play at least 3 matches to submit."),
+                .description(format!("You have not played enough matches to submit. You need to play at least {} matches to submit.", wins_required)),
             None,
         )
         .await?;
@@ -1089,50 +2804,9 @@ async fn submit(
         .await?
         .ok_or(anyhow!("Player not found in the database"))?
         .player_tag;
-    let logs = match ctx
-        .data()
-        .apis
-        .brawl_stars
-        .get_battle_log(&caller_tag)
-        .await?
-    {
-        APIResult::Ok(response) => response.items,
-        APIResult::NotFound => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::new()
-                    .title("Player Not Found")
-                    .description("The player tag you entered was not found. Please try again."),
-                None,
-            )
-            .await?;
-            ctx.log(
-                "Player",
-                format!("Player tag {} not found", caller_tag),
-                crate::log::State::FAILURE,
-                crate::log::Model::PLAYER,
-            )
-            .await?;
-            return Ok(());
-        }
-        APIResult::Maintenance => {
-            ctx.prompt(
-                msg,
-                CreateEmbed::new()
-                    .title("Maintenance")
-                    .description("The Brawl Stars API is currently undergoing maintenance. Please try again later."),
-               None,
-            )
-            .await?;
-            ctx.log(
-                "API",
-                "Brawl Stars API is currently undergoing maintenance",
-                crate::log::State::FAILURE,
-                crate::log::Model::API,
-            )
-            .await?;
-            return Ok(());
-        }
+    let logs = match battle_log(ctx, msg, &caller_tag).await? {
+        Some(logs) => logs,
+        None => return Ok(()),
     };
     ctx.prompt(
         msg,
@@ -1143,36 +2817,133 @@ async fn submit(
     )
     .await?;
     let battles = filter(ctx, logs, &current_match, tournament).await?;
-    if battles.len() < tournament.wins_required as usize {
-        return handle_not_enough_matches(ctx, msg).await;
+    let mut banned = ctx
+        .data()
+        .database
+        .get_banned_brawlers(tournament.tournament_id)
+        .await?;
+    banned.extend(
+        ctx.data()
+            .database
+            .get_match_bans(&current_match.match_id)
+            .await?
+            .into_iter()
+            .map(|ban| ban.brawler),
+    );
+    let violations = battles
+        .iter()
+        .flat_map(|log| banned_picks(&log.battle, &banned))
+        .map(|player| format!("{} ({}) played **{}**", player.name, player.tag, player.brawler.name))
+        .collect::<Vec<String>>();
+    if !violations.is_empty() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Banned brawler used")
+                .description(format!(
+                    "Your result cannot be accepted because a banned brawler was played in this match:\n{}\n\nThe marshals have been notified and will settle the match.",
+                    violations.join("\n")
+                )),
+            None,
+        )
+        .await?;
+        ctx.log(
+            "Banned brawler used",
+            format!(
+                "Match ID: {}\nTournament: {} ({})\nSubmitted by: <@{}>\nBanned brawlers: {}\n{}\nThe submission was rejected. Please settle the match.",
+                current_match.match_id,
+                tournament.name,
+                tournament.tournament_id,
+                caller,
+                banned.join(", "),
+                violations.join("\n")
+            ),
+            log::State::WARNING,
+            log::Model::MARSHAL,
+        )
+        .await?;
+        return Ok(());
     }
-    let winner = analyze(tournament, &battles).await;
-    let score = winner.clone().map(|(_, s)| s).unwrap_or("0-0".to_string());
-    let target = match winner {
-        None => return handle_not_enough_matches(ctx, msg).await,
-        Some((true, score)) => join!(
-            ctx.data()
-                .database
-                .set_winner(&current_match.match_id, &caller, &score),
-            ctx.get_player_from_discord_id(None)
+    let rotation = ctx
+        .data()
+        .database
+        .get_map_rotation(tournament.tournament_id)
+        .await?;
+    let wrong_maps = wrong_maps(tournament, &current_match, &rotation, &battles)?;
+    if !wrong_maps.is_empty() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Wrong map")
+                .description(format!(
+                    "Your result cannot be accepted because some games were not played on their scheduled map:\n{}\n\nThe marshals have been notified and will settle the match.",
+                    wrong_maps.join("\n")
+                )),
+            None,
         )
-        .1?
-        .ok_or(anyhow!("Player not found in the database"))?,
-        Some((false, score)) => {
-            let opponent_id = &bracket.get_opponent(&ctx.author().id.to_string())?;
-            join!(
-                ctx.data()
-                    .database
-                    .set_winner(&bracket.match_id, &opponent_id.discord_id, &score),
-                ctx.get_player_from_discord_id(opponent_id.discord_id.to_string())
-            )
-            .1?
-            .ok_or(anyhow!("Player not found in the database"))?
-        }
+        .await?;
+        ctx.log(
+            "Wrong map played",
+            format!(
+                "Match ID: {}\nTournament: {} ({})\nSubmitted by: <@{}>\n{}\nThe submission was rejected. Please settle the match.",
+                current_match.match_id,
+                tournament.name,
+                tournament.tournament_id,
+                caller,
+                wrong_maps.join("\n")
+            ),
+            log::State::WARNING,
+            log::Model::MARSHAL,
+        )
+        .await?;
+        return Ok(());
+    }
+    let overrides = ctx
+        .data()
+        .database
+        .get_round_wins_required(tournament.tournament_id)
+        .await?;
+    let wins_required = tournament.wins_required_for(&current_match, &overrides)?;
+    if battles.len() < wins_required as usize {
+        return handle_not_enough_matches(ctx, msg, wins_required).await;
+    }
+    let winner = analyze(wins_required, &battles).await;
+    let score = winner.clone().map(|(_, s)| s).unwrap_or("0-0".to_string());
+    let winner_id = match winner {
+        None => return handle_not_enough_matches(ctx, msg, wins_required).await,
+        Some((true, _)) => caller.clone(),
+        Some((false, _)) => current_match.get_opponent(&caller)?.discord_id.clone(),
     };
-    // Final round. Announce the winner and finish the tournament
-    if bracket.round()? == tournament.rounds {
-        finish_tournament(ctx, bracket, &target).await?;
+    if !ctx
+        .data()
+        .database
+        .set_winner(&current_match.match_id, &winner_id, &score)
+        .await?
+    {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("Match already decided")
+                .description("The result of this match was recorded while you were submitting. Check the match from the menu.")
+                .color(Color::RED),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let target = ctx
+        .get_player_from_discord_id(winner_id)
+        .await?
+        .ok_or(anyhow!("Player not found in the database"))?;
+    // Final round. Announce the podium and finish the tournament
+    if finish_if_decided(
+        ctx.http(),
+        &ctx.data().database,
+        &tournament.guild_id,
+        &current_match.match_id,
+    )
+    .await?
+    {
         return Ok(());
     }
 
@@ -1197,89 +2968,851 @@ async fn submit(
         target.user(ctx)
     );
 
-    let embed = CreateEmbed::new()
-        .title("Match submission!")
-        .description(format!(
+    let description = match current_match.bracket()? {
+        Bracket::ThirdPlace => format!(
+            "Congratulations! {} wins the third place match",
+            user?.mention()
+        ),
+        _ => format!(
             "Congratulations! {} passes Round {}",
             user?.mention(),
             tournament.current_round
-        ))
+        ),
+    };
+    let embed = CreateEmbed::new()
+        .title("Match submission!")
+        .description(description)
         .thumbnail(target.icon());
     let channel = tournament.notification_channel(ctx).await?;
 
     let result_msg = channel
         .send_message(
             ctx.http(),
-            CreateMessage::new()
-                .embed(embed)
-                .add_file(CreateAttachment::bytes(image?, "result.png")),
+            CreateMessage::new()
+                .embed(embed)
+                .add_file(CreateAttachment::bytes(image?, "result.png")),
+        )
+        .await?;
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Result has been recorded successfully!")
+            .description(format!(
+                "Click [here]({}) to see the result\nOr head to {} to view other results!",
+                result_msg.link(),
+                channel.mention()
+            )),
+        None,
+    )
+    .await?;
+    ctx.log(
+        "Match submission",
+        format!(
+            "User {} has submitted their match {}",
+            ctx.author().name,
+            bracket.match_id
+        ),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    auto_advance(
+        ctx.http(),
+        &ctx.data().database,
+        &tournament.guild_id,
+        tournament.tournament_id,
+    )
+    .await
+}
+
+/// Display the showdown lobby of the user along with the points scored so far.
+async fn user_display_lobby(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<(), BotError> {
+    let lobby = match ctx
+        .data()
+        .database
+        .get_lobby_by_player(tournament.tournament_id, &ctx.author().id.to_string())
+        .await?
+    {
+        Some(lobby) if lobby.round == tournament.current_round => lobby,
+        _ => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new().title("Lobby Not Found").description(
+                    "You are not currently in a lobby. Please wait for the next round to begin.",
+                ),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let results = ctx
+        .data()
+        .database
+        .get_lobby_results(&lobby.lobby_id)
+        .await?;
+    ctx.prompt(
+        msg,
+        lobby_embed(tournament, &lobby, &results).title("Lobby Information"),
+        None,
+    )
+    .await
+}
+
+/// Lists the players of a lobby from the highest number of points.
+fn lobby_embed(tournament: &Tournament, lobby: &Lobby, results: &[LobbyResult]) -> CreateEmbed {
+    let standings = LobbyStanding::calculate(&lobby.players, results)
+        .iter()
+        .enumerate()
+        .map(|(index, standing)| {
+            format!(
+                "{}. <@{}>: {} points",
+                index + 1,
+                standing.discord_id,
+                standing.points
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    CreateEmbed::new()
+        .description(format!(
+            "Play {} games of {} together with everyone in your lobby. The top {} players advance.\n\n{}",
+            tournament.lobby_games,
+            tournament.mode,
+            Lobby::advancing(lobby.players.len()),
+            standings
+        ))
+        .fields(vec![
+            ("Tournament", tournament.name.clone(), true),
+            ("Lobby ID", lobby.lobby_id.clone(), true),
+            (
+                "Games played",
+                format!("{}/{}", lobby.games_played, tournament.lobby_games),
+                true,
+            ),
+        ])
+}
+
+/// Records the placements of the showdown games that the lobby of the user has played since the
+/// last submission.
+///
+/// Nothing is recorded unless the battle log of every player in the lobby can be fetched.
+async fn submit_lobby(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    tournament: &Tournament,
+) -> Result<(), BotError> {
+    let caller = ctx.author().id.to_string();
+    let lobby = match ctx
+        .data()
+        .database
+        .get_lobby_by_player(tournament.tournament_id, &caller)
+        .await?
+    {
+        Some(lobby)
+            if lobby.round == tournament.current_round
+                && lobby.games_played < tournament.lobby_games =>
+        {
+            lobby
+        }
+        _ => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new().title("Lobby Not Found").description(
+                    "You do not have any games left to play. Please wait for the next round to begin.",
+                ),
+                None,
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let mut players = Vec::with_capacity(lobby.players.len());
+    for discord_id in &lobby.players {
+        players.push(
+            ctx.get_player_from_discord_id(discord_id.clone())
+                .await?
+                .ok_or_else(|| anyhow!("Cannot find player with the Discord {}", discord_id))?,
+        );
+    }
+    let caller_tag = players
+        .iter()
+        .find(|player| player.discord_id == caller)
+        .ok_or(anyhow!("Player not found in the database"))?
+        .player_tag
+        .clone();
+    let caller_logs = match battle_log(ctx, msg, &caller_tag).await? {
+        Some(logs) => logs,
+        None => return Ok(()),
+    };
+    ctx.prompt(
+        msg,
+        CreateEmbed::new()
+            .title("Analyzing results")
+            .description("Hold on. I am analyzing the battle records..."),
+        None,
+    )
+    .await?;
+    // A battle only records the rank of the player whose battle log it comes from
+    let mut logs = Vec::with_capacity(players.len());
+    for player in &players {
+        let log = if player.discord_id == caller {
+            caller_logs.clone()
+        } else {
+            match ctx
+                .data()
+                .apis
+                .brawl_stars
+                .get_battle_log(&player.player_tag)
+                .await?
+            {
+                APIResult::Ok(response) => response.items,
+                // Without every battle log the placements of the lobby would be incomplete
+                APIResult::NotFound | APIResult::Maintenance => {
+                    ctx.prompt(
+                        msg,
+                        CreateEmbed::new()
+                            .title("Verification Incomplete")
+                            .description(format!(
+                                "The battle log of {} could not be fetched, so the results of your lobby cannot be verified and nothing was recorded. Please try again later.",
+                                player.player_name
+                            ))
+                            .color(Color::RED),
+                        None,
+                    )
+                    .await?;
+                    ctx.log(
+                        "Lobby submission",
+                        format!(
+                            "User {} could not submit lobby {}: the battle log of player tag {} is unavailable",
+                            ctx.author().name,
+                            lobby.lobby_id,
+                            player.player_tag
+                        ),
+                        log::State::FAILURE,
+                        log::Model::PLAYER,
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            }
+        };
+        logs.push((player, log));
+    }
+    let games: Vec<(i64, Vec<(String, i32)>)> =
+        lobby_games(&logs, &tournament.mode, lobby.last_battle)
+            .into_iter()
+            .take((tournament.lobby_games - lobby.games_played) as usize)
+            .collect();
+    if games.is_empty() {
+        ctx.prompt(
+            msg,
+            CreateEmbed::new()
+                .title("No New Games")
+                .description("No new games with everyone in your lobby were found in your battle log. Make sure that every player of the lobby is in the game room."),
+            None,
+        )
+        .await?;
+        return Ok(());
+    }
+    let new_games = games.len();
+    let mut results = ctx
+        .data()
+        .database
+        .get_lobby_results(&lobby.lobby_id)
+        .await?;
+    for (index, (battle_time, placements)) in games.into_iter().enumerate() {
+        for (discord_id, placement) in placements {
+            let result = LobbyResult {
+                lobby_id: lobby.lobby_id.clone(),
+                game_number: lobby.games_played + index as i32 + 1,
+                discord_id,
+                placement,
+                points: placement_points(&tournament.placement_points, placement),
+                battle_time,
+            };
+            ctx.data().database.add_lobby_result(&result).await?;
+            results.push(result);
+        }
+    }
+    let lobby = Lobby {
+        games_played: lobby.games_played + new_games as i32,
+        ..lobby
+    };
+    ctx.log(
+        "Lobby submission",
+        format!(
+            "User {} has submitted {} game(s) of lobby {}",
+            ctx.author().name,
+            new_games,
+            lobby.lobby_id
+        ),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    let embed = lobby_embed(tournament, &lobby, &results);
+    if lobby.games_played < tournament.lobby_games {
+        return ctx
+            .prompt(msg, embed.title("Results have been recorded successfully!"), None)
+            .await;
+    }
+
+    let channel = tournament.notification_channel(ctx).await?;
+    let result_msg = channel
+        .send_message(
+            ctx.http(),
+            CreateMessage::new().embed(embed.title(format!("Lobby {} has finished!", lobby.lobby_id))),
         )
         .await?;
     ctx.prompt(
         msg,
         CreateEmbed::new()
-            .title("Result has been recorded successfully!")
+            .title("Your lobby has finished!")
             .description(format!(
-                "Click [here]({}) to see the result\nOr head to {} to view other results!",
-                result_msg.link(),
-                channel.mention()
+                "Click [here]({}) to see the final standings of your lobby.",
+                result_msg.link()
             )),
         None,
     )
     .await?;
-    ctx.log(
-        "Match submission",
-        format!(
-            "User {} has submitted their match {}",
-            ctx.author().name,
-            bracket.match_id
-        ),
-        log::State::SUCCESS,
-        log::Model::PLAYER,
-    )
-    .await?;
+    // The final round is played in a single lobby
+    if tournament.current_round == tournament.rounds {
+        let podium: Vec<String> = LobbyStanding::calculate(&lobby.players, &results)
+            .into_iter()
+            .take(3)
+            .map(|standing| standing.discord_id)
+            .collect();
+        finish_tournament(
+            ctx.http(),
+            &ctx.data().database,
+            &tournament.guild_id,
+            tournament.tournament_id,
+            &podium,
+        )
+        .await?;
+    }
     Ok(())
 }
 
-async fn finish_tournament(
+/// Fetches the battle log of a player, telling the user why if it is unavailable.
+async fn battle_log(
     ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    player_tag: &str,
+) -> Result<Option<Vec<BattleLogItem>>, BotError> {
+    let logs = match ctx
+        .data()
+        .apis
+        .brawl_stars
+        .get_battle_log(player_tag)
+        .await?
+    {
+        APIResult::Ok(response) => Some(response.items),
+        APIResult::NotFound => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Player Not Found")
+                    .description("The player tag you entered was not found. Please try again."),
+                None,
+            )
+            .await?;
+            ctx.log(
+                "Player",
+                format!("Player tag {} not found", player_tag),
+                crate::log::State::FAILURE,
+                crate::log::Model::PLAYER,
+            )
+            .await?;
+            None
+        }
+        APIResult::Maintenance => {
+            ctx.prompt(
+                msg,
+                CreateEmbed::new()
+                    .title("Maintenance")
+                    .description("The Brawl Stars API is currently undergoing maintenance. Please try again later."),
+               None,
+            )
+            .await?;
+            ctx.log(
+                "API",
+                "Brawl Stars API is currently undergoing maintenance",
+                crate::log::State::FAILURE,
+                crate::log::Model::API,
+            )
+            .await?;
+            None
+        }
+    };
+    Ok(logs)
+}
+
+/// Compares two player tags, treating the letter O and the digit 0 as equal since players often
+/// confuse them.
+fn compare_tag(s1: &str, s2: &str) -> bool {
+    s1.chars()
+        .zip(s2.chars())
+        .all(|(c1, c2)| c1 == c2 || (c1 == 'O' && c2 == '0') || (c1 == '0' && c2 == 'O'))
+        && s1.len() == s2.len()
+}
+
+/// Checks that the two sides of a battle are made up of exactly the players of the two rosters,
+/// in either order.
+fn rosters_match(rosters: &[Vec<String>; 2], teams: &[Vec<TeamPlayer>]) -> bool {
+    let same_roster = |roster: &[String], team: &[TeamPlayer]| {
+        roster.len() == team.len()
+            && team
+                .iter()
+                .all(|player| roster.iter().any(|tag| compare_tag(tag, &player.tag)))
+    };
+    match teams {
+        [a, b] => {
+            (same_roster(&rosters[0], a) && same_roster(&rosters[1], b))
+                || (same_roster(&rosters[1], a) && same_roster(&rosters[0], b))
+        }
+        _ => false,
+    }
+}
+
+/// Lists the players of a battle who played one of the banned brawlers.
+///
+/// The battle log spells brawler names in capitals, so they are compared regardless of case.
+fn banned_picks<'a>(battle: &'a Battle, banned: &[String]) -> Vec<&'a TeamPlayer> {
+    battle
+        .teams
+        .iter()
+        .flatten()
+        .chain(battle.players.iter())
+        .filter(|player| {
+            banned
+                .iter()
+                .any(|brawler| brawler.eq_ignore_ascii_case(&player.brawler.name))
+        })
+        .collect()
+}
+
+/// Lists the games of a series that were not played on the map scheduled for them.
+///
+/// Games are counted from the oldest battle, since the battle log lists the newest first.
+fn wrong_maps(
+    tournament: &Tournament,
+    bracket: &Match,
+    rotation: &[ScheduledMap],
+    battles: &[BattleLogItem],
+) -> Result<Vec<String>, BotError> {
+    let mut games = battles.iter().collect::<Vec<&BattleLogItem>>();
+    games.sort_by_key(|log| log.unix());
+    let mut wrong = vec![];
+    for (index, log) in games.into_iter().enumerate() {
+        let game = index as i32 + 1;
+        let expected = tournament.map_for(bracket, game, rotation)?;
+        if !expected.is_any() && !expected.same_as(&log.event.map) {
+            wrong.push(format!(
+                "Game {}: played on **{}** instead of **{}**",
+                game, log.event.map.name, expected.name
+            ));
+        }
+    }
+    Ok(wrong)
+}
+
+/// Reads the tags of every player in a showdown battle.
+///
+/// Solo showdown lists the players on their own, while duo and trio showdown list them in teams.
+fn showdown_tags(battle: &Battle) -> Vec<&str> {
+    if !battle.players.is_empty() {
+        return battle
+            .players
+            .iter()
+            .map(|player| player.tag.as_str())
+            .collect();
+    }
+    battle
+        .teams
+        .iter()
+        .flatten()
+        .map(|player| player.tag.as_str())
+        .collect()
+}
+
+/// Reads the placement of every lobby player in the showdown games played after `after`.
+///
+/// A battle only records the `rank` of the player whose battle log it comes from, so the placement
+/// of each player is read from their own battle log, matching the games by their battle time.
+/// Games are returned from the oldest, and only if they were played by exactly the lobby players
+/// and the placement of every player is known.
+fn lobby_games(
+    logs: &[(&Player, Vec<BattleLogItem>)],
+    mode: &Mode,
+    after: i64,
+) -> Vec<(i64, Vec<(String, i32)>)> {
+    let ranks: Vec<(&Player, HashMap<i64, i32>)> = logs
+        .iter()
+        .map(|(player, log)| {
+            let ranks = log
+                .iter()
+                .filter(|item| {
+                    let tags = showdown_tags(&item.battle);
+                    item.unix() > after
+                        && item.battle.rank > 0
+                        && item
+                            .battle
+                            .battle_type
+                            .eq_ignore_ascii_case(&BattleType::friendly.to_string())
+                        && (item.battle.mode.eq(mode) || item.event.mode.eq(mode))
+                        && tags.len() == logs.len()
+                        && logs.iter().all(|(player, _)| {
+                            tags.iter().any(|tag| compare_tag(&player.player_tag, tag))
+                        })
+                })
+                .map(|item| (item.unix(), item.battle.rank))
+                .collect();
+            (*player, ranks)
+        })
+        .collect();
+    let mut battle_times: Vec<i64> = ranks
+        .first()
+        .map(|(_, ranks)| ranks.keys().copied().collect())
+        .unwrap_or_default();
+    battle_times.sort_unstable();
+    battle_times
+        .into_iter()
+        .filter_map(|battle_time| {
+            ranks
+                .iter()
+                .map(|(player, ranks)| {
+                    ranks
+                        .get(&battle_time)
+                        .map(|rank| (player.discord_id.clone(), *rank))
+                })
+                .collect::<Option<Vec<(String, i32)>>>()
+                .map(|placements| (battle_time, placements))
+        })
+        .collect()
+}
+
+/// The points awarded for a placement. Placements beyond the points table are worth nothing.
+fn placement_points(points_table: &[i32], placement: i32) -> i32 {
+    points_table
+        .get(placement as usize - 1)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Checks whether every match of the final round of a tournament is decided.
+async fn final_round_decided(
+    database: &PgDatabase,
+    tournament: &Tournament,
+) -> Result<bool, BotError> {
+    let brackets = database
+        .get_matches_by_tournament(tournament.tournament_id, Some(tournament.rounds))
+        .await?;
+    Ok(brackets.iter().all(|b| b.winner.is_some()))
+}
+
+/// Reads the top three players of a finished elimination tournament.
+///
+/// The champion and the runner-up come from the last decided final. Third place goes to the winner
+/// of the third place match or, in double elimination, to the player eliminated in the losers
+/// bracket final.
+fn podium(brackets: &[Match]) -> Vec<String> {
+    let last_decided = |kind: Bracket| {
+        brackets
+            .iter()
+            .filter(|b| b.winner.is_some() && b.bracket().is_ok_and(|bracket| bracket == kind))
+            .max_by_key(|b| b.round().unwrap_or(0))
+    };
+    let mut podium = Vec::with_capacity(3);
+    if let Some(final_match) =
+        last_decided(Bracket::GrandFinal).or_else(|| last_decided(Bracket::Winners))
+    {
+        podium.extend(final_match.winner.clone());
+        podium.extend(
+            final_match
+                .get_losing_player()
+                .map(|player| player.discord_id.clone()),
+        );
+    }
+    let third_place = match last_decided(Bracket::ThirdPlace) {
+        Some(third_place_match) => third_place_match.winner.clone(),
+        None => last_decided(Bracket::Losers)
+            .and_then(|losers_final| losers_final.get_losing_player())
+            .map(|player| player.discord_id.clone()),
+    };
+    podium.extend(third_place);
+    podium
+}
+
+/// Finishes the tournament of a match if the result of that match decided it.
+///
+/// Matches are decided by submissions, marshals and deadline forfeits alike, so this only needs
+/// the HTTP client and the database. Like a round, the tournament waits for [`DISPUTE_WINDOW`]
+/// after its last result and while any result is disputed, after which the scheduler finishes it.
+/// Returns whether the tournament was finished.
+pub(crate) async fn finish_if_decided(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    match_id: &str,
+) -> Result<bool, BotError> {
+    let bracket = match database.get_match_by_id(match_id).await? {
+        Some(bracket) => bracket,
+        None => return Ok(false),
+    };
+    let winner = match &bracket.winner {
+        Some(winner) => winner.clone(),
+        None => return Ok(false),
+    };
+    let tournament = match database
+        .get_tournament(guild_id, bracket.tournament()?)
+        .await?
+    {
+        Some(tournament)
+            if matches!(
+                tournament.status,
+                TournamentStatus::Started | TournamentStatus::Paused
+            ) =>
+        {
+            tournament
+        }
+        _ => return Ok(false),
+    };
+    if !is_final_match(database, &tournament, &bracket, &winner).await? {
+        return Ok(false);
+    }
+    if !disputed_matches(database, &tournament).await?.is_empty()
+        || database
+            .get_round_decided_at(tournament.tournament_id, bracket.round()?)
+            .await?
+            .is_some_and(|decided_at| decided_at + DISPUTE_WINDOW > chrono::Utc::now().timestamp())
+    {
+        return Ok(false);
+    }
+    let podium = match tournament.format {
+        // The tournament is won by the best record rather than by the final match
+        TournamentFormat::Swiss => update_standings(database, tournament.tournament_id)
+            .await?
+            .into_iter()
+            .take(3)
+            .map(|standing| standing.discord_id)
+            .collect(),
+        _ => {
+            let brackets = database
+                .get_matches_by_tournament(tournament.tournament_id, None)
+                .await?;
+            podium(&brackets)
+        }
+    };
+    finish_tournament(http, database, guild_id, tournament.tournament_id, &podium).await?;
+    Ok(true)
+}
+
+/// Checks whether the result of a match decides the winner of the whole tournament.
+async fn is_final_match(
+    database: &PgDatabase,
+    tournament: &Tournament,
     bracket: &Match,
-    winner: &Player,
+    winner: &str,
+) -> Result<bool, BotError> {
+    match tournament.format {
+        // The final and the third place match are played in the same round
+        TournamentFormat::SingleElimination => Ok(bracket.round()? == tournament.rounds
+            && final_round_decided(database, tournament).await?),
+        // The last group stage round is only the final round until the playoff bracket starts
+        TournamentFormat::RoundRobin => Ok(bracket.bracket()? != Bracket::Group
+            && bracket.round()? == tournament.rounds
+            && final_round_decided(database, tournament).await?),
+        TournamentFormat::DoubleElimination => {
+            if bracket.bracket()? != Bracket::GrandFinal {
+                return Ok(false);
+            }
+            // The grand final reset is always the last match
+            if bracket.round()? == tournament.rounds {
+                return Ok(true);
+            }
+            // Otherwise the tournament is only over if the winners bracket champion won
+            let winners_final_id = Match::generate_id(
+                tournament.tournament_id,
+                (tournament.rounds - 1) / 2,
+                1,
+            );
+            let winners_final = database
+                .get_match_by_id(&winners_final_id)
+                .await?
+                .ok_or(anyhow!(
+                    "Error finishing tournament {}: Unable to find the winners bracket final {}",
+                    tournament.tournament_id,
+                    winners_final_id
+                ))?;
+            Ok(winners_final.winner.as_deref() == Some(winner))
+        }
+        TournamentFormat::Swiss => Ok(bracket.round()? == tournament.rounds
+            && final_round_decided(database, tournament).await?),
+        // Showdown tournaments are decided by their final lobby instead of a match
+        TournamentFormat::Showdown => Ok(false),
+    }
+}
+
+/// Announces the podium of a tournament, from the champion down to third place, and ends it.
+async fn finish_tournament(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    tournament_id: i32,
+    podium: &[String],
 ) -> Result<(), BotError> {
-    let guild_id = ctx.guild_id().unwrap().to_string();
-    let announcement_channel_id = ctx
-        .data()
-        .database
-        .get_config(&guild_id)
+    let announcement_channel_id = database
+        .get_config(guild_id)
         .await?
-        .unwrap()
+        .ok_or(anyhow!(
+            "Error finishing tournament {}: config not found for guild {}",
+            tournament_id,
+            guild_id
+        ))?
         .announcement_channel_id;
-    let tournament_id = bracket.tournament()?;
-    let tournament = ctx
-        .data()
-        .database
-        .get_tournament(&guild_id, tournament_id)
+    let tournament = database
+        .get_tournament(guild_id, tournament_id)
         .await?
-        .unwrap();
+        .ok_or(anyhow!(
+            "Error finishing tournament {}: tournament not found",
+            tournament_id
+        ))?;
 
+    let champion = podium.first().ok_or(anyhow!(
+        "Error finishing tournament {}: No champion found",
+        tournament_id
+    ))?;
+    let mut announcement = format!(
+        "Congratulations to <@{}> for winning Tournament {}",
+        champion, tournament.name
+    );
+    for (place, discord_id) in ["Second", "Third"].iter().zip(podium.iter().skip(1)) {
+        announcement.push_str(&format!("\n{} place: <@{}>", place, discord_id));
+    }
     ChannelId::new(announcement_channel_id.parse::<u64>()?)
-        .send_message(
-            ctx,
-            CreateMessage::default().content(format!(
-                "Congratulations to <@{}> for winning Tournament {}",
-                winner.discord_id, tournament.name
-            )),
-        )
+        .send_message(http, CreateMessage::default().content(announcement))
         .await?;
 
-    ctx.data()
-        .database
+    database
         .set_tournament_status(tournament_id, TournamentStatus::Inactive)
         .await?;
 
     Ok(())
 }
 
+/// Add screenshots or videos to your open result dispute.
+#[poise::command(slash_command, guild_only, check = "is_config_set")]
+#[instrument]
+async fn dispute_evidence(
+    ctx: BotContext<'_>,
+    #[description = "The ID of your dispute"] dispute_id: i32,
+    #[description = "A screenshot or video of the match"] evidence: Attachment,
+    #[description = "Another screenshot or video of the match"] more_evidence: Option<Attachment>,
+) -> Result<(), BotError> {
+    let reply = |content: String| CreateReply::default().content(content).ephemeral(true);
+    let dispute = match ctx.data().database.get_dispute(dispute_id).await? {
+        Some(dispute)
+            if dispute.discord_id == ctx.author().id.to_string()
+                && dispute.status == DisputeStatus::Open =>
+        {
+            dispute
+        }
+        _ => {
+            ctx.send(reply(format!(
+                "You do not have an open dispute with the ID {}.",
+                dispute_id
+            )))
+            .await?;
+            return Ok(());
+        }
+    };
+    let attachments: Vec<Attachment> = std::iter::once(evidence).chain(more_evidence).collect();
+    if let Some(attachment) = attachments.iter().find(|attachment| {
+        !attachment
+            .content_type
+            .as_deref()
+            .is_some_and(|kind| kind.starts_with("image/") || kind.starts_with("video/"))
+    }) {
+        ctx.send(reply(format!(
+            "`{}` is not a screenshot or video.",
+            attachment.filename
+        )))
+        .await?;
+        return Ok(());
+    }
+    ctx.defer_ephemeral().await?;
+
+    // Attachment links expire, so the files are kept in the thread of the dispute
+    let mut files = Vec::with_capacity(attachments.len());
+    for attachment in &attachments {
+        files.push(CreateAttachment::bytes(
+            attachment.download().await?,
+            attachment.filename.clone(),
+        ));
+    }
+    let mut evidence = attachments
+        .iter()
+        .map(|attachment| attachment.url.clone())
+        .collect::<Vec<String>>();
+    if let Some(thread_id) = &dispute.thread_id {
+        let message = ChannelId::new(thread_id.parse()?)
+            .send_message(
+                ctx.http(),
+                CreateMessage::new()
+                    .content(format!(
+                        "Evidence added by <@{}> to dispute {}",
+                        dispute.discord_id, dispute.dispute_id
+                    ))
+                    .add_files(files),
+            )
+            .await?;
+        evidence = message
+            .attachments
+            .into_iter()
+            .map(|attachment| attachment.url)
+            .collect();
+    }
+    if !ctx
+        .data()
+        .database
+        .add_dispute_evidence(dispute.dispute_id, &evidence)
+        .await?
+    {
+        ctx.send(reply(format!(
+            "Dispute {} was resolved in the meantime.",
+            dispute.dispute_id
+        )))
+        .await?;
+        return Ok(());
+    }
+    ctx.send(reply(format!(
+        "Added {} file(s) to dispute {}. The marshals will see them when they review it.",
+        evidence.len(),
+        dispute.dispute_id
+    )))
+    .await?;
+    ctx.log(
+        "Dispute evidence added",
+        format!(
+            "Dispute ID: {}\nMatch ID: {}\nFiles: {}\nAdded by: {}",
+            dispute.dispute_id,
+            dispute.match_id,
+            evidence.join("\n"),
+            ctx.author().name
+        ),
+        log::State::SUCCESS,
+        log::Model::PLAYER,
+    )
+    .await?;
+    Ok(())
+}
+
 #[poise::command(
     slash_command,
     prefix_command,
@@ -1311,3 +3844,358 @@ async fn credit(ctx: BotContext<'_>) -> Result<(), BotError> {
     .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::models::{Event, MatchPlayer, Mode, PlayerType, RoundWinsRequired};
+    use crate::utils::time::BattleDateTime;
+
+    fn team(tags: &[&str]) -> Vec<TeamPlayer> {
+        tags.iter()
+            .map(|tag| TeamPlayer {
+                tag: tag.to_string(),
+                name: tag.to_string(),
+                brawler: Brawler::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rosters_match_all_six_tags_in_either_order() {
+        let rosters = [
+            vec!["#A1".to_string(), "#A2".to_string(), "#AO3".to_string()],
+            vec!["#B1".to_string(), "#B2".to_string(), "#B3".to_string()],
+        ];
+        let teams = [team(&["#B3", "#B1", "#B2"]), team(&["#A03", "#A2", "#A1"])];
+        assert!(rosters_match(&rosters, &teams));
+
+        let substitute = [team(&["#A1", "#A2", "#C1"]), team(&["#B1", "#B2", "#B3"])];
+        assert!(!rosters_match(&rosters, &substitute));
+
+        let mixed = [team(&["#A1", "#A2", "#B1"]), team(&["#AO3", "#B2", "#B3"])];
+        assert!(!rosters_match(&rosters, &mixed));
+    }
+
+    #[test]
+    fn finds_banned_brawlers_in_either_team() {
+        let pick = |tag: &str, brawler: &str| TeamPlayer {
+            tag: tag.to_string(),
+            name: tag.to_string(),
+            brawler: Brawler {
+                id: 0,
+                name: brawler.to_string(),
+            },
+        };
+        let battle = Battle {
+            mode: Mode::brawlBall,
+            battle_type: "friendly".to_string(),
+            rank: 0,
+            result: BattleResult::default(),
+            trophy_change: None,
+            duration: None,
+            teams: vec![
+                vec![pick("#A1", "SHELLY"), pick("#A2", "EL PRIMO")],
+                vec![pick("#B1", "MORTIS"), pick("#B2", "COLT")],
+            ],
+            players: Vec::new(),
+        };
+        let banned = ["El Primo".to_string(), "Mortis".to_string()];
+        let tags = banned_picks(&battle, &banned)
+            .iter()
+            .map(|player| player.tag.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(tags, vec!["#A2", "#B1"]);
+        assert!(banned_picks(&battle, &[]).is_empty());
+    }
+
+    #[test]
+    fn draft_alternates_until_every_ban_is_used() {
+        let players = ["1", "2"]
+            .iter()
+            .map(|discord_id| MatchPlayer {
+                match_id: "1.1.1".to_string(),
+                discord_id: discord_id.to_string(),
+                player_type: PlayerType::Player,
+                ready: false,
+            })
+            .collect();
+        let bracket = Match::new(1, 1, 1, players, "0-0");
+        let mut bans = vec![];
+        for turn in 0..2 * Match::DRAFT_BANS_PER_PLAYER as i32 {
+            let player = bracket.draft_turn(&bans).map(|p| p.discord_id.clone());
+            assert_eq!(player.as_deref(), Some(["1", "2"][turn as usize % 2]));
+            bans.push(MatchBan {
+                match_id: bracket.match_id.clone(),
+                turn,
+                discord_id: player.unwrap(),
+                brawler: turn.to_string(),
+            });
+        }
+        assert!(bracket.draft_turn(&bans).is_none());
+    }
+
+    #[test]
+    fn reads_lobby_placements_from_every_battle_log_and_points() {
+        let item = |battle_time: &str, rank: i32| BattleLogItem {
+            battle_time: battle_time.to_string(),
+            event: serde_json::from_str(r#"{"id": 0, "mode": "soloShowdown", "map": null}"#)
+                .unwrap(),
+            battle: Battle {
+                mode: Mode::soloShowdown,
+                battle_type: "friendly".to_string(),
+                rank,
+                result: BattleResult::default(),
+                trophy_change: None,
+                duration: None,
+                teams: Vec::new(),
+                players: team(&["#A", "#B", "#C"]),
+            },
+        };
+        let players: Vec<Player> = ["#A", "#B", "#C"]
+            .iter()
+            .map(|tag| Player {
+                discord_id: tag.to_string(),
+                player_tag: tag.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let first = "20261020T120000.000Z";
+        let second = "20261020T121000.000Z";
+        let third = "20261020T122000.000Z";
+        let after = BattleDateTime::from_str(first).unwrap().datetime;
+        // Logs are listed from the most recent battle, and the third game is missing from one log
+        let logs = vec![
+            (
+                &players[0],
+                vec![item(third, 2), item(second, 1), item(first, 3)],
+            ),
+            (
+                &players[1],
+                vec![item(third, 1), item(second, 3), item(first, 1)],
+            ),
+            (&players[2], vec![item(second, 2), item(first, 2)]),
+        ];
+        let second_time = BattleDateTime::from_str(second).unwrap().datetime;
+        assert_eq!(
+            lobby_games(&logs, &Mode::soloShowdown, after),
+            vec![(
+                second_time,
+                vec![
+                    ("#A".to_string(), 1),
+                    ("#B".to_string(), 3),
+                    ("#C".to_string(), 2)
+                ]
+            )]
+        );
+        assert_eq!(lobby_games(&logs, &Mode::duoShowdown, 0), Vec::new());
+
+        let duo = item(first, 1).battle;
+        let duo = Battle {
+            teams: vec![team(&["#A", "#B"]), team(&["#C", "#D"])],
+            players: Vec::new(),
+            ..duo
+        };
+        assert_eq!(showdown_tags(&duo), vec!["#A", "#B", "#C", "#D"]);
+
+        let points_table = [10, 8, 7];
+        assert_eq!(placement_points(&points_table, 1), 10);
+        assert_eq!(placement_points(&points_table, 4), 0);
+    }
+
+    #[test]
+    fn podium_comes_from_the_final_and_third_place_match() {
+        let decided = |match_id: &str, players: &[&str], winner: &str| Match {
+            match_id: match_id.to_string(),
+            match_players: players
+                .iter()
+                .map(|id| MatchPlayer {
+                    match_id: match_id.to_string(),
+                    discord_id: id.to_string(),
+                    player_type: PlayerType::Player,
+                    ready: false,
+                })
+                .collect(),
+            score: "2-0".to_string(),
+            winner: Some(winner.to_string()),
+            start: None,
+            end: None,
+        };
+        let single_elimination = vec![
+            decided("1.1.1", &["a", "d"], "a"),
+            decided("1.1.2", &["b", "c"], "c"),
+            decided("1.2.1", &["a", "c"], "c"),
+            decided("1.2.1.3P", &["d", "b"], "b"),
+        ];
+        assert_eq!(podium(&single_elimination), vec!["c", "a", "b"]);
+
+        let double_elimination = vec![
+            decided("1.2.1", &["a", "b"], "a"),
+            decided("1.3.1.L", &["b", "c"], "b"),
+            decided("1.4.1.GF", &["a", "b"], "b"),
+            decided("1.5.1.GF", &["a", "b"], "a"),
+        ];
+        assert_eq!(podium(&double_elimination), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn wins_required_uses_round_and_final_overrides() {
+        let tournament = Tournament {
+            tournament_id: 1,
+            rounds: 3,
+            wins_required: 2,
+            ..Default::default()
+        };
+        let bracket = |match_id: &str| Match {
+            match_id: match_id.to_string(),
+            match_players: Vec::new(),
+            score: "0-0".to_string(),
+            winner: None,
+            start: None,
+            end: None,
+        };
+        let overrides = [
+            RoundWinsRequired {
+                tournament_id: 1,
+                round: 0,
+                wins_required: 3,
+            },
+            RoundWinsRequired {
+                tournament_id: 1,
+                round: 2,
+                wins_required: 1,
+            },
+        ];
+        let wins = |match_id: &str| tournament.wins_required_for(&bracket(match_id), &overrides).unwrap();
+        assert_eq!(wins("1.1.1"), 2);
+        assert_eq!(wins("1.2.1"), 1);
+        assert_eq!(wins("1.3.1"), 3);
+        assert_eq!(wins("1.3.1.3P"), 3);
+    }
+
+    #[test]
+    fn map_rotation_prefers_games_over_rounds_over_the_tournament_map() {
+        let map = |id: i32, name: &str| BrawlMap {
+            id,
+            name: name.to_string(),
+        };
+        let tournament = Tournament {
+            tournament_id: 1,
+            rounds: 3,
+            map: map(1, "Hard Rock Mine"),
+            ..Default::default()
+        };
+        let scheduled = |round: i32, game: i32, id: i32, name: &str| ScheduledMap {
+            tournament_id: 1,
+            round,
+            game,
+            map: map(id, name),
+        };
+        let rotation = [
+            scheduled(2, 0, 2, "Crystal Arcade"),
+            scheduled(2, 3, 3, "Gem Fort"),
+            scheduled(0, 0, 4, "Undermine"),
+        ];
+        let map_for = |match_id: &str, game: i32| {
+            let bracket = Match::new(1, 1, 1, Vec::new(), "0-0");
+            let bracket = Match {
+                match_id: match_id.to_string(),
+                ..bracket
+            };
+            tournament.map_for(&bracket, game, &rotation).unwrap().name
+        };
+        assert_eq!(map_for("1.1.1", 1), "Hard Rock Mine");
+        assert_eq!(map_for("1.2.1", 1), "Crystal Arcade");
+        assert_eq!(map_for("1.2.1", 3), "Gem Fort");
+        assert_eq!(map_for("1.3.1", 2), "Undermine");
+    }
+
+    #[test]
+    fn battle_log_maps_are_read_from_their_name() {
+        let event: Event =
+            serde_json::from_str(r#"{"id": 15000007, "mode": "gemGrab", "map": "Hard Rock Mine"}"#)
+                .unwrap();
+        assert!(event.map.same_as(&BrawlMap {
+            id: 15000007,
+            name: "HARD ROCK MINE".to_string(),
+        }));
+        let event: Event = serde_json::from_str(r#"{"id": 0, "mode": "gemGrab", "map": null}"#).unwrap();
+        assert!(event.map.is_any());
+    }
+
+    #[test]
+    fn check_in_is_only_open_before_the_window_closes() {
+        let mut tournament = Tournament {
+            status: TournamentStatus::Pending,
+            ..Default::default()
+        };
+        assert!(!tournament.check_in_open(100));
+        tournament.check_in_end = Some(200);
+        assert!(tournament.check_in_open(100));
+        assert!(!tournament.check_in_open(200));
+        tournament.status = TournamentStatus::RegistrationClosed;
+        assert!(tournament.check_in_open(100));
+        tournament.status = TournamentStatus::Started;
+        assert!(!tournament.check_in_open(100));
+    }
+
+    #[test]
+    fn entry_requirements_list_every_unmet_rule() {
+        use crate::api::official_brawl_stars::{Club, Icon, PlayerProfile};
+        use crate::database::models::{EntryRequirements, Requirement};
+
+        let profile = PlayerProfile {
+            tag: "#P1".to_string(),
+            name: "Player".to_string(),
+            club: Some(Club {
+                tag: "#CLUB1".to_string(),
+                name: "Club One".to_string(),
+            }),
+            icon: Icon { id: 0 },
+            trophies: 31000,
+            three_vs_three_victories: 5000,
+            solo_victories: 0,
+            duo_victories: 0,
+            exp_level: 100,
+            exp_points: 0,
+            highest_trophies: 32000,
+            brawlers: vec![Brawler::default(); 60],
+        };
+        let mut requirements = EntryRequirements::default();
+        assert!(requirements.unmet(&profile).is_empty());
+        requirements.set(Requirement::Club, Some("club1")).unwrap();
+        requirements.set(Requirement::MinBrawlers, Some("60")).unwrap();
+        assert!(requirements.unmet(&profile).is_empty());
+
+        requirements.set(Requirement::MaxTrophies, Some("30000")).unwrap();
+        requirements
+            .set(Requirement::MinThreeVsThreeVictories, Some("6000"))
+            .unwrap();
+        assert_eq!(requirements.unmet(&profile).len(), 2);
+        assert!(requirements.set(Requirement::MinTrophies, Some("-1")).is_err());
+        assert!(requirements.set(Requirement::MinTrophies, Some("many")).is_err());
+
+        requirements.set(Requirement::MaxTrophies, None).unwrap();
+        assert_eq!(requirements.unmet(&profile).len(), 1);
+        assert_eq!(requirements.describe().len(), 3);
+    }
+
+    #[test]
+    fn only_capped_tournaments_fill_up() {
+        let mut tournament = Tournament::default();
+        assert!(!tournament.is_full(1000));
+        tournament.max_participants = Some(8);
+        assert!(!tournament.is_full(7));
+        assert!(tournament.is_full(8));
+    }
+
+    #[test]
+    fn dispute_evidence_is_one_link_per_line() {
+        assert_eq!(parse_evidence("").unwrap(), Vec::<String>::new());
+        assert_eq!(
+            parse_evidence("https://a.example/1.png\n\n  http://b.example/clip  ").unwrap(),
+            vec!["https://a.example/1.png", "http://b.example/clip"]
+        );
+        assert!(parse_evidence("https://a.example/1.png\nI have a video").is_err());
+    }
+}
@@ -3,7 +3,7 @@ use crate::BotError;
 use anyhow::anyhow;
 use models::*;
 use poise::serenity_prelude::RoleId;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 /// Models for the database.
 ///
 /// These models are specific to the current database design and schema.
@@ -18,7 +18,7 @@ pub mod models;
 /// If you want to change the database schema, you'll need to change this trait as well as all its associated types.
 
 /// The Postgres database used for the DBC tournament system.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PgDatabase {
     pub pool: PgPool,
 }
@@ -44,6 +44,119 @@ impl PgDatabase {
         sqlx::migrate!("./migrations").run(&self.pool).await?;
         Ok(())
     }
+
+    /// Creates a match with its players, and its winner if it is already decided such as a bye.
+    ///
+    /// Takes a connection so that a whole round can be created in one transaction.
+    async fn insert_match(connection: &mut PgConnection, bracket: &Match) -> Result<(), BotError> {
+        let now = chrono::Utc::now().timestamp();
+        sqlx::query!(
+            r#"
+            INSERT INTO matches (match_id, score, start)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (match_id) DO NOTHING
+            "#,
+            bracket.match_id,
+            "0-0",
+            now,
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        for player in &bracket.match_players {
+            sqlx::query!(
+                r#"
+                INSERT INTO match_players (match_id, discord_id, player_type, ready)
+                VALUES ($1, $2, $3, false)
+                ON CONFLICT (match_id, discord_id) DO NOTHING
+                "#,
+                bracket.match_id,
+                player.discord_id,
+                PlayerType::Player as PlayerType
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        if let Some(winner) = &bracket.winner {
+            sqlx::query!(
+                r#"
+                UPDATE matches
+                SET winner = $1, score = $3, decided_at = $4
+                WHERE match_id = $2 AND winner IS NULL
+                "#,
+                winner,
+                bracket.match_id,
+                bracket.score,
+                now
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a showdown lobby with its players.
+    ///
+    /// Takes a connection so that a whole round can be created in one transaction.
+    async fn insert_lobby(connection: &mut PgConnection, lobby: &Lobby) -> Result<(), BotError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO lobbies (lobby_id, tournament_id, round, start)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            lobby.lobby_id,
+            lobby.tournament_id,
+            lobby.round,
+            chrono::Utc::now().timestamp()
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        for discord_id in &lobby.players {
+            sqlx::query!(
+                r#"
+                INSERT INTO lobby_players (lobby_id, discord_id)
+                VALUES ($1, $2)
+                "#,
+                lobby.lobby_id,
+                discord_id
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the deadline of the undecided matches of a round.
+    ///
+    /// Takes a connection so that the deadline is set in the same transaction as the round starts.
+    async fn set_round_end(
+        connection: &mut PgConnection,
+        tournament_id: i32,
+        round: i32,
+        deadline: i64,
+    ) -> Result<(), BotError> {
+        sqlx::query!(
+            r#"
+            UPDATE matches
+            SET "end" = $1, escalated = false
+            WHERE
+                SPLIT_PART(match_id, '.', 1)::int = $2
+                AND SPLIT_PART(match_id, '.', 2)::int = $3
+                AND winner IS NULL
+            "#,
+            deadline,
+            tournament_id,
+            round
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        Ok(())
+    }
 }
 
 pub trait ConfigDatabase {
@@ -75,6 +188,25 @@ pub trait ConfigDatabase {
 
     /// Retrieves the marshal role of a given guild from the database.
     async fn get_marshal_role(&self, guild_id: &str) -> Result<Option<RoleId>, Self::Error>;
+
+    /// Adds a map to the map pool of a mode in a guild.
+    async fn add_to_map_pool(
+        &self,
+        guild_id: &str,
+        mode: Mode,
+        map: &BrawlMap,
+    ) -> Result<(), Self::Error>;
+
+    /// Removes a map from the map pool of a mode in a guild.
+    async fn remove_from_map_pool(
+        &self,
+        guild_id: &str,
+        mode: Mode,
+        map_id: i32,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the map pool of a mode in a guild, sorted by name.
+    async fn get_map_pool(&self, guild_id: &str, mode: Mode) -> Result<Vec<BrawlMap>, Self::Error>;
 }
 
 impl ConfigDatabase for PgDatabase {
@@ -178,6 +310,74 @@ impl ConfigDatabase for PgDatabase {
         };
         Ok(marshal.parse().ok())
     }
+
+    async fn add_to_map_pool(
+        &self,
+        guild_id: &str,
+        mode: Mode,
+        map: &BrawlMap,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO map_pools (guild_id, mode, map_id, map_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, mode, map_id)
+            DO UPDATE SET map_name = $4
+            "#,
+            guild_id,
+            mode as Mode,
+            map.id,
+            map.name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_from_map_pool(
+        &self,
+        guild_id: &str,
+        mode: Mode,
+        map_id: i32,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM map_pools
+            WHERE guild_id = $1 AND mode = $2 AND map_id = $3
+            "#,
+            guild_id,
+            mode as Mode,
+            map_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_map_pool(&self, guild_id: &str, mode: Mode) -> Result<Vec<BrawlMap>, Self::Error> {
+        let maps = sqlx::query!(
+            r#"
+            SELECT map_id, map_name
+            FROM map_pools
+            WHERE guild_id = $1 AND mode = $2
+            ORDER BY map_name
+            "#,
+            guild_id,
+            mode as Mode
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| BrawlMap {
+            id: row.map_id,
+            name: row.map_name,
+        })
+        .collect();
+
+        Ok(maps)
+    }
 }
 pub trait UserDatabase {
     async fn get_tournament_id(&self, discord_id: &str) -> Result<Option<i32>, Self::Error>;
@@ -210,13 +410,16 @@ pub trait UserDatabase {
     /// Sets the ready status of a player of a specified match to true.
     async fn set_ready(&self, match_id: &str, discord_id: &str) -> Result<(), Self::Error>;
 
-    /// Sets the winner of a match
+    /// Sets the winner of a match that has not been decided yet.
+    ///
+    /// Returns false without changing anything if the match already has a winner, for example
+    /// because a result was submitted at the same time.
     async fn set_winner(
         &self,
         match_id: &str,
         discord_id: &str,
         score: &str,
-    ) -> Result<(), Self::Error>;
+    ) -> Result<bool, Self::Error>;
 
     async fn get_current_match(&self, discord_id: &str) -> Result<Option<Match>, Self::Error>;
 }
@@ -364,21 +567,22 @@ impl UserDatabase for PgDatabase {
         match_id: &str,
         discord_id: &str,
         score: &str,
-    ) -> Result<(), Self::Error> {
-        sqlx::query!(
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
             r#"
             UPDATE matches
-            SET winner = $1
-            WHERE match_id = $2 AND score = $3
+            SET winner = $1, score = $3, decided_at = $4
+            WHERE match_id = $2 AND winner IS NULL
             "#,
             discord_id,
             match_id,
-            score
+            score,
+            chrono::Utc::now().timestamp()
         )
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     async fn get_tournament_id(&self, discord_id: &str) -> Result<Option<i32>, Self::Error> {
@@ -471,13 +675,8 @@ pub trait TournamentDatabase {
     async fn create_tournament(
         &self,
         guild_id: &str,
-        name: &str,
-        mode: &Mode,
+        settings: &TournamentSettings,
         tournament_id: impl Into<Option<i32>>,
-        role_id: String,
-        announcement_channel_id: &str,
-        notification_channel_id: &str,
-        wins_required: i32,
     ) -> Result<i32, Self::Error>;
 
     /// Updates the status of a tournament.
@@ -487,6 +686,21 @@ pub trait TournamentDatabase {
         new_status: TournamentStatus,
     ) -> Result<(), Self::Error>;
 
+    /// Opens or closes the registration of a tournament that has not started yet.
+    ///
+    /// Returns false without changing anything if the tournament has already started or ended.
+    async fn set_registration_status(
+        &self,
+        tournament_id: i32,
+        new_status: TournamentStatus,
+    ) -> Result<bool, Self::Error>;
+
+    /// Marks a tournament that has not started yet as started.
+    ///
+    /// Returns false without changing anything if the tournament was already started or ended,
+    /// so that only one caller can ever start it.
+    async fn claim_tournament_start(&self, tournament_id: i32) -> Result<bool, Self::Error>;
+
     /// Retrieves a tournament from the database given a guild id and tournament id.
     async fn get_tournament(
         &self,
@@ -500,7 +714,7 @@ pub trait TournamentDatabase {
     /// Retrieves all active tournaments from the database.
     ///
     /// This will get all active tournaments that have their status set to either "pending",
-    /// "started", or "paused".
+    /// "registration_closed", "started", or "paused".
     async fn get_active_tournaments(&self, guild_id: &str) -> Result<Vec<Tournament>, Self::Error>;
 
     /// Retrieves all active tournaments that the player has currently entered.
@@ -522,12 +736,15 @@ pub trait TournamentDatabase {
     /// All matches must be done in the current map in order for them to be counted.
     async fn set_map(&self, tournament_id: i32, map: &BrawlMap) -> Result<(), Self::Error>;
 
-    /// Enters a user into a tournament.
+    /// Enters a user into a tournament, unless it has reached its participant cap.
+    ///
+    /// Returns false without entering the user if the tournament is full. Entering a user who is
+    /// already in the tournament does nothing and returns true.
     async fn enter_tournament(
         &self,
         tournament_id: i32,
         discord_id: &str,
-    ) -> Result<(), Self::Error>;
+    ) -> Result<bool, Self::Error>;
 
     /// Exits a user from a tournament.
     async fn exit_tournament(
@@ -549,6 +766,56 @@ pub trait TournamentDatabase {
         wins_required: &i32,
     ) -> Result<(), Self::Error>;
 
+    /// Overrides the number of wins required to win a match in one round of a tournament.
+    ///
+    /// Round 0 stands for the final round.
+    async fn set_round_wins_required(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        wins_required: i32,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the per-round overrides of the number of wins required in a tournament.
+    async fn get_round_wins_required(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Vec<RoundWinsRequired>, Self::Error>;
+
+    /// Adds a brawler to the ban list of a tournament.
+    async fn ban_brawler(&self, tournament_id: i32, brawler: &str) -> Result<(), Self::Error>;
+
+    /// Removes a brawler from the ban list of a tournament.
+    ///
+    /// Returns false if the brawler was not banned.
+    async fn unban_brawler(&self, tournament_id: i32, brawler: &str) -> Result<bool, Self::Error>;
+
+    /// Gets the names of the brawlers banned in a tournament.
+    async fn get_banned_brawlers(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error>;
+
+    /// Assigns a map to a round of a tournament, or to one game of the series in that round.
+    ///
+    /// Round 0 stands for the final and game 0 for every game of the round.
+    async fn set_scheduled_map(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        game: i32,
+        map: &BrawlMap,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the maps assigned to the rounds and games of a tournament.
+    async fn get_map_rotation(&self, tournament_id: i32) -> Result<Vec<ScheduledMap>, Self::Error>;
+
+    /// Opens the check-in of a tournament until the given unix timestamp.
+    async fn open_check_in(&self, tournament_id: i32, check_in_end: i64) -> Result<(), Self::Error>;
+
+    /// Confirms that a player will show up for a tournament.
+    async fn check_in(&self, tournament_id: i32, discord_id: &str) -> Result<(), Self::Error>;
+
+    /// Gets the Discord IDs of the players who checked in for a tournament.
+    async fn get_checked_in_players(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error>;
+
     /// Gets all players in a tournament.
     async fn get_tournament_players(&self, tournament_id: i32) -> Result<Vec<Player>, Self::Error>;
 
@@ -558,11 +825,223 @@ pub trait TournamentDatabase {
     /// when the number of contestants are known.
     async fn set_rounds(&self, tournament_id: i32, rounds: i32) -> Result<(), Self::Error>;
 
+    /// Moves a tournament from the round before the given one to it and creates the matches and
+    /// lobbies of that round, all in one transaction.
+    ///
+    /// Returns false without changing anything if the tournament is no longer on the round
+    /// before, because it was advanced in the meantime.
+    async fn start_round(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        matches: &[Match],
+        lobbies: &[Lobby],
+        deadline: Option<i64>,
+    ) -> Result<bool, Self::Error>;
+
+    /// Records the draw of a tournament and moves it to its first round, all in one transaction.
+    ///
+    /// The players who did not check in leave the tournament with their teams, and the others
+    /// get their seeds, groups and standings before the first round is created.
+    async fn draw_tournament(&self, draw: &TournamentDraw) -> Result<(), Self::Error>;
+
+    /// Ends the group stage of a Round Robin tournament by moving it to the first playoff round,
+    /// setting its total number of rounds and creating the playoff matches, all in one
+    /// transaction.
+    ///
+    /// Returns false without changing anything if the group stage was closed or the tournament
+    /// advanced in the meantime.
+    async fn start_playoffs(
+        &self,
+        tournament_id: i32,
+        group_rounds: i32,
+        rounds: i32,
+        matches: &[Match],
+        deadline: Option<i64>,
+    ) -> Result<bool, Self::Error>;
+
     /// Increments the current round of a tournament by 1.
     ///
     /// The caller is responsible to check if calls to this method will make a tournament's current
     /// round exceed its total number of rounds.
     async fn next_round(&self, tournament_id: i32) -> Result<(), Self::Error>;
+
+    /// Creates or updates the standings of the players in a tournament.
+    async fn set_standings(&self, standings: &[Standing]) -> Result<(), Self::Error>;
+
+    /// Gets the standings of a tournament, ordered from the highest ranked player.
+    async fn get_standings(&self, tournament_id: i32) -> Result<Vec<Standing>, Self::Error>;
+
+    /// Sets the number of players per team of a tournament.
+    async fn set_team_size(&self, tournament_id: i32, team_size: i32) -> Result<(), Self::Error>;
+
+    /// Sets the points awarded for each placement and the number of games played in each lobby
+    /// of a showdown tournament.
+    async fn set_showdown_scoring(
+        &self,
+        tournament_id: i32,
+        placement_points: &[i32],
+        lobby_games: i32,
+    ) -> Result<(), Self::Error>;
+
+    /// Sets how many minutes players have to finish the matches of each round, or removes the
+    /// deadline with None.
+    async fn set_round_duration(
+        &self,
+        tournament_id: i32,
+        round_duration: Option<i32>,
+    ) -> Result<(), Self::Error>;
+
+    /// Renames a tournament.
+    async fn set_tournament_name(&self, tournament_id: i32, name: &str) -> Result<(), Self::Error>;
+
+    /// Sets the game mode the matches of a tournament are played in.
+    async fn set_tournament_mode(&self, tournament_id: i32, mode: Mode) -> Result<(), Self::Error>;
+
+    /// Sets the role given to the participants of a tournament.
+    async fn set_tournament_role(
+        &self,
+        tournament_id: i32,
+        role_id: &str,
+    ) -> Result<(), Self::Error>;
+
+    /// Sets the channel where the progress of a tournament is announced.
+    async fn set_announcement_channel(
+        &self,
+        tournament_id: i32,
+        channel_id: &str,
+    ) -> Result<(), Self::Error>;
+
+    /// Sets the channel where the players of a tournament are notified about their matches.
+    async fn set_notification_channel(
+        &self,
+        tournament_id: i32,
+        channel_id: &str,
+    ) -> Result<(), Self::Error>;
+
+    /// Sets the maximum number of participants of a tournament, or removes the cap with None.
+    async fn set_max_participants(
+        &self,
+        tournament_id: i32,
+        max_participants: Option<i32>,
+    ) -> Result<(), Self::Error>;
+
+    /// Puts a player on the waitlist of a tournament.
+    async fn join_waitlist(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+        joined_at: i64,
+    ) -> Result<(), Self::Error>;
+
+    /// Takes a player off the waitlist of a tournament.
+    async fn leave_waitlist(&self, tournament_id: i32, discord_id: &str)
+        -> Result<(), Self::Error>;
+
+    /// Gets the Discord IDs of the players on the waitlist of a tournament, from the first to
+    /// join it.
+    async fn get_waitlist(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error>;
+
+    /// Gets the IDs of the tournaments whose waitlist a player is on.
+    async fn get_waitlisted_tournaments(&self, discord_id: &str) -> Result<Vec<i32>, Self::Error>;
+
+    /// Replaces the entry requirements of a tournament.
+    async fn set_entry_requirements(
+        &self,
+        requirements: &EntryRequirements,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the entry requirements of a tournament, if it has any.
+    async fn get_entry_requirements(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Option<EntryRequirements>, Self::Error>;
+
+    /// Sets when the tournament starts by itself, or clears the scheduled start with None.
+    async fn set_start_time(
+        &self,
+        tournament_id: i32,
+        start_time: Option<i64>,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the guild and ID of every open tournament whose scheduled start time has passed.
+    ///
+    /// Tournaments with a check-in that is still open are left until the check-in closes.
+    async fn get_due_tournaments(&self, now: i64) -> Result<Vec<(String, i32)>, Self::Error>;
+
+    /// Sets whether the tournament advances to the next round by itself once every match of the
+    /// current round is decided.
+    async fn set_auto_advance(&self, tournament_id: i32, auto_advance: bool)
+        -> Result<(), Self::Error>;
+
+    /// Gets the guild and ID of every started tournament that advances by itself.
+    async fn get_auto_advancing_tournaments(&self) -> Result<Vec<(String, i32)>, Self::Error>;
+
+    /// Gets the guild and ID of every started tournament.
+    async fn get_started_tournaments(&self) -> Result<Vec<(String, i32)>, Self::Error>;
+
+    /// Gets the players of a tournament ordered by the seed they received when it started.
+    async fn get_seeded_players(&self, tournament_id: i32) -> Result<Vec<Player>, Self::Error>;
+
+    /// Gets the Discord IDs of the players in each group of a round robin tournament, ordered by
+    /// group number.
+    async fn get_groups(&self, tournament_id: i32) -> Result<Vec<Vec<String>>, Self::Error>;
+
+    /// Gets the group standings of a round robin tournament from the group stage matches played so
+    /// far, ordered by group number and then from the highest ranked player.
+    async fn get_group_standings(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Vec<GroupStanding>, Self::Error>;
+
+    /// Saves the settings of a tournament as a template of its guild, replacing the template with
+    /// the same name if there is one. Returns the id of the template.
+    async fn save_template(&self, tournament_id: i32, name: &str) -> Result<i32, Self::Error>;
+
+    /// Gets the tournament templates of a guild, ordered by name.
+    async fn get_templates(&self, guild_id: &str) -> Result<Vec<TournamentTemplate>, Self::Error>;
+
+    /// Creates a pending tournament with the settings of a template. Returns the id of the new
+    /// tournament.
+    async fn create_tournament_from_template(
+        &self,
+        template_id: i32,
+        name: &str,
+    ) -> Result<i32, Self::Error>;
+
+    /// Creates a pending tournament with the settings of an existing tournament. Players, matches
+    /// and progress are not copied. Returns the id of the new tournament.
+    async fn clone_tournament(&self, tournament_id: i32, name: &str) -> Result<i32, Self::Error>;
+
+    /// Schedules tournaments to be created from a template on a recurrence. Returns the id of the
+    /// recurrence.
+    async fn add_recurring_tournament(
+        &self,
+        recurrence: &RecurringTournament,
+    ) -> Result<i32, Self::Error>;
+
+    /// Stops creating the tournaments of a recurrence. Returns whether the recurrence existed in
+    /// the guild.
+    async fn remove_recurring_tournament(
+        &self,
+        guild_id: &str,
+        recurrence_id: i32,
+    ) -> Result<bool, Self::Error>;
+
+    /// Gets the recurring tournaments of a guild, ordered by their next start.
+    async fn get_recurring_tournaments(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<RecurringTournament>, Self::Error>;
+
+    /// Gets the recurring tournaments of every guild whose next tournament is due to be created.
+    async fn get_due_recurring_tournaments(
+        &self,
+        now: i64,
+    ) -> Result<Vec<RecurringTournament>, Self::Error>;
+
+    /// Sets when the next tournament of a recurrence starts.
+    async fn set_next_start(&self, recurrence_id: i32, next_start: i64) -> Result<(), Self::Error>;
 }
 
 impl TournamentDatabase for PgDatabase {
@@ -570,13 +1049,8 @@ impl TournamentDatabase for PgDatabase {
     async fn create_tournament(
         &self,
         guild_id: &str,
-        name: &str,
-        mode: &Mode,
+        settings: &TournamentSettings,
         tournament_id: impl Into<Option<i32>>,
-        role_id: String,
-        announcement_channel_id: &str,
-        notification_channel_id: &str,
-        wins_required: i32,
     ) -> Result<i32, Self::Error> {
         let timestamp_time = chrono::offset::Utc::now().timestamp();
 
@@ -584,18 +1058,21 @@ impl TournamentDatabase for PgDatabase {
             None => {
                 sqlx::query!(
                     r#"
-            INSERT INTO tournaments (guild_id, name, mode, created_at, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required)
-            VALUES ($1, $2, $3, $4, 0, 0, $5, $6, $7, $8)
+            INSERT INTO tournaments (guild_id, name, mode, format, created_at, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required, team_size, start_time)
+            VALUES ($1, $2, $3, $4, $5, 0, 0, $6, $7, $8, $9, $10, $11)
             RETURNING tournament_id
             "#,
                     guild_id,
-                    name,
-                    *mode as Mode,
+                    settings.name,
+                    settings.mode as Mode,
+                    settings.format as TournamentFormat,
                     timestamp_time,
-                    role_id,
-                    announcement_channel_id,
-                    notification_channel_id,
-                    wins_required
+                    settings.tournament_role_id,
+                    settings.announcement_channel_id,
+                    settings.notification_channel_id,
+                    settings.wins_required,
+                    settings.team_size,
+                    settings.start_time
                 )
                 .fetch_one(&self.pool)
                 .await?
@@ -604,18 +1081,21 @@ impl TournamentDatabase for PgDatabase {
             Some(custom_id) => {
                 sqlx::query!(
                     r#"
-            INSERT INTO tournaments (guild_id, name, mode, created_at, tournament_id, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id)
-            VALUES ($1, $2, $3, $4, 0, 0, $5, $6, $7, $8)
+            INSERT INTO tournaments (guild_id, name, mode, format, created_at, tournament_id, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id, team_size, start_time)
+            VALUES ($1, $2, $3, $4, $5, 0, 0, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (tournament_id) DO NOTHING
             "#,
                     guild_id,
-                    name,
-                    *mode as Mode,
+                    settings.name,
+                    settings.mode as Mode,
+                    settings.format as TournamentFormat,
                     timestamp_time,
                     custom_id,
-                    role_id,
-                    announcement_channel_id,
-                    notification_channel_id
+                    settings.tournament_role_id,
+                    settings.announcement_channel_id,
+                    settings.notification_channel_id,
+                    settings.team_size,
+                    settings.start_time
                 )
                 .execute(&self.pool)
                 .await?;
@@ -646,6 +1126,42 @@ impl TournamentDatabase for PgDatabase {
         Ok(())
     }
 
+    async fn set_registration_status(
+        &self,
+        tournament_id: i32,
+        new_status: TournamentStatus,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET status = $2
+            WHERE tournament_id = $1 AND (status = 'pending' OR status = 'registration_closed')
+            "#,
+            tournament_id,
+            new_status as TournamentStatus,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn claim_tournament_start(&self, tournament_id: i32) -> Result<bool, Self::Error> {
+        let claimed = sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET status = 'started'
+            WHERE tournament_id = $1 AND (status = 'pending' OR status = 'registration_closed')
+            RETURNING tournament_id
+            "#,
+            tournament_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
     async fn get_tournament(
         &self,
         guild_id: &str,
@@ -663,6 +1179,16 @@ impl TournamentDatabase for PgDatabase {
                 t.created_at, 
                 t.start_time, 
                 t.mode AS "mode: Mode",
+                t.format AS "format: TournamentFormat",
+                t.seeding_method AS "seeding_method: SeedingMethod",
+                t.draw_seed,
+                t.team_size,
+                t.placement_points,
+                t.lobby_games,
+                t.check_in_end,
+                t.auto_advance,
+                t.round_duration,
+                t.max_participants,
                 t.tournament_role_id, 
                 t.wins_required, 
                 t.announcement_channel_id, 
@@ -697,6 +1223,16 @@ impl TournamentDatabase for PgDatabase {
             created_at: row.created_at,
             start_time: row.start_time,
             mode: row.mode,
+            format: row.format,
+            seeding_method: row.seeding_method,
+            draw_seed: row.draw_seed,
+            team_size: row.team_size,
+            placement_points: row.placement_points,
+            lobby_games: row.lobby_games,
+            check_in_end: row.check_in_end,
+            auto_advance: row.auto_advance,
+            round_duration: row.round_duration,
+            max_participants: row.max_participants,
             map: BrawlMap {
                 id: row.map_id,
                 name: row.map_name,
@@ -719,6 +1255,16 @@ impl TournamentDatabase for PgDatabase {
                 t.rounds, t.current_round, 
                 t.created_at, t.start_time, 
                 t.mode as "mode: Mode", 
+                t.format as "format: TournamentFormat",
+                t.seeding_method as "seeding_method: SeedingMethod",
+                t.draw_seed,
+                t.team_size,
+                t.placement_points,
+                t.lobby_games,
+                t.check_in_end,
+                t.auto_advance,
+                t.round_duration,
+                t.max_participants,
                 t.wins_required, 
                 t.tournament_role_id, 
                 t.announcement_channel_id, 
@@ -746,6 +1292,16 @@ impl TournamentDatabase for PgDatabase {
             created_at: row.created_at,
             start_time: row.start_time,
             mode: row.mode,
+            format: row.format,
+            seeding_method: row.seeding_method,
+            draw_seed: row.draw_seed,
+            team_size: row.team_size,
+            placement_points: row.placement_points,
+            lobby_games: row.lobby_games,
+            check_in_end: row.check_in_end,
+            auto_advance: row.auto_advance,
+            round_duration: row.round_duration,
+            max_participants: row.max_participants,
             map: BrawlMap {
                 id: row.map_id,
                 name: row.map_name,
@@ -770,6 +1326,16 @@ impl TournamentDatabase for PgDatabase {
                 t.rounds, t.current_round, 
                 t.created_at, t.start_time, 
                 t.mode as "mode: Mode", 
+                t.format as "format: TournamentFormat",
+                t.seeding_method as "seeding_method: SeedingMethod",
+                t.draw_seed,
+                t.team_size,
+                t.placement_points,
+                t.lobby_games,
+                t.check_in_end,
+                t.auto_advance,
+                t.round_duration,
+                t.max_participants,
                 t.wins_required, 
                 t.tournament_role_id, 
                 t.announcement_channel_id, 
@@ -796,6 +1362,16 @@ impl TournamentDatabase for PgDatabase {
             created_at: row.created_at,
             start_time: row.start_time,
             mode: row.mode,
+            format: row.format,
+            seeding_method: row.seeding_method,
+            draw_seed: row.draw_seed,
+            team_size: row.team_size,
+            placement_points: row.placement_points,
+            lobby_games: row.lobby_games,
+            check_in_end: row.check_in_end,
+            auto_advance: row.auto_advance,
+            round_duration: row.round_duration,
+            max_participants: row.max_participants,
             map: BrawlMap {
                 id: row.map_id,
                 name: row.map_name,
@@ -824,6 +1400,16 @@ impl TournamentDatabase for PgDatabase {
                 t.rounds, t.current_round, 
                 t.created_at, t.start_time, 
                 t.mode as "mode: Mode", 
+                t.format as "format: TournamentFormat",
+                t.seeding_method as "seeding_method: SeedingMethod",
+                t.draw_seed,
+                t.team_size,
+                t.placement_points,
+                t.lobby_games,
+                t.check_in_end,
+                t.auto_advance,
+                t.round_duration,
+                t.max_participants,
                 t.wins_required, 
                 t.tournament_role_id, 
                 t.announcement_channel_id, 
@@ -833,7 +1419,7 @@ impl TournamentDatabase for PgDatabase {
 FROM tournaments AS t
 INNER JOIN tournament_players AS tp ON t.tournament_id = tp.tournament_id
 INNER JOIN brawl_maps AS b ON t.map = b.id
-WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.discord_id = $2;
+WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'registration_closed' OR t.status = 'started') AND tp.discord_id = $2;
             "#,
             guild_id,
             discord_id,
@@ -851,6 +1437,16 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
             created_at: row.created_at,
             start_time: row.start_time,
             mode: row.mode,
+            format: row.format,
+            seeding_method: row.seeding_method,
+            draw_seed: row.draw_seed,
+            team_size: row.team_size,
+            placement_points: row.placement_points,
+            lobby_games: row.lobby_games,
+            check_in_end: row.check_in_end,
+            auto_advance: row.auto_advance,
+            round_duration: row.round_duration,
+            max_participants: row.max_participants,
             map: BrawlMap {
                 id: row.map_id,
                 name: row.map_name,
@@ -882,7 +1478,41 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
         &self,
         tournament_id: i32,
         discord_id: &str,
-    ) -> Result<(), Self::Error> {
+    ) -> Result<bool, Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        // Locking the tournament makes concurrent entries wait, so that they count each other
+        let max_participants = sqlx::query!(
+            r#"
+            SELECT max_participants
+            FROM tournaments
+            WHERE tournament_id = $1
+            FOR UPDATE
+            "#,
+            tournament_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .max_participants;
+
+        if let Some(max_participants) = max_participants {
+            let participants = sqlx::query!(
+                r#"
+                SELECT COUNT(*) AS "count!"
+                FROM tournament_players
+                WHERE tournament_id = $1 AND discord_id <> $2
+                "#,
+                tournament_id,
+                discord_id
+            )
+            .fetch_one(&mut *transaction)
+            .await?
+            .count;
+            if participants >= i64::from(max_participants) {
+                return Ok(false);
+            }
+        }
+
         sqlx::query!(
             r#"
             INSERT INTO tournament_players (tournament_id, discord_id)
@@ -893,10 +1523,12 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
             tournament_id,
             discord_id
         )
-        .execute(&self.pool)
+        .execute(&mut *transaction)
         .await?;
 
-        Ok(())
+        transaction.commit().await?;
+
+        Ok(true)
     }
 
     async fn exit_tournament(
@@ -930,6 +1562,16 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
                 t.rounds, t.current_round, 
                 t.created_at, t.start_time, 
                 t.mode as "mode: Mode", 
+                t.format as "format: TournamentFormat",
+                t.seeding_method as "seeding_method: SeedingMethod",
+                t.draw_seed,
+                t.team_size,
+                t.placement_points,
+                t.lobby_games,
+                t.check_in_end,
+                t.auto_advance,
+                t.round_duration,
+                t.max_participants,
                 t.wins_required, 
                 t.tournament_role_id, 
                 t.announcement_channel_id, 
@@ -959,6 +1601,16 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
             created_at: row.created_at,
             start_time: row.start_time,
             mode: row.mode,
+            format: row.format,
+            seeding_method: row.seeding_method,
+            draw_seed: row.draw_seed,
+            team_size: row.team_size,
+            placement_points: row.placement_points,
+            lobby_games: row.lobby_games,
+            check_in_end: row.check_in_end,
+            auto_advance: row.auto_advance,
+            round_duration: row.round_duration,
+            max_participants: row.max_participants,
             map: BrawlMap {
                 id: row.map_id,
                 name: row.map_name,
@@ -973,6 +1625,56 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
         Ok(tournament)
     }
 
+    async fn open_check_in(&self, tournament_id: i32, check_in_end: i64) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET check_in_end = $1
+            WHERE tournament_id = $2
+            "#,
+            check_in_end,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn check_in(&self, tournament_id: i32, discord_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournament_players
+            SET checked_in = true
+            WHERE tournament_id = $1 AND discord_id = $2
+            "#,
+            tournament_id,
+            discord_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_checked_in_players(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error> {
+        let players = sqlx::query!(
+            r#"
+            SELECT discord_id
+            FROM tournament_players
+            WHERE tournament_id = $1 AND checked_in = true
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.discord_id)
+        .collect();
+
+        Ok(players)
+    }
+
     async fn get_tournament_players(&self, tournament_id: i32) -> Result<Vec<Player>, Self::Error> {
         let players = sqlx::query_as!(
             Player,
@@ -1006,6 +1708,211 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
         Ok(())
     }
 
+    async fn start_round(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        matches: &[Match],
+        lobbies: &[Lobby],
+        deadline: Option<i64>,
+    ) -> Result<bool, Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        let claimed = sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET current_round = $2
+            WHERE tournament_id = $1 AND current_round = $2 - 1
+            "#,
+            tournament_id,
+            round
+        )
+        .execute(&mut *transaction)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for bracket in matches {
+            Self::insert_match(&mut transaction, bracket).await?;
+        }
+        for lobby in lobbies {
+            Self::insert_lobby(&mut transaction, lobby).await?;
+        }
+
+        if let Some(deadline) = deadline {
+            Self::set_round_end(&mut transaction, tournament_id, round, deadline).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn draw_tournament(&self, draw: &TournamentDraw) -> Result<(), Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let tournament_id = draw.tournament_id;
+
+        for discord_id in &draw.dropped {
+            sqlx::query!(
+                r#"
+                DELETE FROM tournament_players
+                WHERE tournament_id = $1 AND discord_id = $2
+                "#,
+                tournament_id,
+                discord_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!(
+                r#"
+                DELETE FROM tournament_teams
+                WHERE tournament_id = $1 AND team_id IN (
+                    SELECT team_id
+                    FROM team_members
+                    WHERE discord_id = $2 AND accepted = true
+                )
+                "#,
+                tournament_id,
+                discord_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        for (index, discord_id) in draw.seeds.iter().enumerate() {
+            sqlx::query!(
+                r#"
+                UPDATE tournament_players
+                SET seed = $1
+                WHERE tournament_id = $2 AND discord_id = $3
+                "#,
+                index as i32 + 1,
+                tournament_id,
+                discord_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        for (index, group) in draw.groups.iter().enumerate() {
+            for discord_id in group {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO tournament_groups (tournament_id, discord_id, group_number)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (tournament_id, discord_id)
+                    DO UPDATE SET group_number = $3
+                    "#,
+                    tournament_id,
+                    discord_id,
+                    index as i32 + 1
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        for standing in &draw.standings {
+            sqlx::query!(
+                r#"
+                INSERT INTO standings (tournament_id, discord_id, wins, losses, byes, buchholz, opponent_win_percentage)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (tournament_id, discord_id)
+                DO UPDATE SET
+                    wins = $3,
+                    losses = $4,
+                    byes = $5,
+                    buchholz = $6,
+                    opponent_win_percentage = $7
+                "#,
+                standing.tournament_id,
+                standing.discord_id,
+                standing.wins,
+                standing.losses,
+                standing.byes,
+                standing.buchholz,
+                standing.opponent_win_percentage
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        for bracket in &draw.matches {
+            Self::insert_match(&mut transaction, bracket).await?;
+        }
+        for lobby in &draw.lobbies {
+            Self::insert_lobby(&mut transaction, lobby).await?;
+        }
+
+        // Move the tournament from round 0 (not started) to the first round
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET
+                seeding_method = $2,
+                draw_seed = COALESCE($3, draw_seed),
+                rounds = $4,
+                current_round = 1
+            WHERE tournament_id = $1
+            "#,
+            tournament_id,
+            draw.seeding as SeedingMethod,
+            draw.draw_seed,
+            draw.rounds
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        if let Some(deadline) = draw.deadline {
+            Self::set_round_end(&mut transaction, tournament_id, 1, deadline).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn start_playoffs(
+        &self,
+        tournament_id: i32,
+        group_rounds: i32,
+        rounds: i32,
+        matches: &[Match],
+        deadline: Option<i64>,
+    ) -> Result<bool, Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        let claimed = sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET rounds = $3, current_round = $2 + 1
+            WHERE tournament_id = $1 AND rounds = $2 AND current_round = $2
+            "#,
+            tournament_id,
+            group_rounds,
+            rounds
+        )
+        .execute(&mut *transaction)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for bracket in matches {
+            Self::insert_match(&mut transaction, bracket).await?;
+        }
+
+        if let Some(deadline) = deadline {
+            Self::set_round_end(&mut transaction, tournament_id, group_rounds + 1, deadline)
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+
     async fn next_round(&self, tournament_id: i32) -> Result<(), Self::Error> {
         sqlx::query!(
             r#"
@@ -1021,33 +1928,70 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
         Ok(())
     }
 
-    async fn set_map(&self, tournament_id: i32, map: &BrawlMap) -> Result<(), Self::Error> {
+    async fn set_round_duration(
+        &self,
+        tournament_id: i32,
+        round_duration: Option<i32>,
+    ) -> Result<(), Self::Error> {
         sqlx::query!(
             r#"
             UPDATE tournaments
-            SET map = $1
+            SET round_duration = $1
             WHERE tournament_id = $2
             "#,
-            map.id,
+            round_duration,
             tournament_id
         )
         .execute(&self.pool)
         .await?;
+
         Ok(())
     }
 
-    async fn set_wins_required(
+    async fn set_tournament_name(&self, tournament_id: i32, name: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET name = $1
+            WHERE tournament_id = $2
+            "#,
+            name,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_tournament_mode(&self, tournament_id: i32, mode: Mode) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET mode = $1
+            WHERE tournament_id = $2
+            "#,
+            mode as Mode,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_tournament_role(
         &self,
-        tournament_id: &i32,
-        wins_required: &i32,
+        tournament_id: i32,
+        role_id: &str,
     ) -> Result<(), Self::Error> {
         sqlx::query!(
             r#"
             UPDATE tournaments
-            SET wins_required = $1
+            SET tournament_role_id = $1
             WHERE tournament_id = $2
             "#,
-            wins_required,
+            role_id,
             tournament_id
         )
         .execute(&self.pool)
@@ -1056,75 +2000,1947 @@ WHERE t.guild_id = $1 AND (t.status = 'pending' OR t.status = 'started') AND tp.
         Ok(())
     }
 
-    async fn current_round(&self, tournament_id: i32) -> Result<i32, Self::Error> {
-        let round = sqlx::query!(
+    async fn set_announcement_channel(
+        &self,
+        tournament_id: i32,
+        channel_id: &str,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
             r#"
-            SELECT current_round
-            FROM tournaments
-            WHERE tournament_id = $1
+            UPDATE tournaments
+            SET announcement_channel_id = $1
+            WHERE tournament_id = $2
             "#,
+            channel_id,
             tournament_id
         )
-        .fetch_one(&self.pool)
-        .await?
-        .current_round;
+        .execute(&self.pool)
+        .await?;
 
-        Ok(round)
+        Ok(())
     }
-}
 
-pub trait MatchDatabase {
-    type Error;
-    /// Creates a match associated with a tournament.
-    async fn create_match(
+    async fn set_notification_channel(
         &self,
         tournament_id: i32,
-        round: i32,
-        sequence_in_round: i32,
-    ) -> Result<(), Self::Error>;
+        channel_id: &str,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET notification_channel_id = $1
+            WHERE tournament_id = $2
+            "#,
+            channel_id,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
 
-    /// Enter a player into a match
-    async fn enter_match(
+        Ok(())
+    }
+
+    async fn set_max_participants(
         &self,
-        match_id: &str,
+        tournament_id: i32,
+        max_participants: Option<i32>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET max_participants = $1
+            WHERE tournament_id = $2
+            "#,
+            max_participants,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn join_waitlist(
+        &self,
+        tournament_id: i32,
         discord_id: &str,
-        player_type: PlayerType,
-    ) -> Result<(), Self::Error>;
+        joined_at: i64,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO tournament_waitlist (tournament_id, discord_id, joined_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tournament_id, discord_id)
+            DO NOTHING
+            "#,
+            tournament_id,
+            discord_id,
+            joined_at
+        )
+        .execute(&self.pool)
+        .await?;
 
-    /// Retrieves all players in a given match.
-    async fn get_match_players(&self, match_id: &str) -> Result<Vec<MatchPlayer>, Self::Error>;
+        Ok(())
+    }
 
-    /// Retrieves a match by its id.
-    async fn get_match_by_id(&self, match_id: &str) -> Result<Option<Match>, Self::Error>;
+    async fn leave_waitlist(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM tournament_waitlist
+            WHERE tournament_id = $1 AND discord_id = $2
+            "#,
+            tournament_id,
+            discord_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_waitlist(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error> {
+        let waitlist = sqlx::query!(
+            r#"
+            SELECT discord_id
+            FROM tournament_waitlist
+            WHERE tournament_id = $1
+            ORDER BY joined_at, discord_id
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.discord_id)
+        .collect();
+
+        Ok(waitlist)
+    }
+
+    async fn get_waitlisted_tournaments(&self, discord_id: &str) -> Result<Vec<i32>, Self::Error> {
+        let tournaments = sqlx::query!(
+            r#"
+            SELECT tournament_id
+            FROM tournament_waitlist
+            WHERE discord_id = $1
+            ORDER BY joined_at
+            "#,
+            discord_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.tournament_id)
+        .collect();
+
+        Ok(tournaments)
+    }
+
+    async fn set_entry_requirements(
+        &self,
+        requirements: &EntryRequirements,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO entry_requirements (tournament_id, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (tournament_id)
+            DO UPDATE SET
+                min_trophies = EXCLUDED.min_trophies,
+                max_trophies = EXCLUDED.max_trophies,
+                min_highest_trophies = EXCLUDED.min_highest_trophies,
+                min_three_vs_three_victories = EXCLUDED.min_three_vs_three_victories,
+                club_tag = EXCLUDED.club_tag,
+                min_brawlers = EXCLUDED.min_brawlers
+            "#,
+            requirements.tournament_id,
+            requirements.min_trophies,
+            requirements.max_trophies,
+            requirements.min_highest_trophies,
+            requirements.min_three_vs_three_victories,
+            requirements.club_tag,
+            requirements.min_brawlers
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_entry_requirements(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Option<EntryRequirements>, Self::Error> {
+        let requirements = sqlx::query_as!(
+            EntryRequirements,
+            r#"
+            SELECT tournament_id, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers
+            FROM entry_requirements
+            WHERE tournament_id = $1
+            "#,
+            tournament_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(requirements)
+    }
+
+    async fn set_start_time(
+        &self,
+        tournament_id: i32,
+        start_time: Option<i64>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET start_time = $1
+            WHERE tournament_id = $2
+            "#,
+            start_time,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_due_tournaments(&self, now: i64) -> Result<Vec<(String, i32)>, Self::Error> {
+        let tournaments = sqlx::query!(
+            r#"
+            SELECT guild_id, tournament_id
+            FROM tournaments
+            WHERE (status = 'pending' OR status = 'registration_closed')
+                AND start_time <= $1
+                AND (check_in_end IS NULL OR check_in_end <= $1)
+            ORDER BY start_time
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.guild_id, row.tournament_id))
+        .collect();
+
+        Ok(tournaments)
+    }
+
+    async fn set_auto_advance(
+        &self,
+        tournament_id: i32,
+        auto_advance: bool,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET auto_advance = $1
+            WHERE tournament_id = $2
+            "#,
+            auto_advance,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_auto_advancing_tournaments(&self) -> Result<Vec<(String, i32)>, Self::Error> {
+        let tournaments = sqlx::query!(
+            r#"
+            SELECT guild_id, tournament_id
+            FROM tournaments
+            WHERE status = 'started' AND auto_advance
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.guild_id, row.tournament_id))
+        .collect();
+
+        Ok(tournaments)
+    }
+
+    async fn get_started_tournaments(&self) -> Result<Vec<(String, i32)>, Self::Error> {
+        let tournaments = sqlx::query!(
+            r#"
+            SELECT guild_id, tournament_id
+            FROM tournaments
+            WHERE status = 'started'
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.guild_id, row.tournament_id))
+        .collect();
+
+        Ok(tournaments)
+    }
+
+    async fn set_map(&self, tournament_id: i32, map: &BrawlMap) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET map = $1
+            WHERE tournament_id = $2
+            "#,
+            map.id,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_wins_required(
+        &self,
+        tournament_id: &i32,
+        wins_required: &i32,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET wins_required = $1
+            WHERE tournament_id = $2
+            "#,
+            wins_required,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_round_wins_required(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        wins_required: i32,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO round_wins_required (tournament_id, round, wins_required)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tournament_id, round)
+            DO UPDATE SET wins_required = $3
+            "#,
+            tournament_id,
+            round,
+            wins_required
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_round_wins_required(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Vec<RoundWinsRequired>, Self::Error> {
+        let overrides = sqlx::query_as!(
+            RoundWinsRequired,
+            r#"
+            SELECT tournament_id, round, wins_required
+            FROM round_wins_required
+            WHERE tournament_id = $1
+            ORDER BY round
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(overrides)
+    }
+
+    async fn ban_brawler(&self, tournament_id: i32, brawler: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO banned_brawlers (tournament_id, brawler)
+            VALUES ($1, $2)
+            ON CONFLICT (tournament_id, brawler)
+            DO NOTHING
+            "#,
+            tournament_id,
+            brawler
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn unban_brawler(&self, tournament_id: i32, brawler: &str) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM banned_brawlers
+            WHERE tournament_id = $1 AND LOWER(brawler) = LOWER($2)
+            "#,
+            tournament_id,
+            brawler
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_banned_brawlers(&self, tournament_id: i32) -> Result<Vec<String>, Self::Error> {
+        let brawlers = sqlx::query!(
+            r#"
+            SELECT brawler
+            FROM banned_brawlers
+            WHERE tournament_id = $1
+            ORDER BY brawler
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.brawler)
+        .collect();
+
+        Ok(brawlers)
+    }
+
+    async fn set_scheduled_map(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        game: i32,
+        map: &BrawlMap,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO map_rotations (tournament_id, round, game, map_id, map_name)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tournament_id, round, game)
+            DO UPDATE SET map_id = $4, map_name = $5
+            "#,
+            tournament_id,
+            round,
+            game,
+            map.id,
+            map.name
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_map_rotation(&self, tournament_id: i32) -> Result<Vec<ScheduledMap>, Self::Error> {
+        let rotation = sqlx::query!(
+            r#"
+            SELECT tournament_id, round, game, map_id, map_name
+            FROM map_rotations
+            WHERE tournament_id = $1
+            ORDER BY round, game
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| ScheduledMap {
+            tournament_id: row.tournament_id,
+            round: row.round,
+            game: row.game,
+            map: BrawlMap {
+                id: row.map_id,
+                name: row.map_name,
+            },
+        })
+        .collect();
+
+        Ok(rotation)
+    }
+
+    async fn current_round(&self, tournament_id: i32) -> Result<i32, Self::Error> {
+        let round = sqlx::query!(
+            r#"
+            SELECT current_round
+            FROM tournaments
+            WHERE tournament_id = $1
+            "#,
+            tournament_id
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .current_round;
+
+        Ok(round)
+    }
+
+    async fn set_standings(&self, standings: &[Standing]) -> Result<(), Self::Error> {
+        for standing in standings {
+            sqlx::query!(
+                r#"
+                INSERT INTO standings (tournament_id, discord_id, wins, losses, byes, buchholz, opponent_win_percentage)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (tournament_id, discord_id)
+                DO UPDATE SET
+                    wins = $3,
+                    losses = $4,
+                    byes = $5,
+                    buchholz = $6,
+                    opponent_win_percentage = $7
+                "#,
+                standing.tournament_id,
+                standing.discord_id,
+                standing.wins,
+                standing.losses,
+                standing.byes,
+                standing.buchholz,
+                standing.opponent_win_percentage
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_standings(&self, tournament_id: i32) -> Result<Vec<Standing>, Self::Error> {
+        let standings = sqlx::query_as!(
+            Standing,
+            r#"
+            SELECT tournament_id, discord_id, wins, losses, byes, buchholz, opponent_win_percentage
+            FROM standings
+            WHERE tournament_id = $1
+            ORDER BY wins DESC, buchholz DESC, opponent_win_percentage DESC
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(standings)
+    }
+
+    async fn set_team_size(&self, tournament_id: i32, team_size: i32) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET team_size = $1
+            WHERE tournament_id = $2
+            "#,
+            team_size,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_showdown_scoring(
+        &self,
+        tournament_id: i32,
+        placement_points: &[i32],
+        lobby_games: i32,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE tournaments
+            SET placement_points = $1, lobby_games = $2
+            WHERE tournament_id = $3
+            "#,
+            placement_points,
+            lobby_games,
+            tournament_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_seeded_players(&self, tournament_id: i32) -> Result<Vec<Player>, Self::Error> {
+        let players = sqlx::query_as!(
+            Player,
+            r#"
+            SELECT users.discord_id, users.discord_name, users.player_name, users.player_tag, users.icon, users.trophies, users.brawlers, users.deleted
+            FROM tournament_players
+            JOIN users ON tournament_players.discord_id = users.discord_id
+            WHERE tournament_players.tournament_id = $1 AND tournament_players.seed IS NOT NULL
+            ORDER BY tournament_players.seed
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(players)
+    }
+
+    async fn get_groups(&self, tournament_id: i32) -> Result<Vec<Vec<String>>, Self::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT group_number, discord_id
+            FROM tournament_groups
+            WHERE tournament_id = $1
+            ORDER BY group_number, discord_id
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut current_group = None;
+        for row in rows {
+            if current_group != Some(row.group_number) {
+                current_group = Some(row.group_number);
+                groups.push(Vec::new());
+            }
+            if let Some(group) = groups.last_mut() {
+                group.push(row.discord_id);
+            }
+        }
+
+        Ok(groups)
+    }
+
+    async fn get_group_standings(
+        &self,
+        tournament_id: i32,
+    ) -> Result<Vec<GroupStanding>, Self::Error> {
+        let standings = sqlx::query_as!(
+            GroupStanding,
+            r#"
+            SELECT
+                g.tournament_id,
+                g.group_number,
+                g.discord_id,
+                COUNT(m.match_id) FILTER (WHERE m.winner = g.discord_id) AS "wins!",
+                COUNT(m.match_id) FILTER (WHERE m.winner <> g.discord_id) AS "losses!"
+            FROM
+                tournament_groups AS g
+            LEFT JOIN
+                match_players AS mp
+            ON
+                mp.discord_id = g.discord_id
+                AND SPLIT_PART(mp.match_id, '.', 1)::int = g.tournament_id -- tournament part
+                AND SPLIT_PART(mp.match_id, '.', 4) = 'G' -- group stage matches only
+            LEFT JOIN
+                matches AS m
+            ON
+                m.match_id = mp.match_id
+            WHERE
+                g.tournament_id = $1
+            GROUP BY
+                g.tournament_id, g.group_number, g.discord_id
+            ORDER BY
+                g.group_number, "wins!" DESC, "losses!" ASC, g.discord_id
+            "#,
+            tournament_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(standings)
+    }
+
+    async fn save_template(&self, tournament_id: i32, name: &str) -> Result<i32, Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        let template_id = sqlx::query!(
+            r#"
+            INSERT INTO tournament_templates (guild_id, name, mode, format, map, wins_required, tournament_role_id, announcement_channel_id, notification_channel_id, team_size, placement_points, lobby_games, auto_advance, round_duration, max_participants, banned_brawlers, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers)
+            SELECT
+                t.guild_id, $2, t.mode, t.format, t.map, t.wins_required, t.tournament_role_id, t.announcement_channel_id, t.notification_channel_id, t.team_size, t.placement_points, t.lobby_games, t.auto_advance, t.round_duration, t.max_participants,
+                ARRAY(SELECT b.brawler FROM banned_brawlers AS b WHERE b.tournament_id = t.tournament_id ORDER BY b.brawler),
+                e.min_trophies, e.max_trophies, e.min_highest_trophies, e.min_three_vs_three_victories, e.club_tag, e.min_brawlers
+            FROM
+                tournaments AS t
+            LEFT JOIN
+                entry_requirements AS e
+            ON
+                e.tournament_id = t.tournament_id
+            WHERE
+                t.tournament_id = $1
+            ON CONFLICT (guild_id, name)
+            DO UPDATE SET
+                mode = EXCLUDED.mode,
+                format = EXCLUDED.format,
+                map = EXCLUDED.map,
+                wins_required = EXCLUDED.wins_required,
+                tournament_role_id = EXCLUDED.tournament_role_id,
+                announcement_channel_id = EXCLUDED.announcement_channel_id,
+                notification_channel_id = EXCLUDED.notification_channel_id,
+                team_size = EXCLUDED.team_size,
+                placement_points = EXCLUDED.placement_points,
+                lobby_games = EXCLUDED.lobby_games,
+                auto_advance = EXCLUDED.auto_advance,
+                round_duration = EXCLUDED.round_duration,
+                max_participants = EXCLUDED.max_participants,
+                banned_brawlers = EXCLUDED.banned_brawlers,
+                min_trophies = EXCLUDED.min_trophies,
+                max_trophies = EXCLUDED.max_trophies,
+                min_highest_trophies = EXCLUDED.min_highest_trophies,
+                min_three_vs_three_victories = EXCLUDED.min_three_vs_three_victories,
+                club_tag = EXCLUDED.club_tag,
+                min_brawlers = EXCLUDED.min_brawlers
+            RETURNING template_id
+            "#,
+            tournament_id,
+            name
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(anyhow!("Tournament {} was not found", tournament_id))?
+        .template_id;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM template_map_rotations
+            WHERE template_id = $1
+            "#,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO template_map_rotations (template_id, round, game, map_id, map_name)
+            SELECT $1, round, game, map_id, map_name
+            FROM map_rotations
+            WHERE tournament_id = $2
+            "#,
+            template_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM template_round_wins_required
+            WHERE template_id = $1
+            "#,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO template_round_wins_required (template_id, round, wins_required)
+            SELECT $1, round, wins_required
+            FROM round_wins_required
+            WHERE tournament_id = $2
+            "#,
+            template_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(template_id)
+    }
+
+    async fn get_templates(&self, guild_id: &str) -> Result<Vec<TournamentTemplate>, Self::Error> {
+        let templates = sqlx::query_as!(
+            TournamentTemplate,
+            r#"
+            SELECT
+                template_id,
+                guild_id,
+                name,
+                mode AS "mode: Mode",
+                format AS "format: TournamentFormat",
+                wins_required,
+                team_size,
+                max_participants,
+                tournament_role_id,
+                announcement_channel_id,
+                notification_channel_id
+            FROM tournament_templates
+            WHERE guild_id = $1
+            ORDER BY name
+            "#,
+            guild_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    async fn create_tournament_from_template(
+        &self,
+        template_id: i32,
+        name: &str,
+    ) -> Result<i32, Self::Error> {
+        let timestamp_time = chrono::offset::Utc::now().timestamp();
+        let mut transaction = self.pool.begin().await?;
+
+        let tournament_id = sqlx::query!(
+            r#"
+            INSERT INTO tournaments (guild_id, name, mode, format, created_at, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required, map, team_size, placement_points, lobby_games, auto_advance, round_duration, max_participants)
+            SELECT guild_id, $2, mode, format, $3, 0, 0, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required, map, team_size, placement_points, lobby_games, auto_advance, round_duration, max_participants
+            FROM tournament_templates
+            WHERE template_id = $1
+            RETURNING tournament_id
+            "#,
+            template_id,
+            name,
+            timestamp_time
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(anyhow!("Template {} was not found", template_id))?
+        .tournament_id;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO banned_brawlers (tournament_id, brawler)
+            SELECT $1, UNNEST(banned_brawlers)
+            FROM tournament_templates
+            WHERE template_id = $2
+            "#,
+            tournament_id,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO entry_requirements (tournament_id, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers)
+            SELECT $1, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers
+            FROM tournament_templates
+            WHERE template_id = $2
+            "#,
+            tournament_id,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO map_rotations (tournament_id, round, game, map_id, map_name)
+            SELECT $1, round, game, map_id, map_name
+            FROM template_map_rotations
+            WHERE template_id = $2
+            "#,
+            tournament_id,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO round_wins_required (tournament_id, round, wins_required)
+            SELECT $1, round, wins_required
+            FROM template_round_wins_required
+            WHERE template_id = $2
+            "#,
+            tournament_id,
+            template_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(tournament_id)
+    }
+
+    async fn clone_tournament(&self, tournament_id: i32, name: &str) -> Result<i32, Self::Error> {
+        let timestamp_time = chrono::offset::Utc::now().timestamp();
+        let mut transaction = self.pool.begin().await?;
+
+        let new_tournament_id = sqlx::query!(
+            r#"
+            INSERT INTO tournaments (guild_id, name, mode, format, created_at, rounds, current_round, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required, map, team_size, placement_points, lobby_games, auto_advance, round_duration, max_participants)
+            SELECT guild_id, $2, mode, format, $3, 0, 0, tournament_role_id, announcement_channel_id, notification_channel_id, wins_required, map, team_size, placement_points, lobby_games, auto_advance, round_duration, max_participants
+            FROM tournaments
+            WHERE tournament_id = $1
+            RETURNING tournament_id
+            "#,
+            tournament_id,
+            name,
+            timestamp_time
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(anyhow!("Tournament {} was not found", tournament_id))?
+        .tournament_id;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO banned_brawlers (tournament_id, brawler)
+            SELECT $1, brawler
+            FROM banned_brawlers
+            WHERE tournament_id = $2
+            "#,
+            new_tournament_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO entry_requirements (tournament_id, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers)
+            SELECT $1, min_trophies, max_trophies, min_highest_trophies, min_three_vs_three_victories, club_tag, min_brawlers
+            FROM entry_requirements
+            WHERE tournament_id = $2
+            "#,
+            new_tournament_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO map_rotations (tournament_id, round, game, map_id, map_name)
+            SELECT $1, round, game, map_id, map_name
+            FROM map_rotations
+            WHERE tournament_id = $2
+            "#,
+            new_tournament_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO round_wins_required (tournament_id, round, wins_required)
+            SELECT $1, round, wins_required
+            FROM round_wins_required
+            WHERE tournament_id = $2
+            "#,
+            new_tournament_id,
+            tournament_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(new_tournament_id)
+    }
+
+    async fn add_recurring_tournament(
+        &self,
+        recurrence: &RecurringTournament,
+    ) -> Result<i32, Self::Error> {
+        let recurrence_id = sqlx::query!(
+            r#"
+            INSERT INTO recurring_tournaments (guild_id, template_id, name, weekday, minute_of_day, registration_hours, auto_start, next_start)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING recurrence_id
+            "#,
+            recurrence.guild_id,
+            recurrence.template_id,
+            recurrence.name,
+            recurrence.weekday,
+            recurrence.minute_of_day,
+            recurrence.registration_hours,
+            recurrence.auto_start,
+            recurrence.next_start
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .recurrence_id;
+
+        Ok(recurrence_id)
+    }
+
+    async fn remove_recurring_tournament(
+        &self,
+        guild_id: &str,
+        recurrence_id: i32,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM recurring_tournaments
+            WHERE guild_id = $1 AND recurrence_id = $2
+            "#,
+            guild_id,
+            recurrence_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_recurring_tournaments(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<RecurringTournament>, Self::Error> {
+        let recurrences = sqlx::query_as!(
+            RecurringTournament,
+            r#"
+            SELECT recurrence_id, guild_id, template_id, name, weekday, minute_of_day, registration_hours, auto_start, next_start
+            FROM recurring_tournaments
+            WHERE guild_id = $1
+            ORDER BY next_start
+            "#,
+            guild_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recurrences)
+    }
+
+    async fn get_due_recurring_tournaments(
+        &self,
+        now: i64,
+    ) -> Result<Vec<RecurringTournament>, Self::Error> {
+        let recurrences = sqlx::query_as!(
+            RecurringTournament,
+            r#"
+            SELECT recurrence_id, guild_id, template_id, name, weekday, minute_of_day, registration_hours, auto_start, next_start
+            FROM recurring_tournaments
+            WHERE next_start - registration_hours * 3600 <= $1
+            ORDER BY next_start
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recurrences)
+    }
+
+    async fn set_next_start(&self, recurrence_id: i32, next_start: i64) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE recurring_tournaments
+            SET next_start = $1
+            WHERE recurrence_id = $2
+            "#,
+            next_start,
+            recurrence_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+pub trait MatchDatabase {
+    type Error;
+    /// Creates a match associated with a tournament.
+    ///
+    /// The match ID encodes the tournament, round, sequence and bracket of the match.
+    /// See [`Match::generate_bracket_id`].
+    async fn create_match(&self, match_id: &str) -> Result<(), Self::Error>;
+
+    /// Enter a player into a match
+    async fn enter_match(
+        &self,
+        match_id: &str,
+        discord_id: &str,
+        player_type: PlayerType,
+    ) -> Result<(), Self::Error>;
+
+    /// Retrieves all players in a given match.
+    async fn get_match_players(&self, match_id: &str) -> Result<Vec<MatchPlayer>, Self::Error>;
+
+    /// Retrieves a match by its id.
+    async fn get_match_by_id(&self, match_id: &str) -> Result<Option<Match>, Self::Error>;
+
+    /// Retrieves a match by the player's discord id.
+    ///
+    /// This will retrive the match with the highest round number that does not yet have a winner.
+    async fn get_match_by_player(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Match>, Self::Error>;
+
+    /// Retrieves all matches associated with a tournament.
+    ///
+    /// Pass in a None for the round number to retrieve all matches for the tournament.
+    async fn get_matches_by_tournament(
+        &self,
+        tournament_id: i32,
+        round: impl Into<Option<i32>>,
+    ) -> Result<Vec<Match>, Self::Error>;
+
+    /// Sets the deadline of every undecided match in a round, or removes it with None.
+    ///
+    /// Matches that were escalated to the marshals are given a new chance.
+    async fn set_round_deadline(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        deadline: Option<i64>,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the guild and ID of every undecided match of a running tournament whose deadline has
+    /// passed and that has not been escalated yet.
+    async fn get_overdue_matches(&self, now: i64) -> Result<Vec<(String, String)>, Self::Error>;
+
+    /// Marks a match as handed over to the marshals after its deadline passed.
+    async fn escalate_match(&self, match_id: &str) -> Result<(), Self::Error>;
+
+    /// Proposes a time to play a match, replacing any earlier proposal.
+    async fn propose_match_time(
+        &self,
+        match_id: &str,
+        proposer: &str,
+        proposed_time: i64,
+    ) -> Result<(), Self::Error>;
+
+    /// Gets the time proposed or agreed on for a match.
+    async fn get_match_schedule(&self, match_id: &str)
+        -> Result<Option<MatchSchedule>, Self::Error>;
+
+    /// Accepts the time proposed for a match by the opponent of the acceptor.
+    ///
+    /// Returns false without accepting anything if the stored proposal is no longer the given
+    /// time, or was made by the acceptor themselves.
+    async fn accept_match_time(
+        &self,
+        match_id: &str,
+        acceptor: &str,
+        proposed_time: i64,
+    ) -> Result<bool, Self::Error>;
+
+    /// Gets the guild and schedule of every undecided match agreed to start before the given time
+    /// whose players have not been reminded yet.
+    async fn get_due_reminders(
+        &self,
+        before: i64,
+    ) -> Result<Vec<(String, MatchSchedule)>, Self::Error>;
+
+    /// Marks the players of a match as reminded of it.
+    async fn set_reminded(&self, match_id: &str) -> Result<(), Self::Error>;
+
+    /// Records a brawler banned by a player during the draft of a match.
+    ///
+    /// Returns false if the turn was already taken or the brawler was already banned.
+    async fn add_match_ban(
+        &self,
+        match_id: &str,
+        turn: i32,
+        discord_id: &str,
+        brawler: &str,
+    ) -> Result<bool, Self::Error>;
+
+    /// Gets the brawlers banned during the draft of a match, in the order they were banned.
+    async fn get_match_bans(&self, match_id: &str) -> Result<Vec<MatchBan>, Self::Error>;
+
+    /// Sets the winner and score of a match on behalf of a marshal and records the override, all
+    /// in one transaction.
+    ///
+    /// Each replacement, given as `(match_id, discord_id, replacement_id)`, puts another player in
+    /// the place of a player of a later match. The ready status, draft and schedule of those
+    /// matches are cleared, since the new player did not take part in them.
+    async fn override_result(
+        &self,
+        record: &MatchOverride,
+        replacements: &[(String, String, String)],
+    ) -> Result<(), Self::Error>;
+
+    /// Clears the result of a match so that it can be played again from `start`.
+    ///
+    /// The winner, score, ready status and battles recorded for the match are removed, and a
+    /// deadline that has already passed is lifted.
+    async fn reset_match(&self, match_id: &str, start: i64) -> Result<(), Self::Error>;
+
+    /// Gets the overrides of a match, from the oldest.
+    async fn get_match_overrides(&self, match_id: &str)
+        -> Result<Vec<MatchOverride>, Self::Error>;
+
+    /// Gets the time the last result of a round was decided. Byes are left out, since their
+    /// result cannot be disputed.
+    async fn get_round_decided_at(
+        &self,
+        tournament_id: i32,
+        round: i32,
+    ) -> Result<Option<i64>, Self::Error>;
+
+    /// Retrieves the last match of a player in a tournament that has a winner.
+    async fn get_last_decided_match(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Match>, Self::Error>;
+
+    /// Opens a dispute about the result of a match. Returns the id of the dispute, or None if the
+    /// result of the match was already disputed.
+    async fn open_dispute(
+        &self,
+        match_id: &str,
+        discord_id: &str,
+        statement: &str,
+        evidence: &[String],
+        created_at: i64,
+    ) -> Result<Option<i32>, Self::Error>;
+
+    /// Records the thread where the marshals were pinged about a dispute.
+    async fn set_dispute_thread(&self, dispute_id: i32, thread_id: &str)
+        -> Result<(), Self::Error>;
+
+    /// Retrieves a dispute by its id.
+    async fn get_dispute(&self, dispute_id: i32) -> Result<Option<MatchDispute>, Self::Error>;
+
+    /// Gets the disputes about the result of a match, from the oldest.
+    async fn get_match_disputes(&self, match_id: &str) -> Result<Vec<MatchDispute>, Self::Error>;
+
+    /// Gets the open disputes of every tournament of a guild, from the oldest.
+    async fn get_open_disputes(&self, guild_id: &str) -> Result<Vec<MatchDispute>, Self::Error>;
+
+    /// Closes an open dispute as accepted or rejected.
+    ///
+    /// Returns false if the dispute was already resolved.
+    async fn resolve_dispute(
+        &self,
+        dispute_id: i32,
+        status: DisputeStatus,
+        resolved_by: &str,
+        resolution: &str,
+        resolved_at: i64,
+    ) -> Result<bool, Self::Error>;
+
+    /// Opens a resolved dispute again, for when its decision could not be carried out.
+    async fn reopen_dispute(&self, dispute_id: i32) -> Result<(), Self::Error>;
+
+    /// Adds evidence to an open dispute.
+    ///
+    /// Returns false if the dispute is not open.
+    async fn add_dispute_evidence(
+        &self,
+        dispute_id: i32,
+        evidence: &[String],
+    ) -> Result<bool, Self::Error>;
+}
+
+impl MatchDatabase for PgDatabase {
+    type Error = BotError;
+    async fn set_round_deadline(
+        &self,
+        tournament_id: i32,
+        round: i32,
+        deadline: Option<i64>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE matches
+            SET "end" = $1, escalated = false
+            WHERE
+                SPLIT_PART(match_id, '.', 1)::int = $2
+                AND SPLIT_PART(match_id, '.', 2)::int = $3
+                AND winner IS NULL
+            "#,
+            deadline,
+            tournament_id,
+            round
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_overdue_matches(&self, now: i64) -> Result<Vec<(String, String)>, Self::Error> {
+        let matches = sqlx::query!(
+            r#"
+            SELECT t.guild_id, m.match_id
+            FROM matches AS m
+            JOIN tournaments AS t
+                ON t.tournament_id = SPLIT_PART(m.match_id, '.', 1)::int
+            WHERE t.status = 'started'
+                AND m.winner IS NULL
+                AND m.escalated = false
+                AND m."end" <= $1
+            ORDER BY m."end"
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.guild_id, row.match_id))
+        .collect();
+
+        Ok(matches)
+    }
+
+    async fn propose_match_time(
+        &self,
+        match_id: &str,
+        proposer: &str,
+        proposed_time: i64,
+    ) -> Result<(), Self::Error> {
+        let time_of_proposal = chrono::Utc::now().timestamp();
+        sqlx::query!(
+            r#"
+            INSERT INTO match_schedules (match_id, proposed_time, time_of_proposal, proposer)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (match_id) DO UPDATE
+            SET proposed_time = $2,
+                time_of_proposal = $3,
+                proposer = $4,
+                accepted = false,
+                reminded = false
+            "#,
+            match_id,
+            proposed_time,
+            time_of_proposal,
+            proposer
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_match_schedule(
+        &self,
+        match_id: &str,
+    ) -> Result<Option<MatchSchedule>, Self::Error> {
+        let schedule = sqlx::query_as!(
+            MatchSchedule,
+            r#"
+            SELECT match_id, proposed_time, time_of_proposal, proposer, accepted, reminded
+            FROM match_schedules
+            WHERE match_id = $1
+            "#,
+            match_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    async fn accept_match_time(
+        &self,
+        match_id: &str,
+        acceptor: &str,
+        proposed_time: i64,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE match_schedules
+            SET accepted = true
+            WHERE match_id = $1 AND proposer <> $2 AND proposed_time = $3
+            "#,
+            match_id,
+            acceptor,
+            proposed_time
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_due_reminders(
+        &self,
+        before: i64,
+    ) -> Result<Vec<(String, MatchSchedule)>, Self::Error> {
+        let reminders = sqlx::query!(
+            r#"
+            SELECT t.guild_id, s.match_id, s.proposed_time, s.time_of_proposal, s.proposer,
+                s.accepted, s.reminded
+            FROM match_schedules AS s
+            JOIN matches AS m ON m.match_id = s.match_id
+            JOIN tournaments AS t
+                ON t.tournament_id = SPLIT_PART(s.match_id, '.', 1)::int
+            WHERE t.status = 'started'
+                AND m.winner IS NULL
+                AND s.accepted = true
+                AND s.reminded = false
+                AND s.proposed_time <= $1
+            "#,
+            before
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            (
+                row.guild_id,
+                MatchSchedule {
+                    match_id: row.match_id,
+                    proposed_time: row.proposed_time,
+                    time_of_proposal: row.time_of_proposal,
+                    proposer: row.proposer,
+                    accepted: row.accepted,
+                    reminded: row.reminded,
+                },
+            )
+        })
+        .collect();
+
+        Ok(reminders)
+    }
+
+    async fn set_reminded(&self, match_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE match_schedules
+            SET reminded = true
+            WHERE match_id = $1
+            "#,
+            match_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn add_match_ban(
+        &self,
+        match_id: &str,
+        turn: i32,
+        discord_id: &str,
+        brawler: &str,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO match_bans (match_id, turn, discord_id, brawler)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+            match_id,
+            turn,
+            discord_id,
+            brawler
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn override_result(
+        &self,
+        record: &MatchOverride,
+        replacements: &[(String, String, String)],
+    ) -> Result<(), Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        for (match_id, discord_id, replacement_id) in replacements {
+            sqlx::query!(
+                r#"
+                UPDATE match_players
+                SET discord_id = $3
+                WHERE match_id = $1 AND discord_id = $2
+                "#,
+                match_id,
+                discord_id,
+                replacement_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE match_players
+                SET ready = false
+                WHERE match_id = $1
+                "#,
+                match_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM match_bans
+                WHERE match_id = $1
+                "#,
+                match_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query!(
+                r#"
+                DELETE FROM match_schedules
+                WHERE match_id = $1
+                "#,
+                match_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE matches
+            SET winner = $1, score = $2, decided_at = $3
+            WHERE match_id = $4
+            "#,
+            record.winner,
+            record.score,
+            record.created_at,
+            record.match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO match_overrides (match_id, previous_winner, previous_score, winner, score, reason, marshal_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            record.match_id,
+            record.previous_winner,
+            record.previous_score,
+            record.winner,
+            record.score,
+            record.reason,
+            record.marshal_id,
+            record.created_at
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn reset_match(&self, match_id: &str, start: i64) -> Result<(), Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE battle_id IN (
+                SELECT b.id
+                FROM battles AS b
+                INNER JOIN battle_records AS r ON r.record_id = b.record_id
+                WHERE r.match_id = $1
+            )
+            "#,
+            match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM battle_classes
+            WHERE battle_id IN (
+                SELECT b.id
+                FROM battles AS b
+                INNER JOIN battle_records AS r ON r.record_id = b.record_id
+                WHERE r.match_id = $1
+            )
+            "#,
+            match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM battles
+            WHERE record_id IN (
+                SELECT record_id
+                FROM battle_records
+                WHERE match_id = $1
+            )
+            "#,
+            match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM battle_records
+            WHERE match_id = $1
+            "#,
+            match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE matches
+            SET
+                winner = NULL,
+                score = '0-0',
+                start = $2,
+                "end" = CASE WHEN "end" <= $2 THEN NULL ELSE "end" END,
+                escalated = false,
+                decided_at = NULL
+            WHERE match_id = $1
+            "#,
+            match_id,
+            start
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE match_players
+            SET ready = false
+            WHERE match_id = $1
+            "#,
+            match_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_match_overrides(
+        &self,
+        match_id: &str,
+    ) -> Result<Vec<MatchOverride>, Self::Error> {
+        let overrides = sqlx::query_as!(
+            MatchOverride,
+            r#"
+            SELECT override_id, match_id, previous_winner, previous_score, winner, score, reason, marshal_id, created_at
+            FROM match_overrides
+            WHERE match_id = $1
+            ORDER BY created_at, override_id
+            "#,
+            match_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(overrides)
+    }
+
+    async fn get_round_decided_at(
+        &self,
+        tournament_id: i32,
+        round: i32,
+    ) -> Result<Option<i64>, Self::Error> {
+        let decided_at = sqlx::query!(
+            r#"
+            SELECT MAX(decided_at) AS decided_at
+            FROM matches
+            WHERE
+                SPLIT_PART(match_id, '.', 1)::int = $1
+                AND SPLIT_PART(match_id, '.', 2)::int = $2
+                AND score <> 'bye'
+            "#,
+            tournament_id,
+            round
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .decided_at;
+
+        Ok(decided_at)
+    }
+
+    async fn get_last_decided_match(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Match>, Self::Error> {
+        let bracket = match sqlx::query!(
+            r#"
+            SELECT
+                match_id,
+                winner,
+                score,
+                start,
+                "end"
+            FROM matches
+            WHERE
+                SPLIT_PART(match_id, '.', 1)::int = $1 -- Extract and match the tournament part
+                AND winner IS NOT NULL
+                AND match_id IN (
+                    SELECT match_id
+                    FROM match_players
+                    WHERE discord_id = $2
+                )
+            ORDER BY
+                SPLIT_PART(match_id, '.', 2)::int DESC -- Order by round part
+            LIMIT 1
+            "#,
+            tournament_id,
+            discord_id,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        {
+            Some(r) => {
+                let players = self.get_match_players(&r.match_id).await?;
+                Some(Match {
+                    match_id: r.match_id,
+                    match_players: players,
+                    winner: r.winner,
+                    score: r.score,
+                    start: r.start,
+                    end: r.end,
+                })
+            }
+            None => None,
+        };
+
+        Ok(bracket)
+    }
+
+    async fn open_dispute(
+        &self,
+        match_id: &str,
+        discord_id: &str,
+        statement: &str,
+        evidence: &[String],
+        created_at: i64,
+    ) -> Result<Option<i32>, Self::Error> {
+        let dispute_id = sqlx::query!(
+            r#"
+            INSERT INTO match_disputes (match_id, discord_id, statement, evidence, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (match_id) DO NOTHING
+            RETURNING dispute_id
+            "#,
+            match_id,
+            discord_id,
+            statement,
+            evidence,
+            created_at
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| row.dispute_id);
+
+        Ok(dispute_id)
+    }
+
+    async fn set_dispute_thread(
+        &self,
+        dispute_id: i32,
+        thread_id: &str,
+    ) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE match_disputes
+            SET thread_id = $1
+            WHERE dispute_id = $2
+            "#,
+            thread_id,
+            dispute_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_dispute(&self, dispute_id: i32) -> Result<Option<MatchDispute>, Self::Error> {
+        let dispute = sqlx::query_as!(
+            MatchDispute,
+            r#"
+            SELECT
+                dispute_id,
+                match_id,
+                discord_id,
+                statement,
+                evidence,
+                thread_id,
+                status AS "status: DisputeStatus",
+                resolved_by,
+                resolution,
+                created_at,
+                resolved_at
+            FROM match_disputes
+            WHERE dispute_id = $1
+            "#,
+            dispute_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(dispute)
+    }
+
+    async fn get_match_disputes(&self, match_id: &str) -> Result<Vec<MatchDispute>, Self::Error> {
+        let disputes = sqlx::query_as!(
+            MatchDispute,
+            r#"
+            SELECT
+                dispute_id,
+                match_id,
+                discord_id,
+                statement,
+                evidence,
+                thread_id,
+                status AS "status: DisputeStatus",
+                resolved_by,
+                resolution,
+                created_at,
+                resolved_at
+            FROM match_disputes
+            WHERE match_id = $1
+            ORDER BY created_at, dispute_id
+            "#,
+            match_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(disputes)
+    }
+
+    async fn get_open_disputes(&self, guild_id: &str) -> Result<Vec<MatchDispute>, Self::Error> {
+        let disputes = sqlx::query_as!(
+            MatchDispute,
+            r#"
+            SELECT
+                d.dispute_id,
+                d.match_id,
+                d.discord_id,
+                d.statement,
+                d.evidence,
+                d.thread_id,
+                d.status AS "status: DisputeStatus",
+                d.resolved_by,
+                d.resolution,
+                d.created_at,
+                d.resolved_at
+            FROM
+                match_disputes AS d
+            INNER JOIN
+                tournaments AS t
+            ON
+                t.tournament_id = SPLIT_PART(d.match_id, '.', 1)::int -- tournament part
+            WHERE
+                t.guild_id = $1
+                AND d.status = 'open'
+            ORDER BY
+                d.created_at, d.dispute_id
+            "#,
+            guild_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(disputes)
+    }
+
+    async fn resolve_dispute(
+        &self,
+        dispute_id: i32,
+        status: DisputeStatus,
+        resolved_by: &str,
+        resolution: &str,
+        resolved_at: i64,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE match_disputes
+            SET status = $1, resolved_by = $2, resolution = $3, resolved_at = $4
+            WHERE dispute_id = $5 AND status = 'open'
+            "#,
+            status as DisputeStatus,
+            resolved_by,
+            resolution,
+            resolved_at,
+            dispute_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn reopen_dispute(&self, dispute_id: i32) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE match_disputes
+            SET status = 'open', resolved_by = NULL, resolution = NULL, resolved_at = NULL
+            WHERE dispute_id = $1
+            "#,
+            dispute_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn add_dispute_evidence(
+        &self,
+        dispute_id: i32,
+        evidence: &[String],
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE match_disputes
+            SET evidence = evidence || $1
+            WHERE dispute_id = $2 AND status = 'open'
+            "#,
+            evidence,
+            dispute_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_match_bans(&self, match_id: &str) -> Result<Vec<MatchBan>, Self::Error> {
+        let bans = sqlx::query_as!(
+            MatchBan,
+            r#"
+            SELECT match_id, turn, discord_id, brawler
+            FROM match_bans
+            WHERE match_id = $1
+            ORDER BY turn
+            "#,
+            match_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bans)
+    }
 
-    /// Retrieves a match by the player's discord id.
-    ///
-    /// This will retrive the match with the highest round number that does not yet have a winner.
-    async fn get_match_by_player(
-        &self,
-        tournament_id: i32,
-        discord_id: &str,
-    ) -> Result<Option<Match>, Self::Error>;
+    async fn escalate_match(&self, match_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE matches
+            SET escalated = true
+            WHERE match_id = $1
+            "#,
+            match_id
+        )
+        .execute(&self.pool)
+        .await?;
 
-    /// Retrieves all matches associated with a tournament.
-    ///
-    /// Pass in a None for the round number to retrieve all matches for the tournament.
-    async fn get_matches_by_tournament(
-        &self,
-        tournament_id: i32,
-        round: impl Into<Option<i32>>,
-    ) -> Result<Vec<Match>, Self::Error>;
-}
+        Ok(())
+    }
 
-impl MatchDatabase for PgDatabase {
-    type Error = BotError;
-    async fn create_match(
-        &self,
-        tournament_id: i32,
-        round: i32,
-        sequence_in_round: i32,
-    ) -> Result<(), Self::Error> {
-        let match_id = Match::generate_id(tournament_id, round, sequence_in_round);
+    async fn create_match(&self, match_id: &str) -> Result<(), Self::Error> {
         let start = chrono::Utc::now().timestamp();
         sqlx::query!(
             r#"
@@ -1336,6 +4152,412 @@ impl MatchDatabase for PgDatabase {
         Ok(brackets)
     }
 }
+pub trait LobbyDatabase {
+    type Error;
+    /// Retrieves all lobbies of a tournament round.
+    async fn get_lobbies(&self, tournament_id: i32, round: i32) -> Result<Vec<Lobby>, Self::Error>;
+
+    /// Retrieves the lobby of the latest round that a player is in.
+    async fn get_lobby_by_player(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Lobby>, Self::Error>;
+
+    /// Records the placement of a player in a game of a lobby.
+    async fn add_lobby_result(&self, result: &LobbyResult) -> Result<(), Self::Error>;
+
+    /// Retrieves the placements of every game played in a lobby.
+    async fn get_lobby_results(&self, lobby_id: &str) -> Result<Vec<LobbyResult>, Self::Error>;
+}
+
+impl LobbyDatabase for PgDatabase {
+    type Error = BotError;
+
+    async fn get_lobbies(&self, tournament_id: i32, round: i32) -> Result<Vec<Lobby>, Self::Error> {
+        let lobbies = sqlx::query_as!(
+            Lobby,
+            r#"
+            SELECT
+                lobbies.lobby_id,
+                lobbies.tournament_id,
+                lobbies.round,
+                ARRAY(
+                    SELECT discord_id FROM lobby_players
+                    WHERE lobby_players.lobby_id = lobbies.lobby_id
+                    ORDER BY discord_id
+                ) AS "players!",
+                (
+                    SELECT COUNT(DISTINCT game_number)::INT FROM lobby_results
+                    WHERE lobby_results.lobby_id = lobbies.lobby_id
+                ) AS "games_played!",
+                GREATEST(lobbies.start, (
+                    SELECT MAX(battle_time) FROM lobby_results
+                    WHERE lobby_results.lobby_id = lobbies.lobby_id
+                )) AS "last_battle!"
+            FROM lobbies
+            WHERE lobbies.tournament_id = $1 AND lobbies.round = $2
+            ORDER BY SPLIT_PART(lobbies.lobby_id, '.', 3)::INT
+            "#,
+            tournament_id,
+            round
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lobbies)
+    }
+
+    async fn get_lobby_by_player(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Lobby>, Self::Error> {
+        let lobby = sqlx::query_as!(
+            Lobby,
+            r#"
+            SELECT
+                lobbies.lobby_id,
+                lobbies.tournament_id,
+                lobbies.round,
+                ARRAY(
+                    SELECT discord_id FROM lobby_players
+                    WHERE lobby_players.lobby_id = lobbies.lobby_id
+                    ORDER BY discord_id
+                ) AS "players!",
+                (
+                    SELECT COUNT(DISTINCT game_number)::INT FROM lobby_results
+                    WHERE lobby_results.lobby_id = lobbies.lobby_id
+                ) AS "games_played!",
+                GREATEST(lobbies.start, (
+                    SELECT MAX(battle_time) FROM lobby_results
+                    WHERE lobby_results.lobby_id = lobbies.lobby_id
+                )) AS "last_battle!"
+            FROM lobbies
+            JOIN lobby_players ON lobbies.lobby_id = lobby_players.lobby_id
+            WHERE lobbies.tournament_id = $1 AND lobby_players.discord_id = $2
+            ORDER BY lobbies.round DESC
+            LIMIT 1
+            "#,
+            tournament_id,
+            discord_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(lobby)
+    }
+
+    async fn add_lobby_result(&self, result: &LobbyResult) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO lobby_results (lobby_id, game_number, discord_id, placement, points, battle_time)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (lobby_id, game_number, discord_id)
+            DO UPDATE SET placement = $4, points = $5, battle_time = $6
+            "#,
+            result.lobby_id,
+            result.game_number,
+            result.discord_id,
+            result.placement,
+            result.points,
+            result.battle_time
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_lobby_results(&self, lobby_id: &str) -> Result<Vec<LobbyResult>, Self::Error> {
+        let results = sqlx::query_as!(
+            LobbyResult,
+            r#"
+            SELECT lobby_id, game_number, discord_id, placement, points, battle_time
+            FROM lobby_results
+            WHERE lobby_id = $1
+            ORDER BY game_number, placement
+            "#,
+            lobby_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results)
+    }
+}
+
+pub trait TeamDatabase {
+    type Error;
+    /// Creates a team in a guild with the given player as its captain and only member.
+    async fn create_team(
+        &self,
+        guild_id: &str,
+        name: &str,
+        captain_id: &str,
+    ) -> Result<Team, Self::Error>;
+
+    /// Deletes a team along with its members and tournament registrations.
+    async fn delete_team(&self, team_id: i32) -> Result<(), Self::Error>;
+
+    /// Gets the team a player is a member of within a guild, if any.
+    ///
+    /// Pending invites are not counted as memberships.
+    async fn get_team_by_member(
+        &self,
+        guild_id: &str,
+        discord_id: &str,
+    ) -> Result<Option<Team>, Self::Error>;
+
+    /// Gets the members of a team that have accepted their invite, captain first.
+    async fn get_team_members(&self, team_id: i32) -> Result<Vec<Player>, Self::Error>;
+
+    /// Invites a player to a team. The player only becomes a member after accepting.
+    async fn invite_team_member(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error>;
+
+    /// Gets the teams within a guild that have a pending invite for a player.
+    async fn get_team_invites(
+        &self,
+        guild_id: &str,
+        discord_id: &str,
+    ) -> Result<Vec<Team>, Self::Error>;
+
+    /// Accepts a pending invite to a team.
+    async fn accept_team_invite(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error>;
+
+    /// Removes a member or a pending invite from a team.
+    async fn remove_team_member(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error>;
+
+    /// Registers a team for a tournament.
+    async fn register_team(&self, tournament_id: i32, team_id: i32) -> Result<(), Self::Error>;
+
+    /// Removes the registration of a team from a tournament.
+    async fn unregister_team(&self, tournament_id: i32, team_id: i32) -> Result<(), Self::Error>;
+
+    /// Gets the team registered for a tournament that a player belongs to, if any.
+    async fn get_tournament_team(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Team>, Self::Error>;
+}
+
+impl TeamDatabase for PgDatabase {
+    type Error = BotError;
+
+    async fn create_team(
+        &self,
+        guild_id: &str,
+        name: &str,
+        captain_id: &str,
+    ) -> Result<Team, Self::Error> {
+        let team = sqlx::query_as!(
+            Team,
+            r#"
+            INSERT INTO teams (guild_id, name, captain_id)
+            VALUES ($1, $2, $3)
+            RETURNING team_id, guild_id, name, captain_id
+            "#,
+            guild_id,
+            name,
+            captain_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO team_members (team_id, discord_id, accepted)
+            VALUES ($1, $2, true)
+            "#,
+            team.team_id,
+            captain_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(team)
+    }
+
+    async fn delete_team(&self, team_id: i32) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM teams
+            WHERE team_id = $1
+            "#,
+            team_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_team_by_member(
+        &self,
+        guild_id: &str,
+        discord_id: &str,
+    ) -> Result<Option<Team>, Self::Error> {
+        let team = sqlx::query_as!(
+            Team,
+            r#"
+            SELECT teams.team_id, teams.guild_id, teams.name, teams.captain_id
+            FROM teams
+            JOIN team_members ON teams.team_id = team_members.team_id
+            WHERE teams.guild_id = $1 AND team_members.discord_id = $2 AND team_members.accepted = true
+            "#,
+            guild_id,
+            discord_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(team)
+    }
+
+    async fn get_team_members(&self, team_id: i32) -> Result<Vec<Player>, Self::Error> {
+        let players = sqlx::query_as!(
+            Player,
+            r#"
+            SELECT users.discord_id, users.discord_name, users.player_name, users.player_tag, users.icon, users.trophies, users.brawlers, users.deleted
+            FROM team_members
+            JOIN teams ON team_members.team_id = teams.team_id
+            JOIN users ON team_members.discord_id = users.discord_id
+            WHERE team_members.team_id = $1 AND team_members.accepted = true
+            ORDER BY users.discord_id = teams.captain_id DESC, users.discord_name
+            "#,
+            team_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(players)
+    }
+
+    async fn invite_team_member(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO team_members (team_id, discord_id)
+            VALUES ($1, $2)
+            ON CONFLICT (team_id, discord_id) DO NOTHING
+            "#,
+            team_id,
+            discord_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_team_invites(
+        &self,
+        guild_id: &str,
+        discord_id: &str,
+    ) -> Result<Vec<Team>, Self::Error> {
+        let teams = sqlx::query_as!(
+            Team,
+            r#"
+            SELECT teams.team_id, teams.guild_id, teams.name, teams.captain_id
+            FROM teams
+            JOIN team_members ON teams.team_id = team_members.team_id
+            WHERE teams.guild_id = $1 AND team_members.discord_id = $2 AND team_members.accepted = false
+            "#,
+            guild_id,
+            discord_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(teams)
+    }
+
+    async fn accept_team_invite(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE team_members
+            SET accepted = true
+            WHERE team_id = $1 AND discord_id = $2
+            "#,
+            team_id,
+            discord_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_team_member(&self, team_id: i32, discord_id: &str) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM team_members
+            WHERE team_id = $1 AND discord_id = $2
+            "#,
+            team_id,
+            discord_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn register_team(&self, tournament_id: i32, team_id: i32) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO tournament_teams (tournament_id, team_id)
+            VALUES ($1, $2)
+            ON CONFLICT (tournament_id, team_id) DO NOTHING
+            "#,
+            tournament_id,
+            team_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn unregister_team(&self, tournament_id: i32, team_id: i32) -> Result<(), Self::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM tournament_teams
+            WHERE tournament_id = $1 AND team_id = $2
+            "#,
+            tournament_id,
+            team_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_tournament_team(
+        &self,
+        tournament_id: i32,
+        discord_id: &str,
+    ) -> Result<Option<Team>, Self::Error> {
+        let team = sqlx::query_as!(
+            Team,
+            r#"
+            SELECT teams.team_id, teams.guild_id, teams.name, teams.captain_id
+            FROM tournament_teams
+            JOIN teams ON tournament_teams.team_id = teams.team_id
+            JOIN team_members ON teams.team_id = team_members.team_id
+            WHERE tournament_teams.tournament_id = $1 AND team_members.discord_id = $2 AND team_members.accepted = true
+            "#,
+            tournament_id,
+            discord_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(team)
+    }
+}
+
 pub trait BattleDatabase {
     async fn add_event(&self, event: &Event, battle_id: i64) -> Result<i64, Self::Error>;
     async fn add_battle_class(
@@ -1,8 +1,13 @@
+use std::collections::HashMap;
 use std::vec;
 
 use crate::utils::discord::DiscordTrait;
 use crate::utils::shorthand::BotContextExt;
-use crate::{api::official_brawl_stars::Brawler, BotContext, BotError};
+use crate::utils::time::describe_recurrence;
+use crate::{
+    api::official_brawl_stars::{Brawler, PlayerProfile},
+    BotContext, BotError,
+};
 use anyhow::{anyhow, Result};
 use poise::serenity_prelude::{GuildChannel, Role, User, UserId};
 use serde::{Deserialize, Serialize};
@@ -57,11 +62,15 @@ impl GuildConfig {
 }
 
 /// The status of a tournament. Used to know if a tournament should be paused, retired, etc.
-#[derive(Debug, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, Display, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, Display, Default,
+)]
 #[sqlx(type_name = "tournament_status", rename_all = "snake_case")]
 pub enum TournamentStatus {
     #[strum(to_string = "Open")]
     Pending,
+    #[strum(to_string = "Registration closed")]
+    RegistrationClosed,
     #[strum(to_string = "In progress")]
     Started,
     #[strum(to_string = "Paused")]
@@ -71,6 +80,136 @@ pub enum TournamentStatus {
     Inactive,
 }
 
+impl TournamentStatus {
+    /// Whether the tournament has not started yet, whether or not players can still join it.
+    pub fn before_start(&self) -> bool {
+        matches!(
+            self,
+            TournamentStatus::Pending | TournamentStatus::RegistrationClosed
+        )
+    }
+}
+
+/// The format of a tournament. Decides how the matches of each round are generated.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    sqlx::Type,
+    Serialize,
+    Deserialize,
+    Display,
+    poise::ChoiceParameter,
+    EnumIter,
+)]
+#[sqlx(type_name = "tournament_format", rename_all = "snake_case")]
+pub enum TournamentFormat {
+    #[name = "Single Elimination"]
+    #[strum(to_string = "Single Elimination")]
+    #[default]
+    SingleElimination,
+    #[name = "Double Elimination"]
+    #[strum(to_string = "Double Elimination")]
+    DoubleElimination,
+    #[name = "Swiss"]
+    #[strum(to_string = "Swiss")]
+    Swiss,
+    #[name = "Round Robin Groups"]
+    #[strum(to_string = "Round Robin Groups")]
+    RoundRobin,
+    #[name = "Showdown Lobbies"]
+    #[strum(to_string = "Showdown Lobbies")]
+    Showdown,
+}
+
+impl Selectable for TournamentFormat {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+    fn identifier(&self) -> String {
+        match self {
+            TournamentFormat::SingleElimination => "single_elimination".to_string(),
+            TournamentFormat::DoubleElimination => "double_elimination".to_string(),
+            TournamentFormat::Swiss => "swiss".to_string(),
+            TournamentFormat::RoundRobin => "round_robin".to_string(),
+            TournamentFormat::Showdown => "showdown".to_string(),
+        }
+    }
+}
+
+impl TournamentFormat {
+    pub fn from_string(format: impl Into<String>) -> Self {
+        match format.into().as_str() {
+            "double_elimination" | "Double Elimination" => Self::DoubleElimination,
+            "swiss" | "Swiss" => Self::Swiss,
+            "round_robin" | "Round Robin Groups" => Self::RoundRobin,
+            "showdown" | "Showdown Lobbies" => Self::Showdown,
+            _ => Self::SingleElimination,
+        }
+    }
+    pub fn all() -> Vec<TournamentFormat> {
+        TournamentFormat::iter().collect()
+    }
+}
+
+/// How the players of a tournament are ordered before the bracket is drawn.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    sqlx::Type,
+    Serialize,
+    Deserialize,
+    Display,
+    poise::ChoiceParameter,
+    EnumIter,
+)]
+#[sqlx(type_name = "seeding_method", rename_all = "snake_case")]
+pub enum SeedingMethod {
+    #[name = "Trophies"]
+    #[strum(to_string = "Trophies")]
+    #[default]
+    Trophies,
+    #[name = "Manual"]
+    #[strum(to_string = "Manual")]
+    Manual,
+    #[name = "Random"]
+    #[strum(to_string = "Random")]
+    Random,
+}
+
+impl Selectable for SeedingMethod {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+    fn identifier(&self) -> String {
+        match self {
+            SeedingMethod::Trophies => "trophies".to_string(),
+            SeedingMethod::Manual => "manual".to_string(),
+            SeedingMethod::Random => "random".to_string(),
+        }
+    }
+}
+
+impl SeedingMethod {
+    pub fn from_string(method: impl Into<String>) -> Self {
+        match method.into().as_str() {
+            "manual" | "Manual" => Self::Manual,
+            "random" | "Random" => Self::Random,
+            _ => Self::Trophies,
+        }
+    }
+    pub fn all() -> Vec<SeedingMethod> {
+        SeedingMethod::iter().collect()
+    }
+}
+
 /// A tournament within the database.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Tournament {
@@ -84,6 +223,27 @@ pub struct Tournament {
     pub status: TournamentStatus,
     pub tournament_role_id: String,
     pub mode: Mode,
+    pub format: TournamentFormat,
+    /// How the bracket was seeded. Only known once the tournament has started.
+    pub seeding_method: Option<SeedingMethod>,
+    /// The RNG seed of a random draw, published before the draw so that it can be verified.
+    pub draw_seed: Option<i64>,
+    /// The number of players per team. 1 for solo tournaments.
+    pub team_size: i32,
+    /// The points awarded for each placement in a showdown lobby, starting from first place.
+    pub placement_points: Vec<i32>,
+    /// The number of games played in each showdown lobby.
+    pub lobby_games: i32,
+    /// When the check-in window closes, as a unix timestamp. None if check-in was never opened.
+    pub check_in_end: Option<i64>,
+    /// Whether the next round is created as soon as every match of the current round is decided.
+    pub auto_advance: bool,
+    /// How many minutes players have to finish the matches of a round. None if rounds have no
+    /// deadline.
+    pub round_duration: Option<i32>,
+    /// The maximum number of participants. Players who join a full tournament are put on its
+    /// waitlist. None if the tournament has no cap.
+    pub max_participants: Option<i32>,
     pub map: BrawlMap,
     pub wins_required: i32,
     pub announcement_channel_id: String,
@@ -108,6 +268,240 @@ impl Tournament {
     pub async fn player_role(&self, ctx: &BotContext<'_>) -> Result<Role, BotError> {
         Self::to_role(ctx, &self.tournament_role_id).await
     }
+
+    /// The deadline of a round that starts now, if the tournament has one.
+    pub fn round_deadline(&self, now: i64) -> Option<i64> {
+        self.round_duration
+            .map(|minutes| now + i64::from(minutes) * 60)
+    }
+
+    /// Whether players can currently check in for the tournament.
+    pub fn check_in_open(&self, now: i64) -> bool {
+        self.status.before_start() && self.check_in_end.is_some_and(|end| now < end)
+    }
+
+    /// Whether the tournament has reached its participant cap with the given number of
+    /// participants.
+    pub fn is_full(&self, participants: usize) -> bool {
+        self.max_participants
+            .is_some_and(|max| participants >= max as usize)
+    }
+
+    /// Whether a match belongs to the final: the grand final and its reset in double elimination,
+    /// or the last round of the playoff bracket otherwise.
+    fn is_final(&self, bracket: &Match) -> Result<bool, BotError> {
+        Ok(match self.format {
+            TournamentFormat::DoubleElimination => bracket.bracket()? == Bracket::GrandFinal,
+            _ => bracket.round()? == self.rounds && bracket.bracket()? != Bracket::Group,
+        })
+    }
+
+    /// The number of wins required to win a match, taking the per-round overrides into account.
+    ///
+    /// An override for round 0 applies to the final.
+    pub fn wins_required_for(
+        &self,
+        bracket: &Match,
+        overrides: &[RoundWinsRequired],
+    ) -> Result<i32, BotError> {
+        let round = bracket.round()?;
+        let is_final = self.is_final(bracket)?;
+        Ok(overrides
+            .iter()
+            .find(|o| o.round == round)
+            .or_else(|| overrides.iter().find(|o| o.round == 0 && is_final))
+            .map_or(self.wins_required, |o| o.wins_required))
+    }
+
+    /// The map to play in one game of a match, starting from game 1.
+    ///
+    /// A map assigned to the game wins over a map assigned to the whole round, which wins over the
+    /// map of the tournament. Like for wins required, round 0 applies to the final.
+    pub fn map_for(
+        &self,
+        bracket: &Match,
+        game: i32,
+        rotation: &[ScheduledMap],
+    ) -> Result<BrawlMap, BotError> {
+        let round = bracket.round()?;
+        let is_final = self.is_final(bracket)?;
+        Ok(rotation
+            .iter()
+            .filter(|m| m.round == round || (m.round == 0 && is_final))
+            .filter(|m| m.game == game || m.game == 0)
+            .min_by_key(|m| (m.game == 0, m.round == 0))
+            .map_or_else(|| self.map.clone(), |m| m.map.clone()))
+    }
+}
+
+/// A rule a player must meet to join a tournament.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, poise::ChoiceParameter, EnumIter)]
+pub enum Requirement {
+    #[name = "Minimum trophies"]
+    #[strum(to_string = "Minimum trophies")]
+    MinTrophies,
+    #[name = "Maximum trophies"]
+    #[strum(to_string = "Maximum trophies")]
+    MaxTrophies,
+    #[name = "Minimum highest trophies"]
+    #[strum(to_string = "Minimum highest trophies")]
+    MinHighestTrophies,
+    #[name = "Minimum 3v3 victories"]
+    #[strum(to_string = "Minimum 3v3 victories")]
+    MinThreeVsThreeVictories,
+    #[name = "Club"]
+    #[strum(to_string = "Club")]
+    Club,
+    #[name = "Minimum brawlers owned"]
+    #[strum(to_string = "Minimum brawlers owned")]
+    MinBrawlers,
+}
+
+/// The requirements a player must meet to join a tournament. Every requirement is optional.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct EntryRequirements {
+    pub tournament_id: i32,
+    pub min_trophies: Option<i32>,
+    pub max_trophies: Option<i32>,
+    pub min_highest_trophies: Option<i32>,
+    pub min_three_vs_three_victories: Option<i32>,
+    /// The tag of the club players must be a member of, without the leading '#'.
+    pub club_tag: Option<String>,
+    pub min_brawlers: Option<i32>,
+}
+
+impl EntryRequirements {
+    /// Sets a requirement, or removes it with None.
+    ///
+    /// Numbers must not be negative. Club tags may be given with or without the leading '#'.
+    pub fn set(&mut self, requirement: Requirement, value: Option<&str>) -> Result<(), BotError> {
+        if requirement == Requirement::Club {
+            self.club_tag = value
+                .map(|tag| tag.trim().trim_start_matches('#').to_uppercase())
+                .filter(|tag| !tag.is_empty());
+            return Ok(());
+        }
+        let value = match value {
+            Some(value) => match value.trim().parse::<i32>() {
+                Ok(number) if number >= 0 => Some(number),
+                _ => {
+                    return Err(anyhow!(
+                        "{} must be a number that is not negative.",
+                        requirement
+                    ))
+                }
+            },
+            None => None,
+        };
+        match requirement {
+            Requirement::MinTrophies => self.min_trophies = value,
+            Requirement::MaxTrophies => self.max_trophies = value,
+            Requirement::MinHighestTrophies => self.min_highest_trophies = value,
+            Requirement::MinThreeVsThreeVictories => self.min_three_vs_three_victories = value,
+            Requirement::MinBrawlers => self.min_brawlers = value,
+            Requirement::Club => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Describes every requirement that is set, one per line.
+    pub fn describe(&self) -> Vec<String> {
+        let numbers = [
+            (Requirement::MinTrophies, self.min_trophies),
+            (Requirement::MaxTrophies, self.max_trophies),
+            (Requirement::MinHighestTrophies, self.min_highest_trophies),
+            (
+                Requirement::MinThreeVsThreeVictories,
+                self.min_three_vs_three_victories,
+            ),
+            (Requirement::MinBrawlers, self.min_brawlers),
+        ];
+        numbers
+            .iter()
+            .filter_map(|(requirement, value)| {
+                value.map(|value| format!("{}: {}", requirement, value))
+            })
+            .chain(
+                self.club_tag
+                    .iter()
+                    .map(|tag| format!("{}: #{}", Requirement::Club, tag)),
+            )
+            .collect()
+    }
+
+    /// Lists the requirements a player does not meet, along with where the player stands.
+    pub fn unmet(&self, profile: &PlayerProfile) -> Vec<String> {
+        let mut unmet = vec![];
+        let mut check = |requirement: Requirement, ok: bool, actual: String| {
+            if !ok {
+                unmet.push(format!("{} (you have {})", requirement, actual));
+            }
+        };
+        if let Some(min) = self.min_trophies {
+            check(
+                Requirement::MinTrophies,
+                profile.trophies >= min,
+                profile.trophies.to_string(),
+            );
+        }
+        if let Some(max) = self.max_trophies {
+            check(
+                Requirement::MaxTrophies,
+                profile.trophies <= max,
+                profile.trophies.to_string(),
+            );
+        }
+        if let Some(min) = self.min_highest_trophies {
+            check(
+                Requirement::MinHighestTrophies,
+                profile.highest_trophies >= min,
+                profile.highest_trophies.to_string(),
+            );
+        }
+        if let Some(min) = self.min_three_vs_three_victories {
+            check(
+                Requirement::MinThreeVsThreeVictories,
+                profile.three_vs_three_victories >= min,
+                profile.three_vs_three_victories.to_string(),
+            );
+        }
+        if let Some(tag) = &self.club_tag {
+            let club = profile.club.as_ref();
+            check(
+                Requirement::Club,
+                club.is_some_and(|club| club.tag.trim_start_matches('#').eq_ignore_ascii_case(tag)),
+                club.map_or("no club".to_string(), |club| club.name.clone()),
+            );
+        }
+        if let Some(min) = self.min_brawlers {
+            check(
+                Requirement::MinBrawlers,
+                profile.brawlers.len() >= min as usize,
+                profile.brawlers.len().to_string(),
+            );
+        }
+        unmet
+    }
+}
+
+/// A map assigned to a round of a tournament, or to one game of the series played in that round.
+///
+/// Round 0 stands for the final and game 0 for every game of the round.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduledMap {
+    pub tournament_id: i32,
+    pub round: i32,
+    pub game: i32,
+    pub map: BrawlMap,
+}
+
+/// The number of wins required to win a match in one round of a tournament, overriding the
+/// tournament default. Round 0 stands for the final.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RoundWinsRequired {
+    pub tournament_id: i32,
+    pub round: i32,
+    pub wins_required: i32,
 }
 
 impl Selectable for Tournament {
@@ -119,6 +513,90 @@ impl Selectable for Tournament {
     }
 }
 
+/// The settings a manager creates a tournament with.
+#[derive(Debug, Clone)]
+pub struct TournamentSettings {
+    pub name: String,
+    pub mode: Mode,
+    pub format: TournamentFormat,
+    pub wins_required: i32,
+    pub team_size: i32,
+    /// When the tournament starts by itself. None if a manager starts it.
+    pub start_time: Option<i64>,
+    pub tournament_role_id: String,
+    pub announcement_channel_id: String,
+    pub notification_channel_id: String,
+}
+
+/// The saved settings of a tournament that new tournaments can be created from.
+///
+/// Besides the fields below, a template keeps the map, map rotation, per-round wins required,
+/// brawler bans, entry requirements and showdown scoring of the tournament it was saved from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TournamentTemplate {
+    pub template_id: i32,
+    pub guild_id: String,
+    pub name: String,
+    pub mode: Mode,
+    pub format: TournamentFormat,
+    pub wins_required: i32,
+    pub team_size: i32,
+    pub max_participants: Option<i32>,
+    pub tournament_role_id: String,
+    pub announcement_channel_id: String,
+    pub notification_channel_id: String,
+}
+
+impl Selectable for TournamentTemplate {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+    fn identifier(&self) -> String {
+        self.template_id.to_string()
+    }
+}
+
+/// A tournament that is created from a template on a schedule, such as every Saturday at 18:00.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurringTournament {
+    pub recurrence_id: i32,
+    pub guild_id: String,
+    pub template_id: i32,
+    /// The name of every tournament created.
+    pub name: String,
+    /// The day of the week the tournaments start, counted from Monday. None for every day.
+    pub weekday: Option<i32>,
+    /// The time of the day the tournaments start, in minutes after midnight UTC.
+    pub minute_of_day: i32,
+    /// How many hours before its start a tournament is created and opened for registration.
+    pub registration_hours: i32,
+    /// Whether the tournaments start by themselves, rather than being started by a manager.
+    pub auto_start: bool,
+    /// When the next tournament starts, as a unix timestamp.
+    pub next_start: i64,
+}
+
+impl RecurringTournament {
+    /// When the next tournament is created, as a unix timestamp.
+    pub fn next_creation(&self) -> i64 {
+        self.next_start - self.registration_hours as i64 * 60 * 60
+    }
+
+    /// Describes when the tournaments start, such as `every Saturday at 18:00 UTC`.
+    pub fn schedule(&self) -> String {
+        describe_recurrence(self.weekday, self.minute_of_day)
+    }
+}
+
+impl Selectable for RecurringTournament {
+    fn label(&self) -> String {
+        format!("{} ({})", self.name, self.schedule())
+    }
+    fn identifier(&self) -> String {
+        self.recurrence_id.to_string()
+    }
+}
+
 /// A Discord user within the database.
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Player {
@@ -128,7 +606,7 @@ pub struct Player {
     pub player_name: String,
     pub icon: i32,
     pub trophies: i32,
-    pub brawlers: sqlx::types::JsonValue, // For match-level brawler bans. Not currently implemented
+    pub brawlers: sqlx::types::JsonValue, // The brawlers the player owns, used to draft match-level bans
     pub deleted: bool,
 }
 
@@ -147,6 +625,35 @@ impl Player {
         format!("https://cdn-old.brawlify.com/profile/{}.png", self.icon)
     }
 }
+impl Selectable for Player {
+    fn label(&self) -> String {
+        format!("{} ({})", self.player_name, self.player_tag)
+    }
+    fn identifier(&self) -> String {
+        self.discord_id.clone()
+    }
+}
+
+/// A team of players that can register for team tournaments.
+///
+/// The captain manages the roster and represents the team in the bracket.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Team {
+    pub team_id: i32,
+    pub guild_id: String,
+    pub name: String,
+    pub captain_id: String,
+}
+
+impl Selectable for Team {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+    fn identifier(&self) -> String {
+        self.team_id.to_string()
+    }
+}
+
 /// A relational object that links a Discord user to a tournament they've joined.
 #[derive(Serialize, Deserialize)]
 pub struct TournamentPlayer {
@@ -162,10 +669,34 @@ impl TournamentPlayer {
     }
 }
 
+/// The draw of a tournament, with everything its first round needs.
+///
+/// The draw is written all at once when the tournament starts, so that a start that fails leaves
+/// nothing of it behind.
+#[derive(Debug, Clone)]
+pub struct TournamentDraw {
+    pub tournament_id: i32,
+    /// The Discord IDs of the players who did not check in and leave the tournament.
+    pub dropped: Vec<String>,
+    pub seeding: SeedingMethod,
+    /// The seed the players were shuffled with, if they were drawn at random.
+    pub draw_seed: Option<i64>,
+    /// The Discord IDs of the players, from the first seed.
+    pub seeds: Vec<String>,
+    /// The Discord IDs of the players in each Round Robin group, from the first group.
+    pub groups: Vec<Vec<String>>,
+    pub standings: Vec<Standing>,
+    pub matches: Vec<Match>,
+    pub lobbies: Vec<Lobby>,
+    pub rounds: i32,
+    /// When the matches of the first round are due, if the tournament has round deadlines.
+    pub deadline: Option<i64>,
+}
+
 /// A match within the database, associated with a particular tournament.
 ///
 /// Also known as a bracket to avoid conflicting with the Rust keyword.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Match {
     pub match_id: String,
     pub match_players: Vec<MatchPlayer>,
@@ -178,6 +709,9 @@ pub struct Match {
 impl DiscordTrait for Match {}
 
 impl Match {
+    /// The number of brawlers each player bans during the draft of a match.
+    pub const DRAFT_BANS_PER_PLAYER: usize = 2;
+
     pub fn new(
         tournament_id: i32,
         round: i32,
@@ -195,10 +729,46 @@ impl Match {
         }
     }
 
+    /// Creates a match in a specific bracket of a double elimination tournament.
+    pub fn new_in_bracket(
+        tournament_id: i32,
+        round: i32,
+        sequence_in_round: i32,
+        bracket: Bracket,
+        match_players: Vec<MatchPlayer>,
+        score: &str,
+    ) -> Self {
+        Self {
+            match_id: Self::generate_bracket_id(tournament_id, round, sequence_in_round, bracket),
+            ..Self::new(tournament_id, round, sequence_in_round, match_players, score)
+        }
+    }
+
     pub fn generate_id(tournament_id: i32, round: i32, sequence_in_round: i32) -> String {
         format!("{}.{}.{}", tournament_id, round, sequence_in_round)
     }
 
+    /// Generates a match ID that also records the bracket the match belongs to.
+    ///
+    /// Winners bracket matches keep the plain `tournament.round.sequence` format so that they are
+    /// interchangeable with single elimination matches. Other brackets append a suffix,
+    /// e.g. `1.3.2.L` for the second losers bracket match of round 3.
+    pub fn generate_bracket_id(
+        tournament_id: i32,
+        round: i32,
+        sequence_in_round: i32,
+        bracket: Bracket,
+    ) -> String {
+        match bracket.suffix() {
+            Some(suffix) => format!(
+                "{}.{}",
+                Self::generate_id(tournament_id, round, sequence_in_round),
+                suffix
+            ),
+            None => Self::generate_id(tournament_id, round, sequence_in_round),
+        }
+    }
+
     /// Retrieves the winning player as a reference to its User type.
     /// The caller is responsible to clone or take ownership of the underlying User type.
     ///
@@ -235,6 +805,31 @@ impl Match {
         )
     }
 
+    /// The player who wins the match by forfeit once its deadline has passed.
+    ///
+    /// This is only the case when exactly one of the two players said they were ready.
+    pub fn forfeit_winner(&self) -> Option<&MatchPlayer> {
+        match self.match_players.as_slice() {
+            [first, second] if first.ready != second.ready => match first.ready {
+                true => Some(first),
+                false => Some(second),
+            },
+            _ => None,
+        }
+    }
+
+    /// The player whose turn it is to ban a brawler in the draft, or None once the draft is over.
+    ///
+    /// The two players take turns, starting with player 1.
+    pub fn draft_turn(&self, bans: &[MatchBan]) -> Option<&MatchPlayer> {
+        match self.match_players.as_slice() {
+            [first, second] if bans.len() < 2 * Self::DRAFT_BANS_PER_PLAYER => {
+                Some([first, second][bans.len() % 2])
+            }
+            _ => None,
+        }
+    }
+
     fn find_player<F>(&self, predicate: F, error_message: String) -> Result<&MatchPlayer>
     where
         F: Fn(&&MatchPlayer) -> bool,
@@ -272,6 +867,21 @@ impl Match {
             .map_err(|_| BotError::from(anyhow!("Error: Unable to parse match from match ID")))
     }
 
+    /// The bracket this match belongs to, read from the suffix of the match ID.
+    pub fn bracket(&self) -> Result<Bracket, BotError> {
+        Bracket::from_suffix(self.match_id.split('.').nth(3))
+    }
+
+    /// Retrieves the losing player of a decided match.
+    ///
+    /// Returns None if the match has no winner yet or if the winner had no opponent (a bye).
+    pub fn get_losing_player(&self) -> Option<&MatchPlayer> {
+        let winner_id = self.winner.as_ref()?;
+        self.match_players
+            .iter()
+            .find(|p| p.discord_id != *winner_id)
+    }
+
     pub async fn winner(&self, ctx: &BotContext<'_>) -> Result<Option<User>, BotError> {
         match &self.winner {
             Some(winner) => Ok(Some(Self::to_user(ctx, winner).await?)),
@@ -284,6 +894,243 @@ impl Match {
     }
 }
 
+/// The bracket a match belongs to.
+///
+/// Single elimination tournaments only ever use the winners bracket.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Display)]
+pub enum Bracket {
+    #[strum(to_string = "Winners Bracket")]
+    Winners,
+    #[strum(to_string = "Losers Bracket")]
+    Losers,
+    #[strum(to_string = "Grand Final")]
+    GrandFinal,
+    #[strum(to_string = "Group Stage")]
+    Group,
+    #[strum(to_string = "Third Place Match")]
+    ThirdPlace,
+}
+
+impl Bracket {
+    /// The suffix appended to the match ID of matches in this bracket.
+    pub fn suffix(&self) -> Option<&'static str> {
+        match self {
+            Bracket::Winners => None,
+            Bracket::Losers => Some("L"),
+            Bracket::GrandFinal => Some("GF"),
+            Bracket::Group => Some("G"),
+            Bracket::ThirdPlace => Some("3P"),
+        }
+    }
+
+    pub fn from_suffix(suffix: Option<&str>) -> Result<Self, BotError> {
+        match suffix {
+            None => Ok(Bracket::Winners),
+            Some("L") => Ok(Bracket::Losers),
+            Some("GF") => Ok(Bracket::GrandFinal),
+            Some("G") => Ok(Bracket::Group),
+            Some("3P") => Ok(Bracket::ThirdPlace),
+            Some(other) => Err(anyhow!(
+                "Error: Unable to parse bracket from match ID suffix {}",
+                other
+            )),
+        }
+    }
+}
+
+/// The record of a player within a tournament that does not eliminate players (e.g. Swiss).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Standing {
+    pub tournament_id: i32,
+    pub discord_id: String,
+    pub wins: i32,
+    pub losses: i32,
+    pub byes: i32,
+    /// The sum of the wins of every opponent the player has faced.
+    pub buchholz: i32,
+    /// The average win percentage of every opponent the player has faced.
+    pub opponent_win_percentage: f32,
+}
+
+impl Standing {
+    /// The lowest win percentage an opponent can contribute, so that players are not punished
+    /// too harshly for facing opponents who lost most of their matches.
+    const MIN_WIN_PERCENTAGE: f32 = 1.0 / 3.0;
+
+    /// Calculates the standings of the given players from the matches played so far.
+    ///
+    /// Byes count as wins but not as an opponent. Matches without a winner are ignored.
+    /// The standings are sorted by wins, then Buchholz, then opponent win percentage.
+    pub fn calculate(tournament_id: i32, discord_ids: &[String], matches: &[Match]) -> Vec<Self> {
+        let mut records: HashMap<&str, Self> = HashMap::new();
+        let mut opponents: HashMap<&str, Vec<&str>> = HashMap::new();
+        let record = |discord_id: &str| Self {
+            tournament_id,
+            discord_id: discord_id.to_string(),
+            ..Default::default()
+        };
+        for bracket in matches {
+            match (bracket.match_players.as_slice(), bracket.winner.as_deref()) {
+                ([player], _) => {
+                    let standing = records
+                        .entry(&player.discord_id)
+                        .or_insert_with(|| record(&player.discord_id));
+                    standing.wins += 1;
+                    standing.byes += 1;
+                }
+                ([player_1, player_2], Some(winner)) => {
+                    for (player, opponent) in [(player_1, player_2), (player_2, player_1)] {
+                        let standing = records
+                            .entry(&player.discord_id)
+                            .or_insert_with(|| record(&player.discord_id));
+                        if player.discord_id == winner {
+                            standing.wins += 1;
+                        } else {
+                            standing.losses += 1;
+                        }
+                        opponents
+                            .entry(&player.discord_id)
+                            .or_default()
+                            .push(&opponent.discord_id);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut standings: Vec<Self> = discord_ids
+            .iter()
+            .map(|discord_id| {
+                let mut standing = records
+                    .get(discord_id.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| record(discord_id));
+                let faced = opponents.get(discord_id.as_str()).cloned().unwrap_or_default();
+                let opponent_records: Vec<&Self> =
+                    faced.iter().filter_map(|id| records.get(id)).collect();
+                standing.buchholz = opponent_records.iter().map(|o| o.wins).sum();
+                if !opponent_records.is_empty() {
+                    standing.opponent_win_percentage = opponent_records
+                        .iter()
+                        .map(|o| o.win_percentage().max(Self::MIN_WIN_PERCENTAGE))
+                        .sum::<f32>()
+                        / opponent_records.len() as f32;
+                }
+                standing
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.wins
+                .cmp(&a.wins)
+                .then(b.buchholz.cmp(&a.buchholz))
+                .then(
+                    b.opponent_win_percentage
+                        .total_cmp(&a.opponent_win_percentage),
+                )
+        });
+        standings
+    }
+
+    pub fn win_percentage(&self) -> f32 {
+        let played = self.wins + self.losses;
+        if played == 0 {
+            return 0.0;
+        }
+        self.wins as f32 / played as f32
+    }
+}
+
+/// The record of a player within their group of a round robin tournament.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupStanding {
+    pub tournament_id: i32,
+    pub group_number: i32,
+    pub discord_id: String,
+    pub wins: i64,
+    pub losses: i64,
+}
+
+/// A lobby of a showdown tournament, where up to ten players play several games against each
+/// other and the best placed players advance.
+///
+/// Lobby IDs follow the format `tournament_id.round.sequence`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Lobby {
+    pub lobby_id: String,
+    pub tournament_id: i32,
+    pub round: i32,
+    pub players: Vec<String>,
+    pub games_played: i32,
+    /// The battle time of the last counted game, or the time the lobby was created. Only games
+    /// played after it count.
+    pub last_battle: i64,
+}
+
+impl Lobby {
+    /// The maximum number of players in a showdown lobby.
+    pub const MAX_PLAYERS: usize = 10;
+
+    pub fn new(tournament_id: i32, round: i32, sequence: i32, players: Vec<String>) -> Self {
+        Self {
+            lobby_id: format!("{}.{}.{}", tournament_id, round, sequence),
+            tournament_id,
+            round,
+            players,
+            games_played: 0,
+            last_battle: 0,
+        }
+    }
+
+    /// The number of players that advance from a lobby of the given size.
+    pub fn advancing(size: usize) -> usize {
+        size.div_ceil(2)
+    }
+}
+
+/// The placement of a player in one game of a showdown lobby.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LobbyResult {
+    pub lobby_id: String,
+    pub game_number: i32,
+    pub discord_id: String,
+    pub placement: i32,
+    pub points: i32,
+    pub battle_time: i64,
+}
+
+/// The total points of a player within a showdown lobby.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LobbyStanding {
+    pub discord_id: String,
+    pub points: i32,
+    pub best_placement: i32,
+}
+
+impl LobbyStanding {
+    /// Totals the points of every player of a lobby.
+    ///
+    /// Players are ranked by points, then by their best placement in any game.
+    pub fn calculate(players: &[String], results: &[LobbyResult]) -> Vec<LobbyStanding> {
+        let mut standings: Vec<LobbyStanding> = players
+            .iter()
+            .map(|discord_id| {
+                let own = results.iter().filter(|r| &r.discord_id == discord_id);
+                LobbyStanding {
+                    discord_id: discord_id.clone(),
+                    points: own.clone().map(|r| r.points).sum(),
+                    best_placement: own.map(|r| r.placement).min().unwrap_or(i32::MAX),
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(a.best_placement.cmp(&b.best_placement))
+        });
+        standings
+    }
+}
+
 /// A relational entity linking players to matches.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MatchPlayer {
@@ -322,14 +1169,89 @@ pub enum PlayerType {
     Pending,
 }
 
-/// A match schedule within the database.
-#[derive(Serialize, Deserialize)]
+/// The time two players agreed on to play their match.
+///
+/// A counter-proposal replaces the previous proposal, so a match only has one schedule at a time.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MatchSchedule {
-    match_id: String,
-    proposed_time: i32,
-    time_of_proposal: chrono::DateTime<chrono::Utc>,
-    proposer: Option<i32>,
-    accepted: bool,
+    pub match_id: String,
+    /// The proposed time as a unix timestamp.
+    pub proposed_time: i64,
+    pub time_of_proposal: i64,
+    /// The Discord ID of the player who proposed the time.
+    pub proposer: String,
+    pub accepted: bool,
+    /// Whether the players have been reminded of the match.
+    pub reminded: bool,
+}
+
+/// A brawler banned by one of the players during the draft of a match.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchBan {
+    pub match_id: String,
+    /// The position of the ban in the draft, starting from 0.
+    pub turn: i32,
+    pub discord_id: String,
+    pub brawler: String,
+}
+
+/// A result set or corrected by a marshal, kept so that every change to a match can be traced.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchOverride {
+    pub override_id: i32,
+    pub match_id: String,
+    /// The winner before the override. None if the match was undecided.
+    pub previous_winner: Option<String>,
+    pub previous_score: String,
+    pub winner: String,
+    pub score: String,
+    pub reason: String,
+    /// The Discord ID of the marshal who made the override.
+    pub marshal_id: String,
+    pub created_at: i64,
+}
+
+/// Where a result dispute stands in the review of the marshals.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, sqlx::Type, Serialize, Deserialize, Display)]
+#[sqlx(type_name = "dispute_status", rename_all = "snake_case")]
+pub enum DisputeStatus {
+    #[strum(to_string = "Open")]
+    Open,
+    #[strum(to_string = "Accepted")]
+    Accepted,
+    #[strum(to_string = "Rejected")]
+    Rejected,
+}
+
+/// A claim by a player that the result of their match is wrong.
+///
+/// The round of the match cannot advance while the dispute is open.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchDispute {
+    pub dispute_id: i32,
+    pub match_id: String,
+    /// The Discord ID of the player who opened the dispute.
+    pub discord_id: String,
+    pub statement: String,
+    /// Links to screenshots or videos that back the statement.
+    pub evidence: Vec<String>,
+    /// The thread where the marshals were pinged about the dispute.
+    pub thread_id: Option<String>,
+    pub status: DisputeStatus,
+    /// The Discord ID of the marshal who resolved the dispute.
+    pub resolved_by: Option<String>,
+    pub resolution: Option<String>,
+    pub created_at: i64,
+    pub resolved_at: Option<i64>,
+}
+
+impl Selectable for MatchDispute {
+    fn label(&self) -> String {
+        format!("Dispute {} (match {})", self.dispute_id, self.match_id)
+    }
+    fn identifier(&self) -> String {
+        self.dispute_id.to_string()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -398,12 +1320,14 @@ pub struct Event {
     pub id: i64,
     #[serde(default)]
     pub mode: Mode,
+    #[serde(default)]
     pub map: BrawlMap, // Optional because map can be NULL in the database
     #[serde(default)]
     pub battle_id: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(from = "RawBrawlMap")]
 pub struct BrawlMap {
     #[serde(default)]
     pub id: i32,
@@ -411,6 +1335,52 @@ pub struct BrawlMap {
     pub name: String,
 }
 
+/// The shapes a map comes in. The battle log of the official API only gives the name of the map.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBrawlMap {
+    Name(String),
+    Map {
+        #[serde(default)]
+        id: i32,
+        #[serde(default)]
+        name: String,
+    },
+    Missing(()),
+}
+
+impl From<RawBrawlMap> for BrawlMap {
+    fn from(value: RawBrawlMap) -> Self {
+        match value {
+            RawBrawlMap::Name(name) => BrawlMap { id: 0, name },
+            RawBrawlMap::Map { id, name } => BrawlMap { id, name },
+            RawBrawlMap::Missing(()) => BrawlMap::default(),
+        }
+    }
+}
+
+impl BrawlMap {
+    /// Whether the map stands for any map rather than a specific one.
+    pub fn is_any(&self) -> bool {
+        self.id == 0 && (self.name.is_empty() || self.name == BrawlMap::default().name)
+    }
+
+    /// Whether two maps are the same. Maps read from the battle log only carry their name.
+    pub fn same_as(&self, other: &BrawlMap) -> bool {
+        (self.id != 0 && self.id == other.id) || self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Selectable for BrawlMap {
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+
+    fn identifier(&self) -> String {
+        self.id.to_string()
+    }
+}
+
 impl Default for BrawlMap {
     fn default() -> Self {
         BrawlMap {
@@ -550,6 +1520,7 @@ impl Mode {
             "siege" => Self::siege,
             "soloShowdown" => Self::soloShowdown,
             "duoShowdown" => Self::duoShowdown,
+            "trioShowdown" => Self::trioShowdown,
             "hotZone" => Self::hotZone,
             "knockout" => Self::knockout,
             "takedown" => Self::takedown,
@@ -570,6 +1541,14 @@ impl Mode {
     pub fn all() -> Vec<Mode> {
         Mode::iter().collect()
     }
+
+    /// Whether the mode is a free-for-all where players are ranked by placement.
+    pub fn is_showdown(&self) -> bool {
+        matches!(
+            self,
+            Mode::soloShowdown | Mode::duoShowdown | Mode::trioShowdown
+        )
+    }
 }
 #[allow(non_camel_case_types)]
 #[derive(
@@ -1,6 +1,8 @@
 use crate::database::*;
 use anyhow::anyhow;
-use poise::serenity_prelude::{ChannelId, Color, CreateEmbed, CreateEmbedAuthor, CreateMessage};
+use poise::serenity_prelude::{
+    ChannelId, Color, CreateEmbed, CreateEmbedAuthor, CreateMessage, Http, Timestamp,
+};
 use std::{str::FromStr, time::SystemTime};
 use strum::Display;
 
@@ -85,12 +87,7 @@ impl Log for BotContext<'_> {
     }
 
     fn thumbnail(&self, state: &State) -> String {
-        match state{
-            State::FAILURE => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9mbkhRWjhzQmtkNUFkY2tzZTdTai5wbmcifQ:supercell:mCcCEDMJI8puCKKc2K9bBURE4tZem68vd5aMETOFjjw?width=2400"),
-            State::SUCCESS => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9iZUduOFpWaWpZYTduUXFKOEtDbi5wbmcifQ:supercell:QVmY9TjwRiZ77-CWw_lkKnpMrFbNbjHBZwalfHQ3KnE?width=2400"),
-            State::INFO => String::from("https://cdn.discordapp.com/emojis/1187845402163167363.webp?size=4096&quality=lossless"),
-            State::WARNING => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9IWjFzZkUyNllLUW9hRWhlTlgyTi5wbmcifQ:supercell:CeCBNWeUn35mJJYWe4g5BMg9_gWf1l1D35idcw0RGXI?width=2400"),
-        }
+        thumbnail(state)
     }
 
     async fn log(
@@ -121,6 +118,75 @@ impl Log for BotContext<'_> {
     }
 }
 
+/// The thumbnail shown on a log message with the given state.
+fn thumbnail(state: &State) -> String {
+    match state{
+        State::FAILURE => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9mbkhRWjhzQmtkNUFkY2tzZTdTai5wbmcifQ:supercell:mCcCEDMJI8puCKKc2K9bBURE4tZem68vd5aMETOFjjw?width=2400"),
+        State::SUCCESS => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9iZUduOFpWaWpZYTduUXFKOEtDbi5wbmcifQ:supercell:QVmY9TjwRiZ77-CWw_lkKnpMrFbNbjHBZwalfHQ3KnE?width=2400"),
+        State::INFO => String::from("https://cdn.discordapp.com/emojis/1187845402163167363.webp?size=4096&quality=lossless"),
+        State::WARNING => String::from("https://cdn-assets-eu.frontify.com/s3/frontify-enterprise-files-eu/eyJwYXRoIjoic3VwZXJjZWxsXC9maWxlXC9IWjFzZkUyNllLUW9hRWhlTlgyTi5wbmcifQ:supercell:CeCBNWeUn35mJJYWe4g5BMg9_gWf1l1D35idcw0RGXI?width=2400"),
+    }
+}
+
+/// The context of an action the bot takes by itself outside of any command, such as a scheduled
+/// task or an automatic forfeit.
+pub struct SystemContext<'a> {
+    pub http: &'a Http,
+    pub database: &'a PgDatabase,
+    pub guild_id: &'a str,
+}
+
+impl Log for SystemContext<'_> {
+    async fn get_log_channel(&self) -> Result<ChannelId, BotError> {
+        let log_channel = ChannelId::from_str(
+            &self
+                .database
+                .get_config(self.guild_id)
+                .await?
+                .ok_or(anyhow!(
+                    "Error getting log channel: config not found for guild {}",
+                    self.guild_id
+                ))?
+                .log_channel_id,
+        )?;
+        Ok(log_channel)
+    }
+
+    fn get_author_img(&self, model: &Model) -> CreateEmbedAuthor {
+        CreateEmbedAuthor::new(model.to_string())
+    }
+
+    fn thumbnail(&self, state: &State) -> String {
+        thumbnail(state)
+    }
+
+    async fn log(
+        &self,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        state: State,
+        model: Model,
+    ) -> Result<(), BotError> {
+        let embed = CreateEmbed::default()
+            .author(self.get_author_img(&model))
+            .title(title)
+            .description(format!(
+                r#"**Action**:
+{reason}
+**Triggered by**
+Automatic action"#,
+                reason = description.into(),
+            ))
+            .timestamp(Timestamp::now())
+            .thumbnail(self.thumbnail(&state))
+            .colour(state as u32);
+        let builder = CreateMessage::default().embed(embed);
+        let channel = self.get_log_channel().await?;
+        channel.send_message(self.http, builder).await?;
+        Ok(())
+    }
+}
+
 /// Creates an info log message in the current guild's designated log channel.
 // pub async fn discord_log_info(
 //     ctx: BotContext<'_>,
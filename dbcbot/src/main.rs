@@ -24,6 +24,8 @@ mod commands;
 mod database;
 /// Contains functions for logging.
 mod log;
+/// Background tasks that act on tournaments at a set time, such as scheduled starts.
+mod scheduler;
 
 mod utils;
 
@@ -183,6 +185,7 @@ async fn run() -> Result<(), BotError> {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
                 println!("Ready as {}", ready.user.name);
+                tokio::spawn(scheduler::run(ctx.http.clone(), pg_database.clone()));
                 Ok(Data::new(pg_database, apis_container))
             })
         })
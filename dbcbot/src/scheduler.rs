@@ -0,0 +1,534 @@
+use std::{sync::Arc, time::Duration};
+
+use poise::serenity_prelude::{ChannelId, Colour, CreateEmbed, CreateMessage, Http};
+use tracing::{error, info};
+
+use crate::{
+    commands::{
+        manager_commands::{launch_tournament, DrawSettings},
+        marshal_commands::{auto_advance, conclude_match},
+        user_commands::finish_if_decided,
+    },
+    database::{
+        models::{MatchSchedule, RecurringTournament, SeedingMethod},
+        ConfigDatabase, MatchDatabase, PgDatabase, TournamentDatabase, UserDatabase,
+    },
+    log::{Log, Model, State, SystemContext},
+    utils::time::next_occurrence,
+    BotError,
+};
+
+/// How often the scheduler looks for tournaments that are due to be created or started.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long before the agreed time of a match its players are reminded of it, in seconds.
+const REMINDER_LEAD: i64 = 15 * 60;
+
+/// The number of players per group for Round Robin tournaments that start by themselves.
+const DEFAULT_GROUP_SIZE: i32 = 4;
+
+/// Runs the scheduled tasks of the bot until it shuts down.
+///
+/// Scheduled starts are read from the database on every tick rather than kept in memory, so the
+/// ones that were set before a restart are picked up again as soon as the bot is back.
+pub async fn run(http: Arc<Http>, database: PgDatabase) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = create_recurring_tournaments(&http, &database).await {
+            error!("Error creating recurring tournaments: {}", e);
+        }
+        if let Err(e) = start_due_tournaments(&http, &database).await {
+            error!("Error starting scheduled tournaments: {}", e);
+        }
+        if let Err(e) = enforce_deadlines(&http, &database).await {
+            error!("Error enforcing match deadlines: {}", e);
+        }
+        if let Err(e) = send_reminders(&http, &database).await {
+            error!("Error sending match reminders: {}", e);
+        }
+        if let Err(e) = advance_settled_rounds(&http, &database).await {
+            error!("Error advancing tournaments: {}", e);
+        }
+        if let Err(e) = finish_settled_tournaments(&http, &database).await {
+            error!("Error finishing tournaments: {}", e);
+        }
+    }
+}
+
+/// Creates the next tournament of every recurrence that is due, announces that its registration
+/// is open, and schedules its start if the recurrence starts by itself.
+///
+/// The next start of the recurrence is moved on before the tournament is created, so that a
+/// tournament that cannot be created is only attempted once. Tournaments that would already have
+/// started, because the bot was offline when they were due, are skipped.
+async fn create_recurring_tournaments(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    let now = chrono::Utc::now().timestamp();
+    for recurrence in database.get_due_recurring_tournaments(now).await? {
+        if let Err(e) = create_recurring_tournament(http, database, &recurrence, now).await {
+            error!(
+                "Error creating recurring tournament {}: {}",
+                recurrence.recurrence_id, e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Creates the next tournament of a single recurrence. See [`create_recurring_tournaments`].
+async fn create_recurring_tournament(
+    http: &Http,
+    database: &PgDatabase,
+    recurrence: &RecurringTournament,
+    now: i64,
+) -> Result<(), BotError> {
+    let start = recurrence.next_start;
+    database
+        .set_next_start(
+            recurrence.recurrence_id,
+            next_occurrence(recurrence.weekday, recurrence.minute_of_day, start.max(now)),
+        )
+        .await?;
+    let system = SystemContext {
+        http,
+        database,
+        guild_id: &recurrence.guild_id,
+    };
+    if start <= now {
+        system
+            .log(
+                "Recurring tournament skipped",
+                format!(
+                    "{} was not created for <t:{}:F> because the bot was offline when it was due.",
+                    recurrence.name, start
+                ),
+                State::FAILURE,
+                Model::TOURNAMENT,
+            )
+            .await?;
+        return Ok(());
+    }
+    info!(
+        "Creating recurring tournament {} for guild {}",
+        recurrence.recurrence_id, recurrence.guild_id
+    );
+    let tournament_id = match database
+        .create_tournament_from_template(recurrence.template_id, &recurrence.name)
+        .await
+    {
+        Ok(tournament_id) => tournament_id,
+        Err(e) => {
+            error!(
+                "Error creating recurring tournament {}: {}",
+                recurrence.recurrence_id, e
+            );
+            system
+                .log(
+                    "Recurring tournament failed",
+                    format!("{} could not be created: {}", recurrence.name, e),
+                    State::FAILURE,
+                    Model::TOURNAMENT,
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+    if recurrence.auto_start {
+        database.set_start_time(tournament_id, Some(start)).await?;
+    }
+    let tournament = match database
+        .get_tournament(&recurrence.guild_id, tournament_id)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => return Ok(()),
+    };
+    let starts = match recurrence.auto_start {
+        true => format!("The tournament starts <t:{}:R>, on <t:{}:F>.", start, start),
+        false => format!("The tournament is planned for <t:{}:F>.", start),
+    };
+    ChannelId::new(tournament.announcement_channel_id.parse()?)
+        .send_message(
+            http,
+            CreateMessage::default().embed(
+                CreateEmbed::default()
+                    .title(format!("Registration for {} is open!", tournament.name))
+                    .description(format!("Join the tournament from `/menu`. {}", starts))
+                    .color(Colour::GOLD),
+            ),
+        )
+        .await?;
+    system
+        .log(
+            "Recurring tournament created",
+            format!(
+                "Tournament: {} ({})\nSchedule: {}\nStarts: <t:{}:F>\nStarted by: {}",
+                tournament.name,
+                tournament_id,
+                recurrence.schedule(),
+                start,
+                match recurrence.auto_start {
+                    true => "Scheduled start",
+                    false => "A manager",
+                }
+            ),
+            State::SUCCESS,
+            Model::TOURNAMENT,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Starts every open tournament whose start time has passed.
+///
+/// The start time is cleared before the tournament is started, which closes the registration and
+/// makes sure a tournament that cannot start is only attempted once. Managers can then start it
+/// by hand.
+async fn start_due_tournaments(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    let now = chrono::Utc::now().timestamp();
+    for (guild_id, tournament_id) in database.get_due_tournaments(now).await? {
+        database.set_start_time(tournament_id, None).await?;
+        let tournament = match database.get_tournament(&guild_id, tournament_id).await? {
+            Some(tournament) => tournament,
+            None => continue,
+        };
+        let system = SystemContext {
+            http,
+            database,
+            guild_id: &guild_id,
+        };
+        info!(
+            "Starting scheduled tournament {} for guild {}",
+            tournament_id, guild_id
+        );
+        let draw = DrawSettings {
+            seeding: SeedingMethod::default(),
+            seeds: Vec::new(),
+            rounds: None,
+            group_size: DEFAULT_GROUP_SIZE,
+        };
+        let outcome = launch_tournament(http, database, &tournament, &draw).await;
+        let reason = match outcome {
+            Ok(Ok(report)) => {
+                ChannelId::new(tournament.announcement_channel_id.parse()?)
+                    .send_message(
+                        http,
+                        CreateMessage::default()
+                            .content(format!("<@&{}>", tournament.tournament_role_id))
+                            .embed(
+                                CreateEmbed::default()
+                                    .title(format!("{} has started!", tournament.name))
+                                    .description("Registration is closed and the first round has been drawn. Check your match from `/menu`. Good luck!")
+                                    .color(Colour::DARK_GREEN),
+                            ),
+                    )
+                    .await?;
+                system
+                    .log(
+                        "Tournament started successfully!",
+                        format!(
+                            "{}Wins required per match: {}\nStarted by: Scheduled start",
+                            report.describe(&tournament),
+                            tournament.wins_required
+                        ),
+                        State::SUCCESS,
+                        Model::TOURNAMENT,
+                    )
+                    .await?;
+                continue;
+            }
+            Ok(Err(reason)) => reason,
+            Err(e) => {
+                error!(
+                    "Error starting scheduled tournament {}: {}",
+                    tournament_id, e
+                );
+                e.to_string()
+            }
+        };
+        system
+            .log(
+                "Scheduled start failed",
+                format!(
+                    "Tournament {} ({}) could not be started: {}",
+                    tournament.name, tournament_id, reason
+                ),
+                State::FAILURE,
+                Model::TOURNAMENT,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Settles the matches whose deadline has passed.
+///
+/// A match where only one player is ready is won by that player by forfeit. Any other match is
+/// handed over to the marshals, since the bot cannot tell who should win it.
+async fn enforce_deadlines(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    let now = chrono::Utc::now().timestamp();
+    for (guild_id, match_id) in database.get_overdue_matches(now).await? {
+        if let Err(e) = enforce_deadline(http, database, &guild_id, &match_id).await {
+            error!("Error enforcing the deadline of match {}: {}", match_id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Settles a single overdue match. See [`enforce_deadlines`].
+async fn enforce_deadline(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    match_id: &str,
+) -> Result<(), BotError> {
+    let bracket = match database.get_match_by_id(match_id).await? {
+        Some(bracket) => bracket,
+        None => return Ok(()),
+    };
+    let tournament_id = bracket.tournament()?;
+    let tournament = match database.get_tournament(guild_id, tournament_id).await? {
+        Some(tournament) => tournament,
+        None => return Ok(()),
+    };
+    let system = SystemContext {
+        http,
+        database,
+        guild_id,
+    };
+    // A bye has nobody to wait for
+    if let [player] = bracket.match_players.as_slice() {
+        if !database
+            .set_winner(match_id, &player.discord_id, "bye")
+            .await?
+        {
+            return Ok(());
+        }
+        return conclude_match(http, database, guild_id, tournament_id, match_id).await;
+    }
+
+    match bracket.forfeit_winner() {
+        Some(winner) => {
+            let loser = bracket.get_opponent(&winner.discord_id)?;
+            // A result submitted since the match was read wins over the forfeit
+            if !database
+                .set_winner(match_id, &winner.discord_id, "forfeit")
+                .await?
+            {
+                return Ok(());
+            }
+            // The match is decided either way, so the tournament must move on even if the players
+            // could not be told
+            let announced = async {
+                ChannelId::new(tournament.notification_channel_id.parse()?)
+                    .send_message(
+                        http,
+                        CreateMessage::default().content(format!(
+                            "<@{winner}> <@{loser}>\n\nThe deadline of match `{match_id}` has passed without a result. <@{loser}> never got ready, so <@{winner}> wins by forfeit.",
+                            winner = winner.discord_id,
+                            loser = loser.discord_id,
+                            match_id = match_id
+                        )),
+                    )
+                    .await?;
+                system
+                    .log(
+                        "Match forfeited",
+                        format!(
+                            "Match ID: {}\nTournament: {} ({})\nWinner: <@{}>\nForfeited by: <@{}>\nReason: the deadline passed while only the winner was ready.",
+                            match_id,
+                            tournament.name,
+                            tournament_id,
+                            winner.discord_id,
+                            loser.discord_id
+                        ),
+                        State::SUCCESS,
+                        Model::MARSHAL,
+                    )
+                    .await?;
+                Ok::<(), BotError>(())
+            }
+            .await;
+            if let Err(e) = announced {
+                error!("Error announcing the forfeit of match {}: {}", match_id, e);
+            }
+            conclude_match(http, database, guild_id, tournament_id, match_id).await?;
+        }
+        None => {
+            database.escalate_match(match_id).await?;
+            let ready = bracket
+                .match_players
+                .iter()
+                .filter(|player| player.ready)
+                .count();
+            let reason = match ready {
+                0 => "Neither player got ready.",
+                _ => "Both players got ready but no result was submitted.",
+            };
+            if let Some(config) = database.get_config(guild_id).await? {
+                system
+                    .get_log_channel()
+                    .await?
+                    .send_message(
+                        http,
+                        CreateMessage::default().content(format!(
+                            "<@&{}> match `{}` needs a marshal.",
+                            config.marshal_role_id, match_id
+                        )),
+                    )
+                    .await?;
+            }
+            system
+                .log(
+                    "Match deadline passed",
+                    format!(
+                        "Match ID: {}\nTournament: {} ({})\nPlayers: {}\nReason: {}\nPlease settle the match, for example with `/disqualify`.",
+                        match_id,
+                        tournament.name,
+                        tournament_id,
+                        bracket
+                            .match_players
+                            .iter()
+                            .map(|player| format!("<@{}>", player.discord_id))
+                            .collect::<Vec<String>>()
+                            .join(" vs "),
+                        reason
+                    ),
+                    State::WARNING,
+                    Model::MARSHAL,
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Reminds the players of the matches that are about to start at the time they agreed on.
+async fn send_reminders(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    let now = chrono::Utc::now().timestamp();
+    for (guild_id, schedule) in database.get_due_reminders(now + REMINDER_LEAD).await? {
+        if let Err(e) = send_reminder(http, database, &guild_id, &schedule).await {
+            error!(
+                "Error reminding the players of match {}: {}",
+                schedule.match_id, e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reminds the players of a single match. See [`send_reminders`].
+async fn send_reminder(
+    http: &Http,
+    database: &PgDatabase,
+    guild_id: &str,
+    schedule: &MatchSchedule,
+) -> Result<(), BotError> {
+    database.set_reminded(&schedule.match_id).await?;
+    let bracket = match database.get_match_by_id(&schedule.match_id).await? {
+        Some(bracket) => bracket,
+        None => return Ok(()),
+    };
+    let tournament = match database
+        .get_tournament(guild_id, bracket.tournament()?)
+        .await?
+    {
+        Some(tournament) => tournament,
+        None => return Ok(()),
+    };
+    let players = bracket
+        .match_players
+        .iter()
+        .map(|player| format!("<@{}>", player.discord_id))
+        .collect::<Vec<String>>()
+        .join(" ");
+    ChannelId::new(tournament.notification_channel_id.parse()?)
+        .send_message(
+            http,
+            CreateMessage::default().content(format!(
+                "{}\n\nReminder: your match `{}` starts <t:{time}:R> (<t:{time}:t>). Open `/menu` and get ready once you are both online!",
+                players,
+                schedule.match_id,
+                time = schedule.proposed_time
+            )),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Advances the tournaments whose current round is decided and can no longer be disputed.
+///
+/// Rounds wait a while after their last result before they advance, so nothing else picks them up
+/// once that time has passed.
+async fn advance_settled_rounds(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    for (guild_id, tournament_id) in database.get_auto_advancing_tournaments().await? {
+        if let Err(e) = auto_advance(http, database, &guild_id, tournament_id).await {
+            error!("Error advancing tournament {}: {}", tournament_id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Finishes the tournaments whose final result is decided and can no longer be disputed.
+///
+/// Like rounds, tournaments wait a while after their final result before they finish, so nothing
+/// else picks them up once that time has passed.
+async fn finish_settled_tournaments(http: &Http, database: &PgDatabase) -> Result<(), BotError> {
+    for (guild_id, tournament_id) in database.get_started_tournaments().await? {
+        let tournament = match database.get_tournament(&guild_id, tournament_id).await? {
+            Some(tournament) => tournament,
+            None => continue,
+        };
+        let brackets = database
+            .get_matches_by_tournament(tournament_id, Some(tournament.current_round))
+            .await?;
+        // The final result is always in the current round, which must be fully decided
+        if brackets.is_empty() || brackets.iter().any(|bracket| bracket.winner.is_none()) {
+            continue;
+        }
+        for bracket in brackets {
+            match finish_if_decided(http, database, &guild_id, &bracket.match_id).await {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => {
+                    error!("Error finishing tournament {}: {}", tournament_id, e);
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::models::{Match, MatchPlayer, PlayerType};
+
+    fn bracket(ready: &[bool]) -> Match {
+        let match_players = ready
+            .iter()
+            .enumerate()
+            .map(|(index, ready)| MatchPlayer {
+                match_id: "1.1.1".to_string(),
+                discord_id: index.to_string(),
+                player_type: PlayerType::Player,
+                ready: *ready,
+            })
+            .collect();
+        Match::new(1, 1, 1, match_players, "0-0")
+    }
+
+    #[test]
+    fn only_a_lone_ready_player_wins_by_forfeit() {
+        let winner = |ready: &[bool]| {
+            bracket(ready)
+                .forfeit_winner()
+                .map(|player| player.discord_id.clone())
+        };
+        assert_eq!(winner(&[true, false]), Some("0".to_string()));
+        assert_eq!(winner(&[false, true]), Some("1".to_string()));
+        assert_eq!(winner(&[false, false]), None);
+        assert_eq!(winner(&[true, true]), None);
+        assert_eq!(winner(&[true]), None);
+    }
+}
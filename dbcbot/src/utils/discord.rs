@@ -4,7 +4,7 @@ use futures::StreamExt;
 use poise::{
     serenity_prelude::{
         self as serenity, Channel, ChannelId, ChannelType, Colour, ComponentInteractionCollector,
-        ComponentInteractionDataKind::{ChannelSelect, RoleSelect},
+        ComponentInteractionDataKind::{ChannelSelect, RoleSelect, UserSelect},
         CreateActionRow, CreateEmbed, CreateSelectMenu, CreateSelectMenuKind,
         CreateSelectMenuOption, GuildChannel, Role, RoleId, User, UserId,
     },
@@ -122,6 +122,43 @@ where
     Err(anyhow!("No role selected"))
 }
 
+pub async fn select_user<S>(
+    ctx: &BotContext<'_>,
+    msg: &ReplyHandle<'_>,
+    title: S,
+    description: S,
+) -> Result<User, BotError>
+where
+    S: Into<String> + Send + 'static,
+{
+    let embed = CreateEmbed::default()
+        .title(title.into())
+        .description(description.into())
+        .color(Colour::GOLD);
+    let component = vec![CreateActionRow::SelectMenu(CreateSelectMenu::new(
+        "user",
+        CreateSelectMenuKind::User {
+            default_users: None,
+        },
+    ))];
+    let builder = CreateReply::default().embed(embed).components(component);
+    msg.edit(*ctx, builder).await?;
+    while let Some(mci) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(std::time::Duration::from_secs(120))
+        .filter(move |mci| mci.data.custom_id == "user")
+        .await
+    {
+        mci.defer(ctx.http()).await?;
+        if let UserSelect { values } = mci.data.kind {
+            let user = values[0].to_user(ctx.http()).await?;
+            return Ok(user);
+        }
+    }
+    Err(anyhow!("No user selected"))
+}
+
 pub async fn select_options<T: Selectable>(
     ctx: &BotContext<'_>,
     msg: &ReplyHandle<'_>,
@@ -6,7 +6,7 @@ use crate::{
         official_brawl_stars::Brawler,
         APIResult,
     },
-    database::models::{GuildConfig, Mode},
+    database::models::{GuildConfig, Mode, Standing},
     BotContext, BotError,
 };
 use anyhow::anyhow;
@@ -19,6 +19,28 @@ use poise::{
     CreateReply, ReplyHandle,
 };
 use tokio::time::Duration;
+
+/// Recalculates the standings of a tournament from its matches and saves them.
+///
+/// Returns the standings, ordered from the highest ranked player.
+pub async fn update_standings(
+    database: &PgDatabase,
+    tournament_id: i32,
+) -> Result<Vec<Standing>, BotError> {
+    let discord_ids: Vec<String> = database
+        .get_tournament_players(tournament_id)
+        .await?
+        .into_iter()
+        .map(|player| player.discord_id)
+        .collect();
+    let matches = database
+        .get_matches_by_tournament(tournament_id, None)
+        .await?;
+    let standings = Standing::calculate(tournament_id, &discord_ids, &matches);
+    database.set_standings(&standings).await?;
+    Ok(standings)
+}
+
 pub trait BotContextExt<'a> {
     async fn mode_selection(&self, msg: &ReplyHandle<'_>) -> Result<FullGameMode, BotError>;
     async fn map_selection(&self, msg: &ReplyHandle<'_>, mode: &Mode)
@@ -126,9 +148,14 @@ pub trait BotContextExt<'a> {
     /// # Arguments
     /// * `ctx` - The context of the command.
     /// * `msg` - The message to reply to.
+    /// * `available` - The brawlers to choose from, or `None` to choose from every brawler.
     /// # Returns
     /// Returns a `Result` containing a `String` representing the selected brawler.
-    async fn brawler_selection(&self, msg: &ReplyHandle<'_>) -> Result<Brawler, BotError>;
+    async fn brawler_selection(
+        &self,
+        msg: &ReplyHandle<'_>,
+        available: Option<&[Brawler]>,
+    ) -> Result<Brawler, BotError>;
 }
 
 impl<'a> BotContextExt<'a> for BotContext<'a> {
@@ -276,15 +303,27 @@ impl<'a> BotContextExt<'a> for BotContext<'a> {
         .await
     }
 
-    async fn brawler_selection(&self, msg: &ReplyHandle<'_>) -> Result<Brawler, BotError> {
+    async fn brawler_selection(
+        &self,
+        msg: &ReplyHandle<'_>,
+        available: Option<&[Brawler]>,
+    ) -> Result<Brawler, BotError> {
         const CAPACITY: usize = 25;
-        let brawlers = match self.data().apis.brawlify.get_brawlers().await? {
+        let mut brawlers = match self.data().apis.brawlify.get_brawlers().await? {
             APIResult::Ok(b) => b,
             APIResult::NotFound => return Err(anyhow!("Brawlers not found")),
             APIResult::Maintenance => {
                 return Err(anyhow!("Brawlify is currently undergoing maintenance"))
             }
         };
+        if let Some(available) = available {
+            brawlers
+                .list
+                .retain(|brawler| available.iter().any(|b| b.id == brawler.id));
+        }
+        if brawlers.list.is_empty() {
+            return Err(anyhow!("There are no brawlers to choose from"));
+        }
         let reply = {
             let embed = CreateEmbed::default().description("Select how you would like to sort the brawler list.\n 🅰️: Sort in alphabetical order.\n💎: Sort by rarity");
             let buttons = CreateActionRow::Buttons(vec![
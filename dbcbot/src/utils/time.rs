@@ -1,5 +1,8 @@
-use chrono::DateTime;
+use anyhow::anyhow;
+use chrono::{NaiveDateTime, NaiveTime, Timelike, Weekday};
 use serde::{Deserialize, Serialize};
+
+use crate::BotError;
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BattleDateTime {
     pub datetime: i64,
@@ -8,8 +11,141 @@ pub struct BattleDateTime {
 impl BattleDateTime {
     // Method to convert from custom string format
     pub fn from_str(s: &str) -> Result<Self, chrono::ParseError> {
-        let datetime = DateTime::parse_from_str(s, "%Y%m%dT%H%M%S%.3fZ")?;
+        // The trailing `Z` carries no offset chrono can read, so the time is parsed as UTC
+        let datetime = NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S%.3fZ")?.and_utc();
         let unix = datetime.timestamp();
         Ok(BattleDateTime { datetime: unix })
     }
 }
+
+/// Parses a time typed by a user, which must be after `now`.
+///
+/// Takes either a UTC date and time such as `2026-10-20 18:00` or a unix timestamp.
+pub fn parse_future_time(input: &str, now: i64) -> Result<i64, BotError> {
+    let input = input.trim();
+    let time = match input.parse::<i64>() {
+        Ok(timestamp) => timestamp,
+        Err(_) => NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .map_err(|_| {
+                anyhow!(
+                    "`{}` is not a valid time. Use the format YYYY-MM-DD HH:MM in UTC.",
+                    input
+                )
+            })?
+            .and_utc()
+            .timestamp(),
+    };
+    if time <= now {
+        return Err(anyhow!("The time must be in the future."));
+    }
+    Ok(time)
+}
+
+/// The names of the days of the week, counted from Monday.
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Parses a recurrence typed by a user, such as `every Saturday 18:00`.
+///
+/// Takes `every <weekday> HH:MM` or `every day HH:MM` in UTC. Returns the weekday counted from
+/// Monday, or None for every day, and the minute of the day.
+pub fn parse_recurrence(input: &str) -> Result<(Option<i32>, i32), BotError> {
+    let invalid = || {
+        anyhow!(
+            "`{}` is not a valid schedule. Use `every <weekday> HH:MM` or `every day HH:MM` in UTC.",
+            input.trim()
+        )
+    };
+    let input_lowercase = input.trim().to_lowercase();
+    let words: Vec<&str> = input_lowercase.split_whitespace().collect();
+    let (day, time) = match words.as_slice() {
+        ["every", day, time] | [day, time] => (*day, *time),
+        _ => return Err(invalid()),
+    };
+    let weekday = match day {
+        "day" | "daily" => None,
+        day => Some(
+            day.parse::<Weekday>()
+                .map_err(|_| invalid())?
+                .num_days_from_monday() as i32,
+        ),
+    };
+    let time = NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| invalid())?;
+    Ok((weekday, (time.hour() * 60 + time.minute()) as i32))
+}
+
+/// The first time after `after` that falls on a recurrence, as a unix timestamp.
+pub fn next_occurrence(weekday: Option<i32>, minute_of_day: i32, after: i64) -> i64 {
+    const DAY: i64 = 24 * 60 * 60;
+    let mut time = after - after.rem_euclid(DAY) + minute_of_day as i64 * 60;
+    loop {
+        // The unix epoch fell on a Thursday
+        let day_of_week = (time.div_euclid(DAY) + 3).rem_euclid(7) as i32;
+        let on_day = match weekday {
+            Some(weekday) => weekday == day_of_week,
+            None => true,
+        };
+        if time > after && on_day {
+            return time;
+        }
+        time += DAY;
+    }
+}
+
+/// Describes a recurrence, such as `every Saturday at 18:00 UTC`.
+pub fn describe_recurrence(weekday: Option<i32>, minute_of_day: i32) -> String {
+    let day = match weekday.and_then(|weekday| WEEKDAYS.get(weekday as usize)) {
+        Some(name) => name,
+        None => "day",
+    };
+    format!(
+        "every {} at {:02}:{:02} UTC",
+        day,
+        minute_of_day / 60,
+        minute_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_recurrence, next_occurrence, parse_future_time, parse_recurrence};
+
+    #[test]
+    fn parses_future_times() {
+        let now = 1_792_000_000;
+        assert_eq!(parse_future_time("2026-10-20 18:00", now).unwrap(), 1_792_519_200);
+        assert_eq!(parse_future_time(" 1792519200 ", now).unwrap(), 1_792_519_200);
+        assert!(parse_future_time("2020-01-01 00:00", now).is_err());
+        assert!(parse_future_time("tomorrow", now).is_err());
+    }
+
+    #[test]
+    fn recurrences_repeat_on_their_weekday() {
+        assert_eq!(parse_recurrence("every Saturday 18:00").unwrap(), (Some(5), 1080));
+        assert_eq!(parse_recurrence(" Every sat 09:30 ").unwrap(), (Some(5), 570));
+        assert_eq!(parse_recurrence("every day 00:05").unwrap(), (None, 5));
+        assert!(parse_recurrence("every Saturday").is_err());
+        assert!(parse_recurrence("every weekend 18:00").is_err());
+        assert!(parse_recurrence("every Saturday 25:00").is_err());
+
+        // Wednesday 2026-10-14 17:46:40 UTC
+        let now = 1_792_000_000;
+        // Saturday 2026-10-17 18:00
+        assert_eq!(next_occurrence(Some(5), 1080, now), 1_792_260_000);
+        // A week later when the last one was exactly then
+        assert_eq!(next_occurrence(Some(5), 1080, 1_792_260_000), 1_792_864_800);
+        // The same day, or the next day once the time has passed
+        assert_eq!(next_occurrence(None, 1080, now), 1_792_000_800);
+        assert_eq!(next_occurrence(None, 1020, now), 1_792_083_600);
+
+        assert_eq!(describe_recurrence(Some(5), 1080), "every Saturday at 18:00 UTC");
+        assert_eq!(describe_recurrence(None, 5), "every day at 00:05 UTC");
+    }
+}